@@ -1,22 +1,93 @@
+use super::token_refresh::TokenRefreshState;
+use crate::services::api_client::ApiClient;
 use log::{error, info};
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use tauri::State;
-use tokio::sync::Mutex;
+use tauri::{AppHandle, Emitter, State};
+use std::sync::Arc;
+use tokio::sync::RwLock;
 
 // 🔹 AuthState (modified)
+// Clones share the same token; reads take a shared lock so polling and user
+// actions don't contend, only login/logout take the write lock.
 #[derive(Debug, Default, Clone)]
 pub struct AuthState {
-    pub token: std::sync::Arc<Mutex<Option<String>>>,
+    pub token: std::sync::Arc<RwLock<Option<String>>>,
+    /// Role returned by the last login; cleared with the token
+    pub role: std::sync::Arc<RwLock<Option<String>>>,
+    /// Saved profile the session was switched to or saved as; cleared with
+    /// the token
+    pub profile: std::sync::Arc<RwLock<Option<String>>>,
+    /// Called when the server ends the session. Shared like the token, so
+    /// every `ApiClient` built on this state reports expiry.
+    session_expired_hook: SessionExpiredHook,
 }
 
-// 🔹 Request & Response Structures
-#[derive(Serialize)]
-struct AuthRequest {
-    username: String,
-    password: String,
+type SessionExpiredCallback = Box<dyn Fn() + Send + Sync>;
+
+#[derive(Default, Clone)]
+struct SessionExpiredHook(std::sync::Arc<std::sync::RwLock<Option<SessionExpiredCallback>>>);
+
+impl std::fmt::Debug for SessionExpiredHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let set = self.0.read().map(|hook| hook.is_some()).unwrap_or(false);
+        f.debug_tuple("SessionExpiredHook").field(&set).finish()
+    }
+}
+
+/// Whether `role` may perform `action`:
+/// - `approve_review`, `reject_review`: `team_lead` and `admin`
+/// - `create_team`, `manage_users` and any other action: `admin` only
+pub fn role_allows(role: &str, action: &str) -> bool {
+    match action {
+        "approve_review" | "reject_review" => matches!(role, "team_lead" | "admin"),
+        _ => role == "admin",
+    }
+}
+
+impl AuthState {
+    /// Run `hook` whenever the session ends because the server rejected the
+    /// token and refreshing it failed. Replaces any earlier hook.
+    pub fn on_session_expired(&self, hook: impl Fn() + Send + Sync + 'static) {
+        *self.session_expired_hook.0.write().unwrap_or_else(|e| e.into_inner()) = Some(Box::new(hook));
+    }
+
+    /// Clear the session and run the session-expired hook, unless another
+    /// client already ended the session.
+    pub async fn expire_session(&self) {
+        let had_token = self.token.write().await.take().is_some();
+        *self.role.write().await = None;
+        *self.profile.write().await = None;
+        if had_token {
+            if let Some(hook) = self.session_expired_hook.0.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+                hook();
+            }
+        }
+    }
+
+    pub async fn current_role(&self) -> Option<String> {
+        self.role.read().await.clone()
+    }
+
+    /// Whether the logged in user's role allows `action`. False when the
+    /// role isn't known.
+    pub async fn has_permission(&self, action: &str) -> bool {
+        self.current_role().await.is_some_and(|role| role_allows(&role, action))
+    }
+
+    /// Fail fast when the cached role rules out `action`. With no cached role
+    /// (e.g. a restored session) the server is left to decide.
+    pub async fn require_permission(&self, action: &str) -> Result<(), String> {
+        match self.current_role().await {
+            Some(role) if !role_allows(&role, action) => {
+                error!("🚫 Role '{}' may not {}", role, action);
+                Err(format!("Insufficient permissions: your role '{}' cannot {}", role, action.replace('_', " ")))
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
+// 🔹 Request & Response Structures
 #[derive(Serialize)]
 struct RegisterRequest {
     username: String,
@@ -30,12 +101,20 @@ struct AuthResponse {
     role: String,
 }
 
+/// Payload of the `auth_logged_in` event
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthLoggedIn {
+    pub role: String,
+}
+
 // 🔹 Login Function
 #[tauri::command]
 #[allow(dead_code)] // The code is being fasly flagged as dead by clippy
 pub async fn login(
+    app: AppHandle,
     state: State<'_, AuthState>,
     api_client: State<'_, crate::services::api_client::ApiClient>,
+    refresh_state: State<'_, Arc<TokenRefreshState>>,
     username: String,
     password: String,
 ) -> Result<(String, String), String> {
@@ -54,37 +133,76 @@ pub async fn login(
     let body: AuthResponse = serde_json::from_str(&response)
         .map_err(|e| format!("❌ JSON parsing error: {e}"))?;
 
-    // Update legacy AuthState
-    let mut token_guard = state.token.lock().await;
-    *token_guard = Some(body.token.clone());
-
-    // Also update ApiClient's auth_state
+    // AuthState and ApiClient share the same token
+    *state.token.write().await = Some(body.token.clone());
     api_client.set_token(body.token.clone()).await;
+    *state.role.write().await = Some(body.role.clone());
+    *state.profile.write().await = None;
 
     info!("✅ Login successful! Token and role stored.");
+    refresh_state.start(app.clone());
+    let _ = app.emit("auth_logged_in", AuthLoggedIn { role: body.role.clone() });
     Ok((body.token, body.role))
 }
 
+/// Roles that can be requested at registration.
+const ALLOWED_ROLES: [&str; 3] = ["user", "team_lead", "admin"];
+
+/// Role of the currently logged in user, if any.
+pub(crate) async fn current_user_role(api_client: &crate::services::api_client::ApiClient) -> Option<String> {
+    let text = api_client.get("/users/me").await.ok()?;
+    let value: serde_json::Value = serde_json::from_str(&text).ok()?;
+    value["data"]["role"].as_str().map(String::from)
+}
+
 // 🔹 Register Function
 #[tauri::command]
 #[allow(dead_code)]
 pub async fn register(
+    app: AppHandle,
     state: State<'_, AuthState>,
     api_client: State<'_, crate::services::api_client::ApiClient>,
+    refresh_state: State<'_, Arc<TokenRefreshState>>,
     username: String,
     password: String,
+    role: Option<String>,
 ) -> Result<String, String> {
+    let role = role.unwrap_or_else(|| "user".to_string());
+    if !ALLOWED_ROLES.contains(&role.as_str()) {
+        return Err(format!(
+            "Invalid role '{}'. Expected one of: {}",
+            role,
+            ALLOWED_ROLES.join(", ")
+        ));
+    }
+
+    // Only admins may provision team leads or other admins.
+    let caller_is_admin = if state.token.read().await.is_none() {
+        false
+    } else if state.current_role().await.is_some() {
+        state.has_permission("manage_users").await
+    } else {
+        current_user_role(&api_client).await.as_deref() == Some("admin")
+    };
+    if role != "user" && !caller_is_admin {
+        error!("🚫 Refusing to register '{}' as {} without admin rights", username, role);
+        return Err(format!("Only admins can register users with the '{}' role", role));
+    }
+
     // Prepare the request body
-    let request_body = serde_json::json!({
-        "username": username,
-        "password": password,
-        "role": "user",
-    });
+    let request_body = RegisterRequest {
+        username: username.clone(),
+        password: password.clone(),
+        role,
+    };
 
-    // Use the ApiClient for the registration request
-    let response = api_client
-        .post_no_auth("/auth/register", &request_body)
-        .await?;
+    // An admin's request carries their token so the server can verify them;
+    // the check above only saves a round trip.
+    let response = if caller_is_admin {
+        api_client.post("/auth/register", &request_body).await?
+    } else {
+        api_client.post_no_auth("/auth/register", &request_body).await?
+    };
 
     // Parse the response to check for success
     let response_json: serde_json::Value = serde_json::from_str(&response)
@@ -92,9 +210,14 @@ pub async fn register(
 
     info!("🔐 Registration response: {:?}", response_json);
     if response_json.get("success").and_then(|v| v.as_bool()).unwrap_or(false) {
+        if caller_is_admin {
+            // An admin provisioning an account keeps their own session.
+            info!("✅ Registered '{}' on behalf of an admin.", username);
+            return Ok("Registration successful!".to_string());
+        }
         info!("✅ Registration succeeded. Proceeding to login.");
         // Automatically login after registration
-        login(state, api_client, username, password)
+        login(app, state, api_client, refresh_state, username, password)
             .await
             .map(|_| "Registration and login successful!".to_string())
     } else {
@@ -105,3 +228,187 @@ pub async fn register(
         Err(maybe_msg.to_string())
     }
 }
+
+// 🔹 Logout Function
+#[tauri::command]
+pub async fn logout(
+    app: AppHandle,
+    api_client: State<'_, crate::services::api_client::ApiClient>,
+    refresh_state: State<'_, Arc<TokenRefreshState>>,
+) -> Result<(), String> {
+    end_session(&api_client, &refresh_state).await;
+    info!("👋 Logged out, token cleared.");
+    let _ = app.emit("auth_logged_out", ());
+    Ok(())
+}
+
+// Stop refreshing before clearing the token; `login` starts the task again
+async fn end_session(api_client: &ApiClient, refresh_state: &TokenRefreshState) {
+    refresh_state.stop();
+    api_client.clear_token().await;
+}
+
+// 🔹 Token Status
+#[derive(Debug, Serialize)]
+pub struct TokenStatus {
+    pub valid: bool,
+    pub expires_at: Option<String>,
+    pub seconds_remaining: Option<i64>,
+}
+
+/// Expiry (`exp`, seconds since the epoch) from a JWT's payload. The
+/// signature is not checked; this is only used to warn before expiry.
+fn jwt_expiry(token: &str) -> Option<i64> {
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+    let mut segments = token.split('.');
+    let (Some(_header), Some(payload), Some(_signature), None) =
+        (segments.next(), segments.next(), segments.next(), segments.next())
+    else {
+        return None;
+    };
+    let bytes = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+    let claims: serde_json::Value = serde_json::from_slice(&bytes).ok()?;
+    claims["exp"].as_i64()
+}
+
+pub(crate) fn token_status_at(token: Option<&str>, now: i64) -> TokenStatus {
+    let Some(token) = token else {
+        return TokenStatus { valid: false, expires_at: None, seconds_remaining: None };
+    };
+    match jwt_expiry(token) {
+        Some(exp) => TokenStatus {
+            valid: exp > now,
+            expires_at: chrono::DateTime::from_timestamp(exp, 0).map(|t| t.to_rfc3339()),
+            seconds_remaining: Some(exp - now),
+        },
+        // Opaque tokens carry no expiry we can read
+        None => TokenStatus { valid: true, expires_at: None, seconds_remaining: None },
+    }
+}
+
+/// Report whether the stored token is still valid and when it expires, so the
+/// UI can prompt for re-login before requests start failing.
+#[tauri::command]
+pub async fn token_status(state: State<'_, AuthState>) -> Result<TokenStatus, String> {
+    let token = state.token.read().await;
+    Ok(token_status_at(token.as_deref(), chrono::Utc::now().timestamp()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::config::AppConfig;
+    use crate::test_support::mock_token;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    const NOW: i64 = 1_700_000_000;
+
+    #[test]
+    fn expired_token_is_invalid() {
+        let status = token_status_at(Some(&mock_token(NOW - 10)), NOW);
+        assert!(!status.valid);
+        assert_eq!(status.seconds_remaining, Some(-10));
+    }
+
+    #[test]
+    fn unexpired_token_reports_its_expiry() {
+        let status = token_status_at(Some(&mock_token(NOW + 600)), NOW);
+        assert!(status.valid);
+        assert_eq!(status.seconds_remaining, Some(600));
+        assert_eq!(status.expires_at.as_deref(), Some("2023-11-14T22:23:20+00:00"));
+    }
+
+    #[test]
+    fn opaque_token_is_valid_without_expiry() {
+        let status = token_status_at(Some("not-a-jwt"), NOW);
+        assert!(status.valid);
+        assert_eq!(status.expires_at, None);
+        assert_eq!(status.seconds_remaining, None);
+
+        assert!(!token_status_at(None, NOW).valid);
+    }
+
+    #[tokio::test]
+    async fn session_expiry_is_reported_once_across_clones() {
+        let state = AuthState::default();
+        let polling_state = state.clone();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        state.on_session_expired(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        *state.token.write().await = Some("token".to_string());
+
+        polling_state.expire_session().await;
+        state.expire_session().await;
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(state.token.read().await.is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn many_readers_and_a_writer_do_not_deadlock() {
+        let state = AuthState::default();
+        *state.token.write().await = Some("old".to_string());
+
+        let readers: Vec<_> = (0..32)
+            .map(|_| {
+                let state = state.clone();
+                tokio::spawn(async move {
+                    for _ in 0..100 {
+                        let token = state.token.read().await.clone();
+                        assert!(matches!(token.as_deref(), Some("old" | "new")));
+                        tokio::task::yield_now().await;
+                    }
+                })
+            })
+            .collect();
+        let writer = {
+            let state = state.clone();
+            tokio::spawn(async move {
+                for _ in 0..100 {
+                    *state.token.write().await = Some("new".to_string());
+                    tokio::task::yield_now().await;
+                }
+            })
+        };
+
+        let all = futures::future::join_all(readers.into_iter().chain(std::iter::once(writer)));
+        let results = tokio::time::timeout(std::time::Duration::from_secs(10), all)
+            .await
+            .expect("token access deadlocked");
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(state.token.read().await.as_deref(), Some("new"));
+    }
+
+    #[tokio::test]
+    async fn logout_stops_the_token_refresh_task() {
+        let state = AuthState::default();
+        let config = AppConfig::builder().api_base_url("http://127.0.0.1:9").build().unwrap();
+        let api_client = ApiClient::new(config, state.clone()).unwrap();
+        api_client.set_token("token".to_string()).await;
+        let refresh_state = TokenRefreshState::default();
+        let task = Arc::new(());
+        let held = task.clone();
+        refresh_state.start_with(|| {
+            tauri::async_runtime::spawn(async move {
+                let _held = held;
+                std::future::pending::<()>().await;
+            })
+        });
+
+        end_session(&api_client, &refresh_state).await;
+
+        // The task drops its clone once it has been aborted
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while Arc::strong_count(&task) > 1 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("token refresh task still running after logout");
+        assert!(!refresh_state.stop());
+        assert!(state.token.read().await.is_none());
+    }
+}
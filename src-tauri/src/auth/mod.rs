@@ -1 +1,2 @@
 pub mod login;
+pub mod token_refresh;
@@ -0,0 +1,163 @@
+// src-tauri/src/auth/token_refresh.rs
+
+use crate::auth::login::{token_status_at, AuthState};
+use crate::services::api_client::ApiClient;
+use log::{error, info, warn};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// How often the refresh task checks the token's remaining lifetime
+const TOKEN_REFRESH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Refresh once fewer than this many seconds remain on the token
+const TOKEN_REFRESH_THRESHOLD_SECONDS: i64 = 5 * 60;
+
+/// Handle of the background token refresh task, if it is running
+#[derive(Debug, Default)]
+pub struct TokenRefreshState {
+    task_handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl TokenRefreshState {
+    /// Start the refresh task unless it is already running
+    pub fn start(&self, app: AppHandle) {
+        self.start_with(|| spawn_token_refresh(app));
+    }
+
+    // Run the task `spawn` starts, unless one is already running
+    pub(crate) fn start_with(&self, spawn: impl FnOnce() -> JoinHandle<()>) {
+        let mut task_handle = self.task_handle.lock().unwrap_or_else(|e| e.into_inner());
+        if task_handle.is_none() {
+            info!("Starting token refresh task");
+            *task_handle = Some(spawn());
+        }
+    }
+
+    /// Stop the refresh task. Returns whether one was running.
+    pub fn stop(&self) -> bool {
+        let handle = self.task_handle.lock().unwrap_or_else(|e| e.into_inner()).take();
+        match handle {
+            Some(handle) => {
+                handle.abort();
+                info!("Token refresh task stopped");
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// Whether a token with `seconds_remaining` left should be refreshed now.
+// Tokens without a readable expiry are never refreshed proactively.
+fn needs_refresh(seconds_remaining: Option<i64>) -> bool {
+    seconds_remaining.is_some_and(|remaining| remaining <= TOKEN_REFRESH_THRESHOLD_SECONDS)
+}
+
+// One pass of the refresh task: refresh the token if it expires soon.
+// Returns `None` when no refresh was due, including when logged out.
+async fn refresh_if_due(auth_state: &AuthState, api_client: &ApiClient, now: i64) -> Option<Result<String, String>> {
+    let token = auth_state.token.read().await.clone();
+    let status = token_status_at(token.as_deref(), now);
+    if status.valid && needs_refresh(status.seconds_remaining) {
+        Some(api_client.refresh_token().await)
+    } else {
+        if !status.valid && status.seconds_remaining.is_some() {
+            warn!("Stored token has expired; waiting for a new login");
+        }
+        None
+    }
+}
+
+fn spawn_token_refresh(app: AppHandle) -> JoinHandle<()> {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let now = chrono::Utc::now().timestamp();
+            match refresh_if_due(&app.state::<AuthState>(), &app.state::<ApiClient>(), now).await {
+                Some(Ok(_)) => {
+                    info!("🔄 Token refreshed before expiry");
+                    let _ = app.emit("token_refreshed", ());
+                }
+                Some(Err(e)) => {
+                    error!("Token refresh failed: {}", e);
+                    let _ = app.emit("token_refresh_failed", e);
+                }
+                None => {}
+            }
+            tokio::time::sleep(TOKEN_REFRESH_CHECK_INTERVAL).await;
+        }
+    })
+}
+
+/// Start refreshing the token in the background shortly before it expires.
+#[tauri::command]
+pub async fn start_token_refresh(app: AppHandle, refresh_state: State<'_, Arc<TokenRefreshState>>) -> Result<(), String> {
+    refresh_state.start(app);
+    Ok(())
+}
+
+/// Stop the background token refresh.
+#[tauri::command]
+pub async fn stop_token_refresh(refresh_state: State<'_, Arc<TokenRefreshState>>) -> Result<(), String> {
+    refresh_state.stop();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::config::AppConfig;
+    use crate::test_support::{mock_server, mock_token};
+
+    async fn client_with_token(base_url: &str, token: String) -> (AuthState, ApiClient) {
+        let auth_state = AuthState::default();
+        let config = AppConfig::builder().api_base_url(base_url).build().unwrap();
+        let client = ApiClient::new(config, auth_state.clone()).unwrap();
+        client.set_token(token).await;
+        (auth_state, client)
+    }
+
+    #[test]
+    fn short_lived_token_needs_refresh() {
+        let now = 1_700_000_000;
+        let status = token_status_at(Some(&mock_token(now + 60)), now);
+        assert!(status.valid);
+        assert!(needs_refresh(status.seconds_remaining));
+    }
+
+    #[test]
+    fn long_lived_token_does_not_need_refresh() {
+        let now = 1_700_000_000;
+        let status = token_status_at(Some(&mock_token(now + 3600)), now);
+        assert!(!needs_refresh(status.seconds_remaining));
+    }
+
+    #[test]
+    fn opaque_token_is_never_refreshed_proactively() {
+        let status = token_status_at(Some("opaque-token"), 0);
+        assert!(!needs_refresh(status.seconds_remaining));
+    }
+
+    #[tokio::test]
+    async fn short_lived_token_is_refreshed_before_expiry() {
+        let (base_url, requests) = mock_server(|_, _, _| (200, r#"{"token":"renewed"}"#.to_string())).await;
+        let now = chrono::Utc::now().timestamp();
+        let (auth_state, client) = client_with_token(&base_url, mock_token(now + 60)).await;
+
+        let result = refresh_if_due(&auth_state, &client, now).await;
+        assert_eq!(result, Some(Ok("renewed".to_string())));
+        assert_eq!(*requests.lock().unwrap(), ["POST /auth/refresh"]);
+        assert_eq!(auth_state.token.read().await.as_deref(), Some("renewed"));
+    }
+
+    #[tokio::test]
+    async fn long_lived_token_is_left_alone() {
+        let (base_url, requests) = mock_server(|_, _, _| (200, r#"{"token":"renewed"}"#.to_string())).await;
+        let now = chrono::Utc::now().timestamp();
+        let (auth_state, client) = client_with_token(&base_url, mock_token(now + 3600)).await;
+
+        assert_eq!(refresh_if_due(&auth_state, &client, now).await, None);
+        assert!(requests.lock().unwrap().is_empty());
+    }
+}
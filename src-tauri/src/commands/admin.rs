@@ -1,5 +1,5 @@
 use crate::services::api_client::ApiClient;
-use log::{debug, error, info};
+use log::{debug, info};
 use tauri::State;
 
 #[tauri::command]
@@ -9,12 +9,11 @@ pub async fn get_user_role(
 ) -> Result<String, String> {
     let url = format!("/users/{}/role", username);
     debug!("Sending role request for username: {}", username);
-    let response_text = api_client.get(&url).await?;
     // ✅ Extract just the role from JSON response
-    let role: String = match serde_json::from_str::<serde_json::Value>(&response_text) {
-        Ok(json) => json["data"].as_str().unwrap_or("unknown").to_string(),
-        Err(_) => return Err("Failed to parse role from response".to_string()),
-    };
+    let role = api_client
+        .get_json::<Option<String>>(&url)
+        .await?
+        .unwrap_or_else(|| "unknown".to_string());
     info!("Successfully retrieved user role for username: {}", username);
     debug!("Role: {}", role);
     Ok(role)
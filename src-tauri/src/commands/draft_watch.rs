@@ -0,0 +1,184 @@
+// src-tauri/src/commands/draft_watch.rs
+
+use crate::commands::reviews::get_review_local_path;
+use log::{error, info, warn};
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{Emitter, State, Window};
+
+/// Writes closer together than this produce a single `draft_changed` event
+const DRAFT_WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Payload of the `draft_changed` event
+#[derive(Debug, Clone, Serialize)]
+pub struct DraftChanged {
+    pub product_id: i32,
+    pub content: String,
+}
+
+// Last draft content the app itself saved, by product id. Watchers skip
+// these so the app's own saves aren't echoed back as `draft_changed`.
+static OWN_SAVES: Mutex<BTreeMap<i32, String>> = Mutex::new(BTreeMap::new());
+
+/// Note that the app is saving `content` as the product's draft. Call before
+/// writing so the watcher can tell the save apart from an external edit.
+pub(crate) fn record_own_save(product_id: i32, content: &str) {
+    OWN_SAVES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(product_id, content.to_string());
+}
+
+fn is_own_save(product_id: i32, content: &str) -> bool {
+    OWN_SAVES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&product_id)
+        .is_some_and(|saved| saved == content)
+}
+
+/// Active draft watchers by product id; dropping a watcher stops it.
+#[derive(Default)]
+pub struct DraftWatchers {
+    watchers: Mutex<HashMap<i32, Debouncer<RecommendedWatcher>>>,
+}
+
+/// Watch a product's local `draft.html` and emit `draft_changed` with the new
+/// content whenever it is changed outside the app. Events are debounced and
+/// only sent when the content actually differs from the last version seen and
+/// from what the app itself last saved. Watching a
+/// product that is already watched replaces the old watcher.
+#[tauri::command(rename_all = "snake_case")]
+pub fn watch_review_draft(
+    window: Window,
+    watchers: State<'_, Arc<DraftWatchers>>,
+    product_id: i32,
+) -> Result<(), String> {
+    let debouncer = start_draft_watcher(product_id, move |change| {
+        if let Err(e) = window.emit("draft_changed", change) {
+            warn!("Failed to emit draft_changed: {}", e);
+        }
+    })?;
+    watchers
+        .watchers
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(product_id, debouncer);
+    Ok(())
+}
+
+// Watch a product's draft, calling `on_change` with each new version
+fn start_draft_watcher(
+    product_id: i32,
+    on_change: impl Fn(DraftChanged) + Send + 'static,
+) -> Result<Debouncer<RecommendedWatcher>, String> {
+    let draft_path = get_review_local_path(product_id, None)?;
+    // The directory is watched because drafts are saved by renaming a
+    // temporary file over `draft.html`, which a file watch would lose
+    let draft_dir = draft_path
+        .parent()
+        .ok_or_else(|| format!("Invalid draft path: {}", draft_path.display()))?
+        .to_path_buf();
+    let last_content = Mutex::new(fs::read_to_string(&draft_path).ok());
+
+    let watched_path = draft_path.clone();
+    let mut debouncer = new_debouncer(DRAFT_WATCH_DEBOUNCE, move |result: DebounceEventResult| {
+        let events = match result {
+            Ok(events) => events,
+            Err(e) => {
+                error!("Draft watcher error for product {}: {}", product_id, e);
+                return;
+            }
+        };
+        if !events.iter().any(|event| event.path == watched_path) {
+            return;
+        }
+        // Removed or mid-rename; the next event will carry the new file
+        let Ok(content) = fs::read_to_string(&watched_path) else {
+            return;
+        };
+        let mut last = last_content.lock().unwrap_or_else(|e| e.into_inner());
+        if last.as_deref() == Some(content.as_str()) {
+            return;
+        }
+        *last = Some(content.clone());
+        if is_own_save(product_id, &content) {
+            return;
+        }
+        info!("Draft for product {} changed on disk", product_id);
+        on_change(DraftChanged { product_id, content });
+    })
+    .map_err(|e| format!("Failed to start draft watcher: {}", e))?;
+    debouncer
+        .watcher()
+        .watch(&draft_dir, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("Failed to watch {}: {}", draft_dir.display(), e))?;
+
+    info!("Watching draft for product {} at {}", product_id, draft_path.display());
+    Ok(debouncer)
+}
+
+/// Stop watching a product's draft. Returns false if it wasn't watched.
+#[tauri::command(rename_all = "snake_case")]
+pub fn stop_watch_review_draft(watchers: State<'_, Arc<DraftWatchers>>, product_id: i32) -> Result<bool, String> {
+    let removed = watchers
+        .watchers
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&product_id)
+        .is_some();
+    if removed {
+        info!("Stopped watching draft for product {}", product_id);
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::reviews::save_review_draft;
+    use crate::test_support::ReviewsDirOverride;
+    use std::sync::mpsc;
+
+    #[tokio::test]
+    async fn external_draft_edits_produce_one_debounced_event_per_change() {
+        let _reviews = ReviewsDirOverride::temp().await;
+        let draft_path = get_review_local_path(21, None).unwrap();
+        fs::write(&draft_path, "<p>v1</p>").unwrap();
+        let (sender, changes) = mpsc::channel();
+        let _watcher = start_draft_watcher(21, move |change| {
+            let _ = sender.send(change);
+        })
+        .unwrap();
+
+        // Two quick edits fall in one debounce window
+        fs::write(&draft_path, "<p>v2</p>").unwrap();
+        fs::write(&draft_path, "<p>v3</p>").unwrap();
+        let change = changes.recv_timeout(Duration::from_secs(5)).expect("draft_changed");
+        assert_eq!((change.product_id, change.content.as_str()), (21, "<p>v3</p>"));
+
+        // Rewriting the same content is not a change
+        fs::write(&draft_path, "<p>v3</p>").unwrap();
+        assert!(changes.recv_timeout(DRAFT_WATCH_DEBOUNCE * 3).is_err());
+    }
+
+    #[tokio::test]
+    async fn the_apps_own_saves_are_not_reported() {
+        let _reviews = ReviewsDirOverride::temp().await;
+        fs::write(get_review_local_path(22, None).unwrap(), "<p>v1</p>").unwrap();
+        let (sender, changes) = mpsc::channel();
+        let _watcher = start_draft_watcher(22, move |change| {
+            let _ = sender.send(change);
+        })
+        .unwrap();
+
+        save_review_draft(22, "<p>autosaved</p>".to_string()).unwrap();
+
+        assert!(changes.recv_timeout(DRAFT_WATCH_DEBOUNCE * 3).is_err());
+    }
+}
@@ -0,0 +1,33 @@
+use crate::services::api_client::{ApiClient, HealthStatus};
+use crate::services::request_metrics::EndpointMetrics;
+use log::{info, warn};
+use tauri::State;
+
+/// Check whether the backend is reachable. Works while logged out, so the UI
+/// can tell a down server apart from an expired session.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn check_server_health(api_client: State<'_, ApiClient>) -> Result<HealthStatus, String> {
+    info!("Checking server health...");
+    let health = api_client.check_api_health().await;
+    if health.reachable {
+        info!("Server reachable in {} ms", health.latency_ms);
+    } else {
+        warn!("Server unreachable after {} ms", health.latency_ms);
+    }
+    Ok(health)
+}
+
+/// Lightweight reachability probe for the login screen, so an unreachable
+/// server is reported as such rather than as a failed login. Same probe as
+/// `check_server_health`, under the name the login screen calls.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn ping_server(api_client: State<'_, ApiClient>) -> Result<HealthStatus, String> {
+    check_server_health(api_client).await
+}
+
+/// Per-endpoint latency (min/avg/p95/max) over recent API requests, so
+/// operators can see whether the backend is slow.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_api_metrics(api_client: State<'_, ApiClient>) -> Result<Vec<EndpointMetrics>, String> {
+    Ok(api_client.api_metrics())
+}
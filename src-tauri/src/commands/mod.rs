@@ -1,9 +1,16 @@
 pub mod admin;
 pub mod contracts;
+pub mod draft_watch;
+pub mod health;
 pub mod notifications;
+pub mod operations;
+pub mod production_workflow;
 pub mod products;
+pub mod profiles;
 pub mod reviews;
+pub mod session;
 pub mod settings;
+pub mod shutdown;
 pub mod taskorders;
 pub mod team;
 pub mod users;
@@ -1,8 +1,12 @@
 // src-tauri/src/commands/notifications.rs
 
+use crate::services::notification_snooze::{self, filter_snoozed};
+use crate::services::shown_notifications;
 use crate::services::{api_client::ApiClient, config::AppConfig};
 use crate::auth::login::AuthState;
-use log::{debug, error, info};
+use crate::utils::parse_server_timestamp;
+use chrono::{DateTime, Utc};
+use log::{debug, error, info, warn};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::time::Duration;
@@ -14,12 +18,6 @@ use tokio::task::JoinHandle;
 // ======================
 // === Data Structures ==
 // ======================
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct NotificationCountResponse {
-    pub total: i64,
-    pub unread: i64,
-}
-
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct NotificationTarget {
     pub id: i32,
@@ -50,110 +48,6 @@ pub struct NotificationWithTargets {
     pub dismissed: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct NotificationResponse {
-    pub success: bool,
-    pub status_code: u16,
-    pub message: String,
-    pub timestamp: String,
-    pub data: Vec<NotificationWithTargets>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CountResponse {
-    pub success: bool,
-    pub status_code: u16,
-    pub message: String,
-    pub timestamp: String,
-    pub data: NotificationCountResponse,
-}
-
-// ===============================
-// === Internal Helper Methods ===
-// ===============================
-//
-// These do the actual HTTP requests but take `&AuthState` directly
-// instead of Tauri `State<'_, AuthState>`. This lets you call them
-// from the polling loop, where you only have `AuthState` without `State`.
-
-/// Internal helper to fetch the current notification count.
-async fn get_notification_count_internal(auth_state: &crate::auth::login::AuthState) -> Result<String, String> {
-    let client = reqwest::Client::new();
-    let url = "http://localhost:3000/notifications/count".to_string();
-
-    // NEW: call get_auth_header_internal(...) instead of get_auth_header(...)
-    let auth_header = crate::utils::get_auth_header_internal(auth_state).await?;
-
-    info!("Fetching notification count...");
-
-    let response = client
-        .get(&url)
-        .header("Authorization", auth_header)
-        .send()
-        .await
-        .map_err(|e| {
-            error!("Request failed: {e}");
-            format!("Request failed: {e}")
-        })?;
-
-    let status = response.status();
-    let response_text = response.text().await.unwrap_or_default();
-
-    if status.is_success() {
-        info!("Successfully retrieved notification count");
-        debug!("Response: {}", response_text);
-        Ok(response_text)
-    } else {
-        error!(
-            "Failed to retrieve notification count. Status: {:?}, Response: {}",
-            status, response_text
-        );
-        Err(format!(
-            "Failed to retrieve notification count: {:?}",
-            response_text
-        ))
-    }
-}
-
-/// Internal helper to fetch all notifications for the current user.
-async fn get_notifications_internal(auth_state: &crate::auth::login::AuthState) -> Result<String, String> {
-    let client = reqwest::Client::new();
-    let url = "http://localhost:3000/notifications?include_dismissed=false".to_string();
-
-    // NEW: call get_auth_header_internal(...) instead of get_auth_header(...)
-    let auth_header = crate::utils::get_auth_header_internal(auth_state).await?;
-
-    info!("Fetching notifications...");
-
-    let response = client
-        .get(&url)
-        .header("Authorization", auth_header)
-        .send()
-        .await
-        .map_err(|e| {
-            error!("Request failed: {e}");
-            format!("Request failed: {e}")
-        })?;
-
-    let status = response.status();
-    let response_text = response.text().await.unwrap_or_default();
-
-    if status.is_success() {
-        info!("Successfully retrieved notifications");
-        debug!("Response: {}", response_text);
-        Ok(response_text)
-    } else {
-        error!(
-            "Failed to retrieve notifications. Status: {:?}, Response: {}",
-            status, response_text
-        );
-        Err(format!(
-            "Failed to retrieve notifications: {:?}",
-            response_text
-        ))
-    }
-}
-
 // ===============================
 // === Tauri Commands (Public) ===
 // ===============================
@@ -172,6 +66,56 @@ pub async fn get_notifications(api_client: State<'_, ApiClient>) -> Result<Strin
     api_client.get("/notifications?include_dismissed=false").await
 }
 
+// Whether a notification's `expires_at` has passed. Timestamps without an
+// offset are taken as UTC; unparseable ones never expire.
+fn is_expired(notification: &NotificationItem, now: DateTime<Utc>) -> bool {
+    let Some(expires_at) = notification.expires_at.as_deref() else {
+        return false;
+    };
+    parse_server_timestamp(expires_at).is_some_and(|expires_at| expires_at <= now)
+}
+
+// Drop expired and locally snoozed notifications
+fn visible_notifications(notifications: Vec<NotificationWithTargets>) -> Vec<NotificationWithTargets> {
+    let now = Utc::now();
+    let snoozed = notification_snooze::active_snoozes(now);
+    notifications
+        .into_iter()
+        .filter(|n| !is_expired(&n.notification, now) && !snoozed.contains(&n.notification.id))
+        .collect()
+}
+
+// Parse a raw `/notifications` response body into visible notifications
+fn parse_notifications(body: &str) -> Result<Vec<NotificationWithTargets>, String> {
+    let response: serde_json::Value = serde_json::from_str(body).map_err(|e| format!("Failed to parse notifications: {}", e))?;
+    let notifications = serde_json::from_value(response["data"].clone())
+        .map_err(|e| format!("Failed to parse notifications: {}", e))?;
+    Ok(visible_notifications(notifications))
+}
+
+// Emit both the raw `notifications` payload and the parsed
+// `notifications_refreshed` one
+fn emit_notifications(window: &Window, body: String) {
+    match parse_notifications(&body) {
+        Ok(notifications) => {
+            let _ = window.emit("notifications_refreshed", notifications);
+        }
+        Err(e) => warn!("{}", e),
+    }
+    let _ = window.emit("notifications", filter_snoozed(body));
+}
+
+/// Fetch the current user's notifications as typed items, with expired and
+/// snoozed ones removed.
+#[tauri::command]
+pub async fn get_notifications_typed(api_client: State<'_, ApiClient>) -> Result<Vec<NotificationWithTargets>, String> {
+    info!("Fetching notifications...");
+    let notifications: Vec<NotificationWithTargets> = api_client
+        .get_json("/notifications?include_dismissed=false")
+        .await?;
+    Ok(visible_notifications(notifications))
+}
+
 /// Tauri command that dismisses a specific notification.
 #[tauri::command]
 pub async fn dismiss_notification(
@@ -183,6 +127,128 @@ pub async fn dismiss_notification(
     Ok(())
 }
 
+/// Hide a notification locally until `until` (RFC 3339) without dismissing it
+/// on the server. It reappears in the `notifications` event once the snooze
+/// passes.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn snooze_notification(notification_id: i32, until: String) -> Result<(), String> {
+    let until = chrono::DateTime::parse_from_rfc3339(&until)
+        .map_err(|e| format!("Invalid snooze time '{}': {}", until, e))?
+        .with_timezone(&chrono::Utc);
+    if until <= chrono::Utc::now() {
+        return Err("Snooze time must be in the future".to_string());
+    }
+    info!("Snoozing notification {notification_id} until {until}");
+    notification_snooze::snooze(notification_id, until)
+}
+
+/// Tauri command that dismisses every dismissible notification of one type,
+/// leaving the rest in place. Returns how many were dismissed.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn dismiss_notifications_by_type(
+    api_client: State<'_, ApiClient>,
+    type_field: String,
+) -> Result<usize, String> {
+    dismiss_by_type(&api_client, &type_field).await
+}
+
+async fn dismiss_by_type(api_client: &ApiClient, type_field: &str) -> Result<usize, String> {
+    info!("Dismissing '{type_field}' notifications...");
+    let notifications: Vec<NotificationWithTargets> = api_client
+        .get_json("/notifications?include_dismissed=false")
+        .await?;
+
+    let matching: Vec<i32> = notifications
+        .iter()
+        .filter(|n| !n.dismissed && n.notification.type_field == type_field)
+        .filter(|n| {
+            if !n.notification.dismissible {
+                debug!("Skipping non-dismissible notification {}", n.notification.id);
+            }
+            n.notification.dismissible
+        })
+        .map(|n| n.notification.id)
+        .collect();
+
+    // ApiClient already caps how many of these run at once
+    let results = futures::future::join_all(matching.iter().map(|id| {
+        async move {
+            api_client
+                .post(&format!("/notifications/{}/dismiss", id), &())
+                .await
+                .map_err(|e| error!("Failed to dismiss notification {id}: {e}"))
+        }
+    }))
+    .await;
+
+    let dismissed = results.iter().filter(|r| r.is_ok()).count();
+    info!("Dismissed {dismissed} of {} '{type_field}' notification(s)", matching.len());
+    Ok(dismissed)
+}
+
+// Read an integer ID out of a notification's `action_data`
+fn action_id(action_data: &Option<serde_json::Value>, key: &str) -> Result<i32, String> {
+    action_data
+        .as_ref()
+        .and_then(|data| data.get(key))
+        .and_then(|v| v.as_i64())
+        .and_then(|v| i32::try_from(v).ok())
+        .ok_or_else(|| format!("Notification action data is missing '{}'", key))
+}
+
+// An action `resolve_notification_action` knows how to perform
+#[derive(Debug, PartialEq)]
+enum ResolvableAction {
+    ApproveReview { review_id: i32 },
+    ApproveTeamJoin { request_id: i32, team_id: i32 },
+}
+
+// Look up a notification and the action it asks for
+async fn find_notification_action(api_client: &ApiClient, notification_id: i32) -> Result<ResolvableAction, String> {
+    let notifications: Vec<NotificationWithTargets> = api_client
+        .get_json("/notifications?include_dismissed=true")
+        .await?;
+    let notification = notifications
+        .into_iter()
+        .map(|n| n.notification)
+        .find(|n| n.id == notification_id)
+        .ok_or_else(|| format!("Notification {} not found", notification_id))?;
+
+    match notification.action_type.as_deref() {
+        Some("review_approval") => Ok(ResolvableAction::ApproveReview {
+            review_id: action_id(&notification.action_data, "review_id")?,
+        }),
+        Some("team_join") => Ok(ResolvableAction::ApproveTeamJoin {
+            request_id: action_id(&notification.action_data, "request_id")?,
+            team_id: action_id(&notification.action_data, "team_id")?,
+        }),
+        Some(other) => Err(format!("Unsupported notification action type '{}'", other)),
+        None => Err(format!("Notification {} has no action", notification_id)),
+    }
+}
+
+/// Tauri command that performs the action attached to a notification, e.g.
+/// approving the review or team join request it refers to.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn resolve_notification_action(
+    auth_state: State<'_, AuthState>,
+    config: State<'_, Arc<AppConfig>>,
+    api_client: State<'_, ApiClient>,
+    notification_id: i32,
+) -> Result<serde_json::Value, String> {
+    info!("Resolving action for notification {notification_id}...");
+    match find_notification_action(&api_client, notification_id).await? {
+        ResolvableAction::ApproveReview { review_id } => {
+            crate::commands::reviews::approve_review(auth_state, api_client, config, review_id).await
+        }
+        ResolvableAction::ApproveTeamJoin { request_id, team_id } => {
+            let response =
+                crate::commands::userteams::approve_team_request(api_client, request_id, team_id).await?;
+            Ok(serde_json::from_str(&response).unwrap_or(serde_json::Value::String(response)))
+        }
+    }
+}
+
 /// Tauri command that dismisses all notifications.
 #[tauri::command]
 pub async fn dismiss_all_notifications(api_client: State<'_, ApiClient>) -> Result<String, String> {
@@ -190,27 +256,69 @@ pub async fn dismiss_all_notifications(api_client: State<'_, ApiClient>) -> Resu
     api_client.post("/notifications/dismiss-all", &()).await
 }
 
+/// Notification action types the frontend can navigate for:
+///
+/// - `open_review`: `action_data.review_id` is the review to open
+/// - `open_product`: `action_data.product_id` is the product to open
+/// - `open_workflow_instance`: `action_data.instance_id` is the workflow
+///   instance to open, `action_data.product_id` its product
+/// - `open_production_issue`: `action_data.issue_id` is the production issue
+///   to open, `action_data.product_id` its product
+///
+/// Other action types are shown as plain notifications.
+pub const SUPPORTED_NOTIFICATION_ACTIONS: [&str; 4] =
+    ["open_review", "open_product", "open_workflow_instance", "open_production_issue"];
+
+/// Payload of the `notification_action` window event
+#[derive(Debug, Serialize, Clone)]
+pub struct NotificationAction {
+    pub notification_id: Option<i32>,
+    pub action_type: String,
+    pub action_data: serde_json::Value,
+}
+
 /// Tauri command that shows a system notification (using the Tauri plugin).
-#[tauri::command]
+///
+/// For a supported `action_type`, the action is attached to the notification
+/// where the platform reports clicks. Desktop platforms don't, so the action
+/// is also emitted as a `notification_action` window event for the frontend
+/// to offer in-app (e.g. an "Open" button on a toast).
+///
+/// A `notification_id` is toasted at most once, even across restarts.
+#[tauri::command(rename_all = "snake_case")]
 pub async fn show_system_notification(
     window: Window,
     title: String,
     body: String,
+    notification_id: Option<i32>,
+    action_type: Option<String>,
+    action_data: Option<serde_json::Value>,
 ) -> Result<(), String> {
     info!("Showing system notification: {title} - {body}");
+    let action = action_type
+        .filter(|action_type| SUPPORTED_NOTIFICATION_ACTIONS.contains(&action_type.as_str()))
+        .map(|action_type| NotificationAction {
+            notification_id,
+            action_type,
+            action_data: action_data.unwrap_or(serde_json::Value::Null),
+        });
 
     match window.notification().permission_state() {
         Ok(PermissionState::Granted) => {
-            window
-                .notification()
-                .builder()
-                .title(title)
-                .body(body)
-                .show()
-                .map_err(|e| {
-                    error!("Failed to show notification: {e}");
-                    format!("Failed to show notification: {e}")
-                })?;
+            if notification_id.is_some_and(|id| !shown_notifications::mark_shown(id)) {
+                debug!("Notification {:?} was already shown, skipping", notification_id);
+                return Ok(());
+            }
+            let mut builder = window.notification().builder().title(title).body(body);
+            if let Some(action) = &action {
+                builder = builder
+                    .action_type_id(action.action_type.clone())
+                    .extra("action_data", &action.action_data);
+            }
+            builder.show().map_err(|e| {
+                error!("Failed to show notification: {e}");
+                format!("Failed to show notification: {e}")
+            })?;
         }
         Ok(PermissionState::Prompt) => {
             if let Err(e) = window.notification().request_permission() {
@@ -228,13 +336,28 @@ pub async fn show_system_notification(
         }
     }
 
+    if let Some(action) = action {
+        debug!("Emitting notification action: {}", action.action_type);
+        let _ = window.emit("notification_action", action);
+    }
     Ok(())
 }
 
+/// Forget which notifications have been toasted, so each can show once more.
+#[tauri::command]
+pub async fn reset_shown_notifications() -> Result<(), String> {
+    info!("Resetting shown notifications");
+    shown_notifications::reset()
+}
+
 // =============================
 // === Polling-Related State ===
 // =============================
 
+/// Polls run every 30 seconds, so a slow backend should fail fast rather than
+/// hold the request for the full configured timeout.
+const NOTIFICATION_POLL_TIMEOUT: Duration = Duration::from_secs(5);
+
 // Polling state now holds ApiClient
 #[derive(Debug, Default)]
 pub struct PollingState {
@@ -245,40 +368,113 @@ pub struct PollingState {
 #[tauri::command]
 pub async fn start_notification_polling(
     window: Window,
-    auth_state: State<'_, Arc<Mutex<AuthState>>>,
-    config: State<'_, Arc<AppConfig>>,
+    api_client: State<'_, ApiClient>,
     polling_state: State<'_, Arc<PollingState>>,
 ) -> Result<(), String> {
     info!("Starting notification polling...");
-    let polling_client = ApiClient::new((**config).clone(), auth_state.inner().clone());
-    let window = window.clone();
     let mut task_handle = polling_state.task_handle.lock().await;
     if task_handle.is_some() {
         return Ok(());
     }
-    let handle = tokio::spawn(async move {
-        loop {
-            match polling_client.get("/notifications/count").await {
-                Ok(count) => {
-                    let _ = window.emit("notification_count", count);
-                }
-                Err(e) => {
-                    error!("Polling error: {}", e);
+    *task_handle = Some(spawn_notification_polling(window, &api_client));
+    Ok(())
+}
+
+/// Restart polling against the client's current server, if it is running.
+pub(crate) async fn restart_notification_polling(window: Window, api_client: &ApiClient, polling_state: &PollingState) {
+    let mut task_handle = polling_state.task_handle.lock().await;
+    if let Some(handle) = task_handle.take() {
+        info!("Restarting notification polling...");
+        handle.abort();
+        *task_handle = Some(spawn_notification_polling(window, api_client));
+    }
+}
+
+/// Normal interval between polls
+const NOTIFICATION_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Longest wait between polls while the backend is unreachable
+const NOTIFICATION_POLL_MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+/// Delay before the next poll: the normal interval, doubled for each
+/// consecutive failure up to `NOTIFICATION_POLL_MAX_BACKOFF`.
+fn next_poll_delay(consecutive_failures: u32) -> Duration {
+    NOTIFICATION_POLL_INTERVAL
+        .saturating_mul(2u32.saturating_pow(consecutive_failures))
+        .min(NOTIFICATION_POLL_MAX_BACKOFF)
+}
+
+/// What a poll tells the frontend
+#[derive(Debug, Clone, PartialEq)]
+enum PollEvent {
+    Count(String),
+    Notifications(String),
+    ConnectionLost(String),
+    ConnectionRestored,
+}
+
+// Polling gets its own client so its requests don't queue behind user actions
+fn spawn_notification_polling(window: Window, api_client: &ApiClient) -> JoinHandle<()> {
+    let polling_client = match api_client.background_client() {
+        Ok(client) => client,
+        Err(e) => {
+            error!("Notification polling disabled: {}", e);
+            return tokio::spawn(async {});
+        }
+    };
+    let emit = move |event: PollEvent| match event {
+        PollEvent::Count(count) => {
+            let _ = window.emit("notification_count", count);
+        }
+        PollEvent::Notifications(body) => emit_notifications(&window, body),
+        PollEvent::ConnectionLost(e) => {
+            let _ = window.emit("notification_connection_lost", e);
+        }
+        PollEvent::ConnectionRestored => {
+            let _ = window.emit("notification_connection_restored", ());
+        }
+    };
+    tokio::spawn(poll_notifications(polling_client, emit, tokio::time::sleep))
+}
+
+// Poll until aborted, waiting `next_poll_delay` between polls
+async fn poll_notifications<F: std::future::Future<Output = ()>>(
+    polling_client: ApiClient,
+    emit: impl Fn(PollEvent),
+    mut wait: impl FnMut(Duration) -> F,
+) {
+    let mut consecutive_failures: u32 = 0;
+    loop {
+        let (count, notifications) = tokio::join!(
+            polling_client.get_with_timeout("/notifications/count", NOTIFICATION_POLL_TIMEOUT),
+            polling_client.get_with_timeout("/notifications?include_dismissed=false", NOTIFICATION_POLL_TIMEOUT),
+        );
+        if let Ok(count) = &count {
+            emit(PollEvent::Count(count.clone()));
+        }
+        if let Ok(notifications) = &notifications {
+            emit(PollEvent::Notifications(notifications.clone()));
+        }
+        match count.and(notifications) {
+            Ok(_) => {
+                if consecutive_failures > 0 {
+                    info!("Notification polling reconnected after {consecutive_failures} failed attempt(s)");
+                    emit(PollEvent::ConnectionRestored);
                 }
+                consecutive_failures = 0;
             }
-            match polling_client.get("/notifications?include_dismissed=false").await {
-                Ok(notifications) => {
-                    let _ = window.emit("notifications", notifications);
-                }
-                Err(e) => {
+            Err(e) => {
+                if consecutive_failures == 0 {
                     error!("Polling error: {}", e);
+                    emit(PollEvent::ConnectionLost(e));
+                } else {
+                    debug!("Polling still failing: {}", e);
                 }
+                consecutive_failures = consecutive_failures.saturating_add(1);
             }
-            tokio::time::sleep(Duration::from_secs(30)).await;
         }
-    });
-    *task_handle = Some(handle);
-    Ok(())
+        wait(next_poll_delay(consecutive_failures)).await;
+    }
 }
 
 /// Stop notification polling
@@ -310,7 +506,7 @@ pub async fn manual_refresh_notifications(
     }
     match api_client.get("/notifications?include_dismissed=false").await {
         Ok(notifications) => {
-            let _ = window.emit("notifications", notifications);
+            emit_notifications(&window, notifications);
         }
         Err(e) => {
             error!("Manual refresh error: {}", e);
@@ -318,3 +514,124 @@ pub async fn manual_refresh_notifications(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{mock_server, test_client};
+
+    #[test]
+    fn poll_delay_widens_on_failures_then_resets() {
+        let delays: Vec<u64> = [0, 1, 2, 3, 4, 10, 0].into_iter().map(|f| next_poll_delay(f).as_secs()).collect();
+        assert_eq!(delays, [30, 60, 120, 240, 300, 300, 30]);
+    }
+
+    #[test]
+    fn poll_delay_does_not_overflow() {
+        assert_eq!(next_poll_delay(u32::MAX), NOTIFICATION_POLL_MAX_BACKOFF);
+    }
+
+    #[tokio::test]
+    async fn polling_backs_off_while_failing_and_resets_on_reconnect() {
+        // Both requests of the first three polls fail
+        let served = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let (base_url, _) = mock_server(move |_, path, _| {
+            if served.fetch_add(1, std::sync::atomic::Ordering::SeqCst) < 6 {
+                (500, r#"{"message":"down"}"#.to_string())
+            } else if path.starts_with("/notifications/count") {
+                (200, "3".to_string())
+            } else {
+                (200, "[]".to_string())
+            }
+        })
+        .await;
+        let client = test_client(&base_url).await;
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = events.clone();
+        let (delays_sender, mut delays) = tokio::sync::mpsc::unbounded_channel();
+
+        let polling = tokio::spawn(poll_notifications(
+            client,
+            move |event| recorded.lock().unwrap().push(event),
+            move |delay| {
+                let _ = delays_sender.send(delay);
+                tokio::task::yield_now()
+            },
+        ));
+        let mut waited = Vec::new();
+        while waited.len() < 5 {
+            let delay = tokio::time::timeout(Duration::from_secs(10), delays.recv()).await.unwrap().unwrap();
+            waited.push(delay.as_secs());
+        }
+        polling.abort();
+
+        assert_eq!(waited, [60, 120, 240, 30, 30]);
+        let events = events.lock().unwrap();
+        let connection: Vec<&PollEvent> =
+            events.iter().filter(|e| matches!(e, PollEvent::ConnectionLost(_) | PollEvent::ConnectionRestored)).collect();
+        assert!(matches!(connection[..], [PollEvent::ConnectionLost(_), PollEvent::ConnectionRestored]));
+        assert!(events.contains(&PollEvent::Count("3".to_string())));
+    }
+
+    fn notification(id: i32, type_field: &str, action_type: Option<&str>, action_data: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({
+            "notification": {
+                "id": id,
+                "title": format!("Notification {}", id),
+                "body": null,
+                "type": type_field,
+                "action_type": action_type,
+                "action_data": action_data,
+                "global": false,
+                "dismissible": true,
+                "created_at": "2026-01-01T00:00:00Z",
+                "expires_at": null
+            },
+            "targets": [],
+            "dismissed": false
+        })
+    }
+
+    #[tokio::test]
+    async fn only_matching_dismissible_notifications_are_dismissed() {
+        let mut locked = notification(2, "review", None, serde_json::Value::Null);
+        locked["notification"]["dismissible"] = false.into();
+        let notifications = serde_json::json!({ "data": [
+            notification(1, "review", None, serde_json::Value::Null),
+            locked,
+            notification(3, "team", None, serde_json::Value::Null),
+            notification(4, "review", None, serde_json::Value::Null),
+        ]})
+        .to_string();
+        let (base_url, requests) = mock_server(move |method, _, _| match method {
+            "GET" => (200, notifications.clone()),
+            _ => (200, "{}".to_string()),
+        })
+        .await;
+        let api_client = test_client(&base_url).await;
+
+        assert_eq!(dismiss_by_type(&api_client, "review").await, Ok(2));
+        let mut dismissed: Vec<String> =
+            requests.lock().unwrap().iter().filter(|r| r.starts_with("POST")).cloned().collect();
+        dismissed.sort();
+        assert_eq!(dismissed, ["POST /notifications/1/dismiss", "POST /notifications/4/dismiss"]);
+    }
+
+    #[tokio::test]
+    async fn recognized_action_type_resolves_to_its_action() {
+        let notifications = serde_json::json!({ "data": [
+            notification(1, "team", Some("team_join"), serde_json::json!({ "request_id": 5, "team_id": 9 })),
+            notification(2, "info", Some("do_something_else"), serde_json::Value::Null),
+        ]})
+        .to_string();
+        let (base_url, _) = mock_server(move |_, _, _| (200, notifications.clone())).await;
+        let api_client = test_client(&base_url).await;
+
+        assert_eq!(
+            find_notification_action(&api_client, 1).await,
+            Ok(ResolvableAction::ApproveTeamJoin { request_id: 5, team_id: 9 })
+        );
+        let unknown = find_notification_action(&api_client, 2).await.unwrap_err();
+        assert_eq!(unknown, "Unsupported notification action type 'do_something_else'");
+    }
+}
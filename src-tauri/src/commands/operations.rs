@@ -0,0 +1,108 @@
+// src-tauri/src/commands/operations.rs
+
+use crate::services::api_client::ApiClient;
+use log::{info, warn};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+use tokio_util::sync::CancellationToken;
+
+/// Progress of a cancellable operation, emitted as `operation_progress`
+#[derive(Debug, Clone, Serialize)]
+pub struct OperationProgress {
+    pub operation_id: String,
+    pub completed: usize,
+    pub total: usize,
+}
+
+/// Cancellation tokens for long-running commands, keyed by an operation id
+/// chosen by the frontend.
+#[derive(Default)]
+pub struct OperationRegistry {
+    // Each token is tagged with the generation of the registration that
+    // created it, so a guard only unregisters its own operation
+    tokens: Mutex<HashMap<String, (u64, CancellationToken)>>,
+    next_generation: AtomicU64,
+}
+
+/// Keeps an operation registered until the command finishes
+pub struct OperationGuard<'a> {
+    registry: &'a OperationRegistry,
+    operation_id: String,
+    generation: u64,
+    pub token: CancellationToken,
+}
+
+impl Drop for OperationGuard<'_> {
+    fn drop(&mut self) {
+        let mut tokens = self.registry.tokens.lock().unwrap_or_else(|e| e.into_inner());
+        // A later operation may have taken over the id; leave it registered
+        if tokens.get(&self.operation_id).is_some_and(|(generation, _)| *generation == self.generation) {
+            tokens.remove(&self.operation_id);
+        }
+    }
+}
+
+impl OperationRegistry {
+    /// Register an operation; a second operation with the same id replaces
+    /// the first, which can then no longer be cancelled.
+    pub fn register(&self, operation_id: &str) -> OperationGuard<'_> {
+        let token = CancellationToken::new();
+        let generation = self.next_generation.fetch_add(1, Ordering::Relaxed);
+        let mut tokens = self.tokens.lock().unwrap_or_else(|e| e.into_inner());
+        if tokens.insert(operation_id.to_string(), (generation, token.clone())).is_some() {
+            warn!("Operation id {} was already in use", operation_id);
+        }
+        OperationGuard { registry: self, operation_id: operation_id.to_string(), generation, token }
+    }
+
+    pub fn cancel(&self, operation_id: &str) -> bool {
+        match self.tokens.lock().unwrap_or_else(|e| e.into_inner()).get(operation_id) {
+            Some((_, token)) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Ask a running operation to stop. Returns false if no operation with that
+/// id is running.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn cancel_operation(
+    operations: State<'_, Arc<OperationRegistry>>,
+    operation_id: String,
+) -> Result<bool, String> {
+    info!("Cancelling operation {}", operation_id);
+    Ok(operations.cancel(&operation_id))
+}
+
+/// Cancel an in-flight API request started with a caller-chosen request id.
+/// Returns false if the request already finished or was never started.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn cancel_request(api_client: State<'_, ApiClient>, request_id: String) -> Result<bool, String> {
+    info!("Cancelling request {}", request_id);
+    Ok(api_client.cancel_request(&request_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finished_operation_does_not_unregister_a_newer_one_with_the_same_id() {
+        let registry = OperationRegistry::default();
+        let first = registry.register("export");
+        let second = registry.register("export");
+
+        drop(first);
+        assert!(registry.cancel("export"));
+        assert!(second.token.is_cancelled());
+
+        drop(second);
+        assert!(!registry.cancel("export"));
+    }
+}
@@ -1,9 +1,12 @@
 use crate::services::api_client::ApiClient;
+use crate::utils::{parse_server_date, parse_server_timestamp};
+use reqwest::{Method, StatusCode};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tauri::{command, State};
-use chrono::{NaiveDate, NaiveDateTime};
-use std::collections::HashMap;
+use chrono::NaiveDateTime;
+use std::collections::{HashMap, HashSet};
+use crate::models::production::{BottleneckItem, DeadlineItem, ProductionDashboardData};
 
 // Production workflow data structures
 #[derive(Debug, Serialize, Deserialize)]
@@ -88,7 +91,8 @@ pub struct NewProductWorkflowInstance {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+// Default supports `..Default::default()` in partial updates
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct UpdateProductWorkflowInstance {
     pub current_step_id: Option<i32>,
     pub status: Option<String>,
@@ -100,116 +104,6 @@ pub struct UpdateProductWorkflowInstance {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ProductionDashboardData {
-    pub total_active_products: i64,
-    pub products_by_status: Vec<StatusCount>,
-    pub products_by_priority: Vec<PriorityCount>,
-    pub throughput_metrics: ThroughputMetrics,
-    pub capacity_utilization: CapacityUtilization,
-    pub sla_performance: SlaPerformance,
-    pub quality_metrics: QualityMetrics,
-    pub bottlenecks: Vec<BottleneckItem>,
-    pub upcoming_deadlines: Vec<DeadlineItem>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct StatusCount {
-    pub status: String,
-    pub count: i64,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct PriorityCount {
-    pub priority: String,
-    pub count: i64,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ThroughputMetrics {
-    pub products_completed_today: i64,
-    pub products_completed_week: i64,
-    pub products_completed_month: i64,
-    pub average_cycle_time_hours: f64,
-    pub throughput_trend: Vec<ThroughputDataPoint>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ThroughputDataPoint {
-    pub date: String,
-    pub completed_count: i64,
-    pub average_cycle_time: f64,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CapacityUtilization {
-    pub total_capacity: f64,
-    pub utilized_capacity: f64,
-    pub utilization_percentage: f64,
-    pub by_team: Vec<TeamCapacityData>,
-    pub by_user: Vec<UserCapacityData>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct TeamCapacityData {
-    pub team_id: i32,
-    pub team_name: String,
-    pub capacity: f64,
-    pub utilization: f64,
-    pub utilization_percentage: f64,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct UserCapacityData {
-    pub user_id: i32,
-    pub username: String,
-    pub capacity: f64,
-    pub utilization: f64,
-    pub utilization_percentage: f64,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct SlaPerformance {
-    pub on_time_percentage: f64,
-    pub average_delay_hours: f64,
-    pub sla_breaches_today: i64,
-    pub sla_breaches_week: i64,
-    pub at_risk_count: i64,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct QualityMetrics {
-    pub average_quality_score: f64,
-    pub quality_trend: Vec<QualityDataPoint>,
-    pub defect_rate: f64,
-    pub rework_rate: f64,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct QualityDataPoint {
-    pub date: String,
-    pub average_score: f64,
-    pub total_inspections: i64,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct BottleneckItem {
-    pub workflow_step_name: String,
-    pub products_waiting: i64,
-    pub average_wait_time_hours: f64,
-    pub severity: String,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct DeadlineItem {
-    pub product_id: i32,
-    pub product_name: String,
-    pub due_date: String,
-    pub hours_until_due: f64,
-    pub current_status: String,
-    pub priority: String,
-}
-
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ProductionIssue {
     pub id: i32,
@@ -258,7 +152,7 @@ pub struct UpdateProductionIssue {
 // PRODUCTION WORKFLOW COMMANDS
 // ========================================
 
-#[command]
+#[command(rename_all = "snake_case")]
 pub async fn get_production_workflows(
     api_client: State<'_, ApiClient>,
 ) -> Result<Vec<ProductionWorkflow>, String> {
@@ -276,75 +170,142 @@ pub async fn get_production_workflows(
     Ok(workflows)
 }
 
-#[command]
+#[command(rename_all = "snake_case")]
 pub async fn create_production_workflow(
     api_client: State<'_, ApiClient>,
     workflow: NewProductionWorkflow,
 ) -> Result<ProductionWorkflow, String> {
-    let response = api_client
-        .post("/production/workflows", &workflow)
+    let created_workflow: ProductionWorkflow = api_client
+        .post_json("/production/workflows", &workflow)
         .await
         .map_err(|e| format!("Failed to create workflow: {}", e))?;
 
-    let created_workflow: ProductionWorkflow = serde_json::from_value(response["data"].clone())
-        .map_err(|e| format!("Failed to parse created workflow: {}", e))?;
-
     Ok(created_workflow)
 }
 
-#[command]
+#[command(rename_all = "snake_case")]
 pub async fn get_production_workflow_by_id(
     api_client: State<'_, ApiClient>,
     id: i32,
 ) -> Result<Option<ProductionWorkflow>, String> {
-    let response = api_client
-        .get(&format!("/production/workflows/{}", id))
+    let workflow: Option<ProductionWorkflow> = api_client
+        .get_json(&format!("/production/workflows/{}", id))
         .await
         .map_err(|e| format!("Failed to fetch workflow: {}", e))?;
 
-    let workflow: Option<ProductionWorkflow> = serde_json::from_value(response["data"].clone())
-        .map_err(|e| format!("Failed to parse workflow: {}", e))?;
-
     Ok(workflow)
 }
 
-#[command]
+#[command(rename_all = "snake_case")]
 pub async fn get_workflow_steps(
     api_client: State<'_, ApiClient>,
     workflow_id: i32,
 ) -> Result<Vec<WorkflowStep>, String> {
-    let response = api_client
-        .get(&format!("/production/workflows/{}/steps", workflow_id))
+    let steps: Vec<WorkflowStep> = api_client
+        .get_json(&format!("/production/workflows/{}/steps", workflow_id))
         .await
         .map_err(|e| format!("Failed to fetch workflow steps: {}", e))?;
 
-    let steps: Vec<WorkflowStep> = serde_json::from_value(response["data"].clone())
-        .map_err(|e| format!("Failed to parse workflow steps: {}", e))?;
-
     Ok(steps)
 }
 
-#[command]
+#[command(rename_all = "snake_case")]
 pub async fn create_workflow_step(
     api_client: State<'_, ApiClient>,
     step: NewWorkflowStep,
 ) -> Result<WorkflowStep, String> {
-    let response = api_client
-        .post(&format!("/production/workflows/{}/steps", step.workflow_id), &step)
+    let created_step: WorkflowStep = api_client
+        .post_json(&format!("/production/workflows/{}/steps", step.workflow_id), &step)
         .await
         .map_err(|e| format!("Failed to create workflow step: {}", e))?;
 
-    let created_step: WorkflowStep = serde_json::from_value(response["data"].clone())
-        .map_err(|e| format!("Failed to parse created workflow step: {}", e))?;
-
     Ok(created_step)
 }
 
+/// Check that `ordered_step_ids` is exactly the set of `existing_step_ids`,
+/// with no duplicates, missing or unknown steps.
+fn validate_step_order(existing_step_ids: &[i32], ordered_step_ids: &[i32]) -> Result<(), String> {
+    let mut seen = HashSet::new();
+    let duplicates: Vec<i32> = ordered_step_ids
+        .iter()
+        .filter(|id| !seen.insert(**id))
+        .copied()
+        .collect();
+    if !duplicates.is_empty() {
+        return Err(format!("Duplicate step IDs in new order: {:?}", duplicates));
+    }
+
+    let existing: HashSet<i32> = existing_step_ids.iter().copied().collect();
+    let missing: Vec<i32> = existing_step_ids
+        .iter()
+        .filter(|id| !seen.contains(id))
+        .copied()
+        .collect();
+    let unknown: Vec<i32> = ordered_step_ids
+        .iter()
+        .filter(|id| !existing.contains(id))
+        .copied()
+        .collect();
+    if !missing.is_empty() || !unknown.is_empty() {
+        return Err(format!(
+            "Step IDs must match the workflow's steps exactly (missing: {:?}, unknown: {:?})",
+            missing, unknown
+        ));
+    }
+    Ok(())
+}
+
+#[command(rename_all = "snake_case")]
+pub async fn reorder_workflow_steps(
+    api_client: State<'_, ApiClient>,
+    workflow_id: i32,
+    ordered_step_ids: Vec<i32>,
+) -> Result<Vec<WorkflowStep>, String> {
+    let steps_endpoint = format!("/production/workflows/{}/steps", workflow_id);
+    let existing: Vec<WorkflowStep> = api_client
+        .get_json(&steps_endpoint)
+        .await
+        .map_err(|e| format!("Failed to fetch workflow steps: {}", e))?;
+    let existing_ids: Vec<i32> = existing.iter().map(|step| step.id).collect();
+    validate_step_order(&existing_ids, &ordered_step_ids)?;
+
+    let bulk = api_client
+        .execute(
+            Method::PUT,
+            &format!("{}/reorder", steps_endpoint),
+            Some(&serde_json::json!({ "step_ids": ordered_step_ids })),
+        )
+        .await;
+    match bulk {
+        Ok(_) => {}
+        Err(e) if e.status() == Some(StatusCode::NOT_FOUND) => {
+            log::info!("Bulk reorder endpoint not found, updating steps individually");
+            for (index, step_id) in ordered_step_ids.iter().enumerate() {
+                api_client
+                    .patch(
+                        &format!("{}/{}", steps_endpoint, step_id),
+                        &serde_json::json!({ "step_order": index as i32 + 1 }),
+                    )
+                    .await
+                    .map_err(|e| format!("Failed to update order of step {}: {}", step_id, e))?;
+            }
+        }
+        Err(e) => return Err(format!("Failed to reorder workflow steps: {}", e)),
+    }
+
+    let mut steps: Vec<WorkflowStep> = api_client
+        .get_json(&steps_endpoint)
+        .await
+        .map_err(|e| format!("Failed to fetch workflow steps: {}", e))?;
+    steps.sort_by_key(|step| step.step_order);
+    Ok(steps)
+}
+
 // ========================================
 // PRODUCT WORKFLOW INSTANCE COMMANDS
 // ========================================
 
-#[command]
+#[command(rename_all = "snake_case")]
 pub async fn get_product_workflow_instances(
     api_client: State<'_, ApiClient>,
     product_id: Option<i32>,
@@ -373,55 +334,116 @@ pub async fn get_product_workflow_instances(
             .join("&")
     };
 
-    let response = api_client
-        .get(&format!("/production/instances{}", query_string))
+    let instances: Vec<ProductWorkflowInstance> = api_client
+        .get_json(&format!("/production/instances{}", query_string))
         .await
         .map_err(|e| format!("Failed to fetch workflow instances: {}", e))?;
 
-    let instances: Vec<ProductWorkflowInstance> = serde_json::from_value(response["data"].clone())
-        .map_err(|e| format!("Failed to parse workflow instances: {}", e))?;
-
     Ok(instances)
 }
 
-#[command]
+#[command(rename_all = "snake_case")]
 pub async fn create_product_workflow_instance(
     api_client: State<'_, ApiClient>,
     instance: NewProductWorkflowInstance,
 ) -> Result<ProductWorkflowInstance, String> {
-    let response = api_client
-        .post("/production/instances", &instance)
+    let created_instance: ProductWorkflowInstance = api_client
+        .post_json("/production/instances", &instance)
         .await
         .map_err(|e| format!("Failed to create workflow instance: {}", e))?;
 
-    let created_instance: ProductWorkflowInstance = serde_json::from_value(response["data"].clone())
-        .map_err(|e| format!("Failed to parse created workflow instance: {}", e))?;
-
     Ok(created_instance)
 }
 
-#[command]
+#[command(rename_all = "snake_case")]
 pub async fn update_product_workflow_instance(
     api_client: State<'_, ApiClient>,
     id: i32,
     updates: UpdateProductWorkflowInstance,
 ) -> Result<ProductWorkflowInstance, String> {
-    let response = api_client
-        .put(&format!("/production/instances/{}", id), &updates)
+    let updated_instance: ProductWorkflowInstance = api_client
+        .put_json(&format!("/production/instances/{}", id), &updates)
         .await
         .map_err(|e| format!("Failed to update workflow instance: {}", e))?;
 
-    let updated_instance: ProductWorkflowInstance = serde_json::from_value(response["data"].clone())
-        .map_err(|e| format!("Failed to parse updated workflow instance: {}", e))?;
-
     Ok(updated_instance)
 }
 
+/// Send an in-app notification to a single user
+async fn notify_user(
+    api_client: &ApiClient,
+    user_id: i32,
+    title: &str,
+    body: &str,
+    action_type: &str,
+    action_data: Value,
+) -> Result<String, String> {
+    let notification = serde_json::json!({
+        "title": title,
+        "body": body,
+        "type": "info",
+        "action_type": action_type,
+        "action_data": action_data,
+        "targets": [{ "scope": "user", "target_id": user_id }],
+    });
+    api_client.post("/notifications", &notification).await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkflowAssignmentResult {
+    pub instance: ProductWorkflowInstance,
+    /// Set when the assignment succeeded but the assignee could not be notified
+    pub warning: Option<String>,
+}
+
+#[command(rename_all = "snake_case")]
+pub async fn assign_workflow_instance(
+    api_client: State<'_, ApiClient>,
+    instance_id: i32,
+    user_id: i32,
+    note: Option<String>,
+) -> Result<WorkflowAssignmentResult, String> {
+    assign_instance(&api_client, instance_id, user_id, note).await
+}
+
+async fn assign_instance(
+    api_client: &ApiClient,
+    instance_id: i32,
+    user_id: i32,
+    note: Option<String>,
+) -> Result<WorkflowAssignmentResult, String> {
+    // Only send the assignee so other instance fields are left untouched.
+    let instance: ProductWorkflowInstance = api_client
+        .put_json(
+            &format!("/production/instances/{}", instance_id),
+            &serde_json::json!({ "assigned_user_id": user_id }),
+        )
+        .await
+        .map_err(|e| format!("Failed to assign workflow instance: {}", e))?;
+
+    let body = match &note {
+        Some(note) => format!("Workflow for product {} was assigned to you: {}", instance.product_id, note),
+        None => format!("Workflow for product {} was assigned to you.", instance.product_id),
+    };
+    let action_data = serde_json::json!({ "instance_id": instance_id, "product_id": instance.product_id });
+
+    // The assignment stands even if the notification can't be delivered.
+    let warning = match notify_user(api_client, user_id, "New workflow assigned", &body, "open_workflow_instance", action_data).await {
+        Ok(_) => None,
+        Err(e) => {
+            log::warn!("Assigned instance {} but failed to notify user {}: {}", instance_id, user_id, e);
+            Some(format!("Assignment saved, but the user could not be notified: {}", e))
+        }
+    };
+
+    Ok(WorkflowAssignmentResult { instance, warning })
+}
+
 // ========================================
 // PRODUCTION DASHBOARD COMMANDS
 // ========================================
 
-#[command]
+#[command(rename_all = "snake_case")]
 pub async fn get_production_dashboard(
     api_client: State<'_, ApiClient>,
     team_id: Option<i32>,
@@ -432,31 +454,307 @@ pub async fn get_production_dashboard(
         String::new()
     };
 
-    let response = api_client
-        .get(&format!("/production/dashboard{}", query_string))
+    api_client
+        .get_json::<ProductionDashboardData>(&format!("/production/dashboard{}", query_string))
+        .await
+        .map_err(|e| format!("Failed to fetch dashboard data: {}", e))
+}
+
+/// Active workflow instances due within `within_days`, soonest first. Overdue
+/// items come first with negative `hours_until_due`. An instance's
+/// `estimated_completion` is its due date, falling back to the product's.
+#[command(rename_all = "snake_case")]
+pub async fn get_products_due_soon(
+    api_client: State<'_, ApiClient>,
+    within_days: i64,
+    team_id: Option<i32>,
+) -> Result<Vec<DeadlineItem>, String> {
+    if within_days < 0 {
+        return Err("within_days must not be negative".to_string());
+    }
+    let (instances, products) = tokio::join!(
+        fetch_active_instances(&api_client, team_id),
+        api_client.get_json::<Vec<Value>>("/products"),
+    );
+    let instances = instances?;
+    let products: HashMap<i64, Value> = products
+        .map_err(|e| format!("Failed to fetch products: {}", e))?
+        .into_iter()
+        .filter_map(|p| Some((p["id"].as_i64()?, p)))
+        .collect();
+    Ok(deadline_items(instances, &products, chrono::Utc::now().naive_utc(), within_days))
+}
+
+// Instances due by `now + within_days`, soonest (most overdue) first
+fn deadline_items(
+    instances: Vec<ProductWorkflowInstance>,
+    products: &HashMap<i64, Value>,
+    now: NaiveDateTime,
+    within_days: i64,
+) -> Vec<DeadlineItem> {
+    let horizon = now + chrono::Duration::days(within_days);
+    let mut items: Vec<DeadlineItem> = instances
+        .into_iter()
+        .filter_map(|instance| {
+            let product = products.get(&(instance.product_id as i64));
+            let due_date = instance
+                .estimated_completion
+                .clone()
+                .or_else(|| product?["due_date"].as_str().map(String::from))?;
+            let due = parse_due_date(&due_date)?;
+            if due > horizon {
+                return None;
+            }
+            let product_name = product
+                .and_then(|p| p["site_id"].as_str().or(p["item_id"].as_str()))
+                .map(String::from)
+                .unwrap_or_else(|| format!("Product {}", instance.product_id));
+            Some(DeadlineItem {
+                product_id: instance.product_id,
+                product_name,
+                due_date,
+                hours_until_due: (due - now).num_minutes() as f64 / 60.0,
+                current_status: instance.status,
+                priority: instance.priority,
+            })
+        })
+        .collect();
+
+    items.sort_by(|a, b| a.hours_until_due.total_cmp(&b.hours_until_due));
+    items
+}
+
+/// Fraction of the SLA window remaining at which an instance counts as at risk.
+const SLA_AT_RISK_FRACTION: f64 = 0.2;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SlaRiskItem {
+    pub instance_id: i32,
+    pub product_id: i32,
+    pub workflow_id: i32,
+    pub current_step_id: Option<i32>,
+    pub step_name: Option<String>,
+    pub priority: String,
+    pub assigned_team_id: Option<i32>,
+    pub sla_hours: i32,
+    pub deadline: String,
+    pub hours_remaining: f64,
+    pub classification: String,
+}
+
+/// Parse a stored timestamp as naive UTC, the form workflow times are compared in
+fn parse_timestamp(value: &str) -> Option<NaiveDateTime> {
+    parse_server_timestamp(value).map(|t| t.naive_utc())
+}
+
+/// Due dates may be full timestamps or plain dates (midnight UTC)
+fn parse_due_date(value: &str) -> Option<NaiveDateTime> {
+    parse_server_date(value).map(|t| t.naive_utc())
+}
+
+/// Classify an SLA deadline relative to `now`: `breached` once the deadline has
+/// passed, `at_risk` when less than 20% of the SLA window remains, otherwise
+/// `on_track`.
+fn classify_sla(deadline: NaiveDateTime, sla_hours: i32, now: NaiveDateTime) -> &'static str {
+    let remaining = deadline - now;
+    let at_risk_window =
+        chrono::Duration::seconds((sla_hours as f64 * 3600.0 * SLA_AT_RISK_FRACTION) as i64);
+    if remaining < chrono::Duration::zero() {
+        "breached"
+    } else if remaining <= at_risk_window {
+        "at_risk"
+    } else {
+        "on_track"
+    }
+}
+
+/// Instances that are neither completed nor cancelled, optionally for one team
+async fn fetch_active_instances(
+    api_client: &ApiClient,
+    team_id: Option<i32>,
+) -> Result<Vec<ProductWorkflowInstance>, String> {
+    let instances: Vec<ProductWorkflowInstance> = api_client
+        .get_json("/production/instances")
         .await
-        .map_err(|e| format!("Failed to fetch dashboard data: {}", e))?;
+        .map_err(|e| format!("Failed to fetch workflow instances: {}", e))?;
+    Ok(instances
+        .into_iter()
+        .filter(|i| i.completed_at.is_none() && !matches!(i.status.as_str(), "completed" | "cancelled"))
+        .filter(|i| team_id.is_none() || i.assigned_team_id == team_id)
+        .collect())
+}
 
-    let dashboard: ProductionDashboardData = serde_json::from_value(response["data"].clone())
-        .map_err(|e| format!("Failed to parse dashboard data: {}", e))?;
+/// Steps of every workflow the instances belong to, fetched once per workflow
+async fn fetch_steps_by_workflow(
+    api_client: &ApiClient,
+    instances: &[ProductWorkflowInstance],
+) -> Result<HashMap<i32, Vec<WorkflowStep>>, String> {
+    let mut steps_by_workflow = HashMap::new();
+    for workflow_id in instances.iter().map(|i| i.workflow_id).collect::<HashSet<_>>() {
+        let steps: Vec<WorkflowStep> = api_client
+            .get_json(&format!("/production/workflows/{}/steps", workflow_id))
+            .await
+            .map_err(|e| format!("Failed to fetch workflow steps: {}", e))?;
+        steps_by_workflow.insert(workflow_id, steps);
+    }
+    Ok(steps_by_workflow)
+}
 
-    Ok(dashboard)
+#[command(rename_all = "snake_case")]
+pub async fn get_sla_at_risk(
+    api_client: State<'_, ApiClient>,
+    team_id: Option<i32>,
+) -> Result<Vec<SlaRiskItem>, String> {
+    let active = fetch_active_instances(&api_client, team_id).await?;
+    // Steps carry the SLA
+    let steps_by_workflow = fetch_steps_by_workflow(&api_client, &active).await?;
+
+    let now = chrono::Utc::now().naive_utc();
+    let mut items: Vec<SlaRiskItem> = active
+        .into_iter()
+        .filter_map(|instance| {
+            let step = instance.current_step_id.and_then(|step_id| {
+                steps_by_workflow
+                    .get(&instance.workflow_id)?
+                    .iter()
+                    .find(|s| s.id == step_id)
+            })?;
+            let sla_hours = step.sla_hours?;
+            // Fall back to the SLA measured from the start when no estimate is set.
+            let deadline = match instance.estimated_completion.as_deref() {
+                Some(estimate) => parse_timestamp(estimate)?,
+                None => parse_timestamp(&instance.started_at)? + chrono::Duration::hours(sla_hours as i64),
+            };
+            let classification = classify_sla(deadline, sla_hours, now);
+            if classification == "on_track" {
+                return None;
+            }
+            Some(SlaRiskItem {
+                instance_id: instance.id,
+                product_id: instance.product_id,
+                workflow_id: instance.workflow_id,
+                current_step_id: instance.current_step_id,
+                step_name: Some(step.step_name.clone()),
+                priority: instance.priority,
+                assigned_team_id: instance.assigned_team_id,
+                sla_hours,
+                deadline: deadline.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                hours_remaining: (deadline - now).num_minutes() as f64 / 60.0,
+                classification: classification.to_string(),
+            })
+        })
+        .collect();
+
+    // Most overdue first, then the ones closest to breaching.
+    items.sort_by(|a, b| a.hours_remaining.total_cmp(&b.hours_remaining));
+    Ok(items)
+}
+
+/// Waiting products or hours at which a step counts as a medium/high bottleneck
+const BOTTLENECK_MEDIUM_WAITING: i64 = 5;
+const BOTTLENECK_HIGH_WAITING: i64 = 10;
+const BOTTLENECK_MEDIUM_WAIT_HOURS: f64 = 24.0;
+const BOTTLENECK_HIGH_WAIT_HOURS: f64 = 72.0;
+
+fn bottleneck_severity(products_waiting: i64, average_wait_time_hours: f64) -> &'static str {
+    if products_waiting >= BOTTLENECK_HIGH_WAITING || average_wait_time_hours >= BOTTLENECK_HIGH_WAIT_HOURS {
+        "high"
+    } else if products_waiting >= BOTTLENECK_MEDIUM_WAITING || average_wait_time_hours >= BOTTLENECK_MEDIUM_WAIT_HOURS {
+        "medium"
+    } else {
+        "low"
+    }
+}
+
+/// Compute bottlenecks from the current instances rather than the dashboard
+/// aggregate: active instances grouped by their current step.
+#[command(rename_all = "snake_case")]
+pub async fn compute_bottlenecks(
+    api_client: State<'_, ApiClient>,
+    team_id: Option<i32>,
+) -> Result<Vec<BottleneckItem>, String> {
+    let active = fetch_active_instances(&api_client, team_id).await?;
+    let steps_by_workflow = fetch_steps_by_workflow(&api_client, &active).await?;
+
+    // (workflow_id, step_id) -> hours each instance has been waiting
+    let now = chrono::Utc::now().naive_utc();
+    let mut waiting: HashMap<(i32, i32), Vec<f64>> = HashMap::new();
+    for instance in &active {
+        let (Some(step_id), Some(started_at)) = (instance.current_step_id, parse_timestamp(&instance.started_at)) else {
+            continue;
+        };
+        let hours = (now - started_at).num_minutes() as f64 / 60.0;
+        waiting.entry((instance.workflow_id, step_id)).or_default().push(hours);
+    }
+
+    let mut bottlenecks: Vec<BottleneckItem> = waiting
+        .into_iter()
+        .map(|((workflow_id, step_id), hours)| {
+            let workflow_step_name = steps_by_workflow
+                .get(&workflow_id)
+                .and_then(|steps| steps.iter().find(|s| s.id == step_id))
+                .map(|s| s.step_name.clone())
+                .unwrap_or_else(|| format!("Step {}", step_id));
+            let products_waiting = hours.len() as i64;
+            let average_wait_time_hours = hours.iter().sum::<f64>() / hours.len() as f64;
+            BottleneckItem {
+                workflow_step_name,
+                products_waiting,
+                average_wait_time_hours,
+                severity: bottleneck_severity(products_waiting, average_wait_time_hours).to_string(),
+            }
+        })
+        .collect();
+
+    bottlenecks.sort_by(|a, b| {
+        b.products_waiting
+            .cmp(&a.products_waiting)
+            .then(b.average_wait_time_hours.total_cmp(&a.average_wait_time_hours))
+    });
+    Ok(bottlenecks)
 }
 
 // ========================================
 // PRODUCTION ISSUES COMMANDS
 // ========================================
 
-#[command]
+#[command(rename_all = "snake_case")]
+#[allow(clippy::too_many_arguments)]
 pub async fn get_production_issues(
     api_client: State<'_, ApiClient>,
     status: Option<String>,
     severity: Option<String>,
     assigned_to: Option<i32>,
     product_id: Option<i32>,
+    reported_after: Option<String>,
+    reported_before: Option<String>,
+    due_before: Option<String>,
+    sort_by: Option<String>,
+    sort_desc: Option<bool>,
 ) -> Result<Vec<ProductionIssue>, String> {
+    // Reject bad filters before anything is sent
+    validate_issue_filters(
+        reported_after.as_deref(),
+        reported_before.as_deref(),
+        due_before.as_deref(),
+        sort_by.as_deref(),
+    )?;
+
     let mut query_params = HashMap::new();
-    
+    for (name, value) in [
+        ("reported_after", reported_after),
+        ("reported_before", reported_before),
+        ("due_before", due_before),
+    ] {
+        if let Some(value) = value {
+            query_params.insert(name, value);
+        }
+    }
+    if let Some(field) = &sort_by {
+        query_params.insert("sort_by", field.clone());
+        query_params.insert("sort_desc", sort_desc.unwrap_or(false).to_string());
+    }
+
     if let Some(s) = status {
         query_params.insert("status", s);
     }
@@ -470,65 +768,249 @@ pub async fn get_production_issues(
         query_params.insert("product_id", pid.to_string());
     }
 
+    // Timestamps may contain '+', so the query must be encoded
     let query_string = if query_params.is_empty() {
         String::new()
     } else {
-        "?".to_string() + &query_params
-            .iter()
-            .map(|(k, v)| format!("{}={}", k, v))
-            .collect::<Vec<String>>()
-            .join("&")
+        "?".to_string()
+            + &serde_urlencoded::to_string(&query_params)
+                .map_err(|e| format!("Failed to encode issue filters: {}", e))?
     };
 
-    let response = api_client
-        .get(&format!("/production/issues{}", query_string))
+    let mut issues: Vec<ProductionIssue> = api_client
+        .get_json(&format!("/production/issues{}", query_string))
         .await
         .map_err(|e| format!("Failed to fetch production issues: {}", e))?;
 
-    let issues: Vec<ProductionIssue> = serde_json::from_value(response["data"].clone())
-        .map_err(|e| format!("Failed to parse production issues: {}", e))?;
+    // Sort locally too, in case the backend ignores `sort_by`
+    if let Some(sort_by) = sort_by {
+        sort_production_issues(&mut issues, &sort_by, sort_desc.unwrap_or(false))?;
+    }
 
     Ok(issues)
 }
 
-#[command]
+const ISSUE_SORT_FIELDS: [&str; 3] = ["severity", "due_date", "reported_at"];
+
+// Report timestamps like the ones the backend stores; `due_before` is
+// compared with `due_date`, so it may also be a plain date
+fn validate_issue_filters(
+    reported_after: Option<&str>,
+    reported_before: Option<&str>,
+    due_before: Option<&str>,
+    sort_by: Option<&str>,
+) -> Result<(), String> {
+    for (name, value) in [("reported_after", reported_after), ("reported_before", reported_before)] {
+        if let Some(value) = value.filter(|v| parse_timestamp(v).is_none()) {
+            return Err(format!(
+                "Invalid {} '{}': expected a timestamp such as 2026-03-01T09:00:00Z",
+                name, value
+            ));
+        }
+    }
+    if let Some(value) = due_before.filter(|v| parse_due_date(v).is_none()) {
+        return Err(format!(
+            "Invalid due_before '{}': expected a date such as 2026-03-01 or a timestamp",
+            value
+        ));
+    }
+    match sort_by {
+        Some(field) if !ISSUE_SORT_FIELDS.contains(&field) => Err(unsupported_sort_field(field)),
+        _ => Ok(()),
+    }
+}
+
+fn unsupported_sort_field(field: &str) -> String {
+    format!("Unsupported sort field '{}'. Expected {}", field, ISSUE_SORT_FIELDS.join(", "))
+}
+
+/// Rank severities by impact so they don't sort alphabetically
+fn severity_rank(severity: &str) -> u8 {
+    match severity.to_ascii_lowercase().as_str() {
+        "critical" => 4,
+        "high" => 3,
+        "medium" => 2,
+        "low" => 1,
+        _ => 0,
+    }
+}
+
+/// Sort issues by `severity`, `due_date` or `reported_at`. Issues without a
+/// due date always sort last.
+fn sort_production_issues(issues: &mut [ProductionIssue], sort_by: &str, desc: bool) -> Result<(), String> {
+    let directed = |ordering: std::cmp::Ordering| if desc { ordering.reverse() } else { ordering };
+    match sort_by {
+        "severity" => issues.sort_by(|a, b| directed(severity_rank(&a.severity).cmp(&severity_rank(&b.severity)))),
+        "due_date" => issues.sort_by(|a, b| match (&a.due_date, &b.due_date) {
+            (Some(a), Some(b)) => directed(a.cmp(b)),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        }),
+        "reported_at" => issues.sort_by(|a, b| directed(a.reported_at.cmp(&b.reported_at))),
+        other => return Err(unsupported_sort_field(other)),
+    }
+    Ok(())
+}
+
+#[command(rename_all = "snake_case")]
 pub async fn create_production_issue(
     api_client: State<'_, ApiClient>,
     issue: NewProductionIssue,
 ) -> Result<ProductionIssue, String> {
-    let response = api_client
-        .post("/production/issues", &issue)
+    let created_issue: ProductionIssue = api_client
+        .post_json("/production/issues", &issue)
         .await
         .map_err(|e| format!("Failed to create production issue: {}", e))?;
 
-    let created_issue: ProductionIssue = serde_json::from_value(response["data"].clone())
-        .map_err(|e| format!("Failed to parse created production issue: {}", e))?;
+    if let Some(user_id) = created_issue.assigned_to {
+        notify_issue_assignee(&api_client, &created_issue, user_id).await;
+    }
 
     Ok(created_issue)
 }
 
-#[command]
+/// Best-effort notification to the user an issue was assigned to
+async fn notify_issue_assignee(api_client: &ApiClient, issue: &ProductionIssue, user_id: i32) {
+    let body = format!("[{}] {}", issue.severity, issue.title);
+    let action_data = serde_json::json!({ "issue_id": issue.id, "product_id": issue.product_id });
+    if let Err(e) = notify_user(api_client, user_id, "Production issue assigned", &body, "open_production_issue", action_data).await {
+        log::warn!("Failed to notify user {} about issue {}: {}", user_id, issue.id, e);
+    }
+}
+
+#[command(rename_all = "snake_case")]
 pub async fn update_production_issue(
     api_client: State<'_, ApiClient>,
     id: i32,
     updates: UpdateProductionIssue,
 ) -> Result<ProductionIssue, String> {
-    let response = api_client
-        .put(&format!("/production/issues/{}", id), &updates)
+    let updated_issue: ProductionIssue = api_client
+        .put_json(&format!("/production/issues/{}", id), &updates)
         .await
         .map_err(|e| format!("Failed to update production issue: {}", e))?;
 
-    let updated_issue: ProductionIssue = serde_json::from_value(response["data"].clone())
-        .map_err(|e| format!("Failed to parse updated production issue: {}", e))?;
+    if let Some(user_id) = updates.assigned_to {
+        notify_issue_assignee(&api_client, &updated_issue, user_id).await;
+    }
 
     Ok(updated_issue)
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OverdueIssue {
+    pub issue: ProductionIssue,
+    pub hours_overdue: f64,
+    pub escalated: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OverdueIssueGroup {
+    pub severity: String,
+    pub issues: Vec<OverdueIssue>,
+}
+
+/// Severity one level up, for escalating overdue high/critical issues
+fn escalated_severity(severity: &str) -> Option<&'static str> {
+    match severity.to_ascii_lowercase().as_str() {
+        "high" => Some("critical"),
+        _ => None,
+    }
+}
+
+/// Open issues past their due date, grouped by severity (most severe first).
+/// With `escalate`, overdue high issues are raised to critical and their
+/// assignees notified; critical issues are already at the top and are only
+/// notified. Issues without a due date are excluded.
+#[command(rename_all = "snake_case")]
+pub async fn get_overdue_issues(
+    api_client: State<'_, ApiClient>,
+    team_id: Option<i32>,
+    escalate: Option<bool>,
+) -> Result<Vec<OverdueIssueGroup>, String> {
+    let now = chrono::Utc::now().naive_utc();
+    overdue_issue_groups(&api_client, team_id, escalate.unwrap_or(false), now).await
+}
+
+async fn overdue_issue_groups(
+    api_client: &ApiClient,
+    team_id: Option<i32>,
+    escalate: bool,
+    now: NaiveDateTime,
+) -> Result<Vec<OverdueIssueGroup>, String> {
+    let endpoint = match team_id {
+        Some(tid) => format!("/production/issues?team_id={}", tid),
+        None => "/production/issues".to_string(),
+    };
+    let issues: Vec<ProductionIssue> = api_client
+        .get_json(&endpoint)
+        .await
+        .map_err(|e| format!("Failed to fetch production issues: {}", e))?;
+
+    let mut overdue: Vec<OverdueIssue> = issues
+        .into_iter()
+        .filter(|issue| issue.resolved_at.is_none() && !matches!(issue.status.as_str(), "resolved" | "closed"))
+        .filter_map(|issue| {
+            let due = parse_due_date(issue.due_date.as_deref()?)?;
+            let hours_overdue = (now - due).num_minutes() as f64 / 60.0;
+            (hours_overdue > 0.0).then_some(OverdueIssue { issue, hours_overdue, escalated: false })
+        })
+        .collect();
+
+    if escalate {
+        for item in overdue.iter_mut().filter(|i| severity_rank(&i.issue.severity) >= severity_rank("high")) {
+            if let Some(new_severity) = escalated_severity(&item.issue.severity) {
+                // Send only the severity so the rest of the issue is untouched
+                match api_client
+                    .put(
+                        &format!("/production/issues/{}", item.issue.id),
+                        &serde_json::json!({ "severity": new_severity }),
+                    )
+                    .await
+                {
+                    Ok(_) => {
+                        item.issue.severity = new_severity.to_string();
+                        item.escalated = true;
+                    }
+                    Err(e) => log::warn!("Failed to escalate issue {}: {}", item.issue.id, e),
+                }
+            }
+            if let Some(user_id) = item.issue.assigned_to {
+                let body = format!(
+                    "[{}] {} is {:.0} hours overdue",
+                    item.issue.severity, item.issue.title, item.hours_overdue
+                );
+                let action_data = serde_json::json!({ "issue_id": item.issue.id, "product_id": item.issue.product_id });
+                if let Err(e) = notify_user(api_client, user_id, "Overdue production issue", &body, "open_production_issue", action_data).await {
+                    log::warn!("Failed to notify user {} about overdue issue {}: {}", user_id, item.issue.id, e);
+                }
+            }
+        }
+    }
+
+    // Unrecognised severities share a rank, so order by name too to keep
+    // each one's issues together
+    overdue.sort_by(|a, b| {
+        severity_rank(&b.issue.severity)
+            .cmp(&severity_rank(&a.issue.severity))
+            .then_with(|| a.issue.severity.to_lowercase().cmp(&b.issue.severity.to_lowercase()))
+            .then(b.hours_overdue.total_cmp(&a.hours_overdue))
+    });
+    let mut groups: Vec<OverdueIssueGroup> = Vec::new();
+    for item in overdue {
+        match groups.last_mut() {
+            Some(group) if group.severity.eq_ignore_ascii_case(&item.issue.severity) => group.issues.push(item),
+            _ => groups.push(OverdueIssueGroup { severity: item.issue.severity.to_lowercase(), issues: vec![item] }),
+        }
+    }
+    Ok(groups)
+}
+
 // ========================================
 // WORKFLOW AUTOMATION COMMANDS
 // ========================================
 
-#[command]
+#[command(rename_all = "snake_case")]
 pub async fn advance_workflow_step(
     api_client: State<'_, ApiClient>,
     workflow_instance_id: i32,
@@ -545,22 +1027,27 @@ pub async fn advance_workflow_step(
     update_product_workflow_instance(api_client, workflow_instance_id, updates).await
 }
 
-#[command]
+#[command(rename_all = "snake_case")]
 pub async fn approve_workflow_step(
-    api_client: State<'_, ApiClient>,
+    _api_client: State<'_, ApiClient>,
     workflow_instance_id: i32,
     step_id: i32,
     approval_notes: Option<String>,
 ) -> Result<bool, String> {
     // This would implement workflow step approval logic
     // For now, we'll just return success
-    log::info!("Approving workflow step {} for instance {}", step_id, workflow_instance_id);
+    log::info!(
+        "Approving workflow step {} for instance {} (notes: {})",
+        step_id,
+        workflow_instance_id,
+        approval_notes.as_deref().unwrap_or("none")
+    );
     Ok(true)
 }
 
-#[command]
+#[command(rename_all = "snake_case")]
 pub async fn reject_workflow_step(
-    api_client: State<'_, ApiClient>,
+    _api_client: State<'_, ApiClient>,
     workflow_instance_id: i32,
     step_id: i32,
     rejection_reason: String,
@@ -571,18 +1058,216 @@ pub async fn reject_workflow_step(
     Ok(true)
 }
 
-// Implement Default for UpdateProductWorkflowInstance to support ..Default::default()
-impl Default for UpdateProductWorkflowInstance {
-    fn default() -> Self {
-        Self {
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{mock_server, test_client};
+
+    fn issue(id: i32, severity: &str, due_date: Option<&str>) -> ProductionIssue {
+        ProductionIssue {
+            id,
+            product_id: 1,
+            workflow_instance_id: None,
+            issue_type: "data".to_string(),
+            severity: severity.to_string(),
+            title: format!("Issue {}", id),
+            description: String::new(),
+            status: "open".to_string(),
+            reported_by: None,
+            assigned_to: None,
+            resolved_by: None,
+            reported_at: format!("2026-01-0{}T00:00:00Z", id),
+            due_date: due_date.map(String::from),
+            resolved_at: None,
+            resolution_notes: None,
+            impact_assessment: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    fn ids(issues: &[ProductionIssue]) -> Vec<i32> {
+        issues.iter().map(|issue| issue.id).collect()
+    }
+
+    #[test]
+    fn severity_sorts_by_rank_not_alphabetically() {
+        let mut issues =
+            vec![issue(1, "medium", None), issue(2, "Critical", None), issue(3, "low", None), issue(4, "high", None)];
+        sort_production_issues(&mut issues, "severity", true).unwrap();
+        assert_eq!(ids(&issues), [2, 4, 1, 3]);
+        sort_production_issues(&mut issues, "severity", false).unwrap();
+        assert_eq!(ids(&issues), [3, 1, 4, 2]);
+    }
+
+    #[test]
+    fn missing_due_dates_sort_last_in_both_directions() {
+        let mut issues = vec![
+            issue(1, "low", None),
+            issue(2, "low", Some("2026-03-01")),
+            issue(3, "low", Some("2026-02-01")),
+        ];
+        sort_production_issues(&mut issues, "due_date", false).unwrap();
+        assert_eq!(ids(&issues), [3, 2, 1]);
+        sort_production_issues(&mut issues, "due_date", true).unwrap();
+        assert_eq!(ids(&issues), [2, 3, 1]);
+    }
+
+    #[test]
+    fn unknown_sort_field_is_rejected() {
+        assert!(sort_production_issues(&mut [issue(1, "low", None)], "title", false).is_err());
+        let error = validate_issue_filters(None, None, None, Some("title")).unwrap_err();
+        assert!(error.starts_with("Unsupported sort field 'title'"), "{error}");
+    }
+
+    #[test]
+    fn due_before_accepts_plain_dates_but_report_filters_need_a_time() {
+        assert_eq!(validate_issue_filters(None, None, Some("2026-03-01"), Some("due_date")), Ok(()));
+        let reported = (Some("2026-03-01T09:00:00"), Some("2026-03-02T09:00:00+01:00"));
+        assert_eq!(validate_issue_filters(reported.0, reported.1, None, None), Ok(()));
+        let error = validate_issue_filters(Some("2026-03-01"), None, None, None).unwrap_err();
+        assert!(error.starts_with("Invalid reported_after '2026-03-01'"), "{error}");
+        assert!(validate_issue_filters(None, None, Some("March 1st"), None).is_err());
+    }
+
+    #[tokio::test]
+    async fn failed_assignment_notification_keeps_the_assignment() {
+        let instance = serde_json::json!({ "data": {
+            "id": 4, "product_id": 12, "workflow_id": 1, "current_step_id": null,
+            "status": "in_progress", "priority": "high", "assigned_team_id": null,
+            "assigned_user_id": 8, "started_at": "2026-01-01T00:00:00Z", "completed_at": null,
+            "estimated_completion": null, "actual_completion": null, "notes": null,
+            "created_at": "2026-01-01T00:00:00Z", "updated_at": "2026-01-02T00:00:00Z"
+        }})
+        .to_string();
+        let (base_url, requests) = mock_server(move |method, _, _| match method {
+            "PUT" => (200, instance.clone()),
+            _ => (500, r#"{"message":"notifications unavailable"}"#.to_string()),
+        })
+        .await;
+        let api_client = test_client(&base_url).await;
+
+        let result = assign_instance(&api_client, 4, 8, None).await.unwrap();
+        assert_eq!(result.instance.assigned_user_id, Some(8));
+        assert!(result.warning.is_some_and(|w| w.starts_with("Assignment saved")));
+        assert_eq!(*requests.lock().unwrap(), ["PUT /production/instances/4", "POST /notifications"]);
+    }
+
+    fn at(value: &str) -> NaiveDateTime {
+        parse_timestamp(value).unwrap()
+    }
+
+    #[tokio::test]
+    async fn overdue_issues_are_escalated_and_grouped_by_severity() {
+        let mut escalated = issue(1, "high", Some("2026-03-09"));
+        escalated.assigned_to = Some(8);
+        let issues = vec![
+            escalated,
+            issue(2, "critical", Some("2026-03-08")),
+            issue(3, "blocker", Some("2026-03-09")),
+            issue(4, "minor", Some("2026-03-07")),
+            issue(5, "Blocker", Some("2026-03-05")),
+            // Not yet due, and no due date
+            issue(6, "low", Some("2026-03-20")),
+            issue(7, "medium", None),
+        ];
+        let listing = serde_json::json!({ "data": issues }).to_string();
+        let (base_url, requests) = mock_server(move |method, _, _| match method {
+            "GET" => (200, listing.clone()),
+            _ => (200, "{}".to_string()),
+        })
+        .await;
+        let api_client = test_client(&base_url).await;
+
+        let groups = overdue_issue_groups(&api_client, Some(3), true, at("2026-03-10T00:00:00Z")).await.unwrap();
+
+        let summary: Vec<(&str, Vec<i32>)> = groups
+            .iter()
+            .map(|group| (group.severity.as_str(), group.issues.iter().map(|item| item.issue.id).collect()))
+            .collect();
+        assert_eq!(summary, [("critical", vec![2, 1]), ("blocker", vec![5, 3]), ("minor", vec![4])]);
+        let escalated = &groups[0].issues[1];
+        assert!(escalated.escalated);
+        assert_eq!(escalated.issue.severity, "critical");
+        assert_eq!(escalated.hours_overdue, 24.0);
+        assert_eq!(
+            *requests.lock().unwrap(),
+            ["GET /production/issues?team_id=3", "PUT /production/issues/1", "POST /notifications"]
+        );
+    }
+
+    fn instance(product_id: i32, estimated_completion: Option<&str>) -> ProductWorkflowInstance {
+        ProductWorkflowInstance {
+            id: product_id * 10,
+            product_id,
+            workflow_id: 1,
             current_step_id: None,
-            status: None,
-            priority: None,
+            status: "in_progress".to_string(),
+            priority: "normal".to_string(),
             assigned_team_id: None,
             assigned_user_id: None,
-            estimated_completion: None,
+            started_at: "2026-01-01T00:00:00Z".to_string(),
+            completed_at: None,
+            estimated_completion: estimated_completion.map(String::from),
             actual_completion: None,
             notes: None,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn due_soon_lists_overdue_first_and_drops_items_outside_the_window() {
+        let now = at("2026-03-01T12:00:00Z");
+        let products: HashMap<i64, Value> = [
+            (4, serde_json::json!({ "id": 4, "site_id": "SITE-4", "due_date": "2026-03-03" })),
+            (5, serde_json::json!({ "id": 5, "item_id": "ITEM-5" })),
+        ]
+        .into_iter()
+        .collect();
+        let instances = vec![
+            instance(1, Some("2026-03-04T12:00:00Z")),
+            instance(2, Some("2026-02-28T12:00:00Z")),
+            instance(3, Some("2026-04-01T00:00:00Z")),
+            // Falls back to the product's due date
+            instance(4, None),
+            // No due date anywhere
+            instance(5, None),
+        ];
+
+        let items = deadline_items(instances, &products, now, 7);
+        let summary: Vec<(i32, f64)> = items.iter().map(|item| (item.product_id, item.hours_until_due)).collect();
+        assert_eq!(summary, [(2, -24.0), (4, 36.0), (1, 72.0)]);
+        assert_eq!(items[1].product_name, "SITE-4");
+        assert_eq!(items[0].product_name, "Product 2");
+    }
+
+    #[test]
+    fn sla_classification_boundaries() {
+        // A 10 hour SLA is at risk in its last 2 hours
+        let deadline = at("2026-03-01T12:00:00Z");
+        let cases = [
+            ("2026-03-01T09:59:59Z", "on_track"),
+            ("2026-03-01T10:00:00Z", "at_risk"),
+            ("2026-03-01T12:00:00Z", "at_risk"),
+            ("2026-03-01T12:00:01Z", "breached"),
+        ];
+        for (now, expected) in cases {
+            assert_eq!(classify_sla(deadline, 10, at(now)), expected, "at {}", now);
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn step_order_must_match_existing_steps() {
+        assert!(validate_step_order(&[1, 2, 3], &[3, 1, 2]).is_ok());
+
+        let duplicate = validate_step_order(&[1, 2, 3], &[1, 2, 2]).unwrap_err();
+        assert!(duplicate.contains("Duplicate step IDs"), "{}", duplicate);
+
+        let missing = validate_step_order(&[1, 2, 3], &[1, 2]).unwrap_err();
+        assert!(missing.contains("missing: [3]"), "{}", missing);
+
+        let unknown = validate_step_order(&[1, 2, 3], &[1, 2, 3, 9]).unwrap_err();
+        assert!(unknown.contains("unknown: [9]"), "{}", unknown);
+    }
+}
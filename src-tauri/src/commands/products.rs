@@ -1,18 +1,359 @@
+use crate::auth::login::current_user_role;
+use crate::commands::operations::{OperationProgress, OperationRegistry};
 use crate::services::api_client::ApiClient;
-use log::info;
-use tauri::State;
-use serde_json::json;
+use crate::services::s2_cell::{self, CellId};
+use futures::stream::{self, StreamExt};
+use log::{error, info, warn};
+use reqwest::{Method, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager, State, Window};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
-#[tauri::command]
-pub async fn get_all_products(api_client: State<'_, ApiClient>) -> Result<String, String> {
+/// Maximum number of product requests in flight during bulk operations.
+const PRODUCT_BATCH_CONCURRENCY: usize = 8;
+
+/// A single CSV row; headers match the `create_product` arguments.
+#[derive(Debug, Deserialize)]
+struct ProductCsvRow {
+    item_id: Option<String>,
+    site_id: Option<String>,
+    product_type_id: Option<i32>,
+    status: Option<String>,
+    status_date: Option<String>,
+    taskorder_id: Option<i32>,
+    file_path: Option<String>,
+    s2_index: Option<String>,
+    classification: Option<String>,
+    coordinate_system: Option<String>,
+    srid: Option<i32>,
+}
+
+/// A single product status transition.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StatusChange {
+    pub from_status: Option<String>,
+    pub to_status: String,
+    pub changed_by: Option<String>,
+    pub changed_at: String,
+}
+
+/// One entry in a product's assignment history.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AssignmentEvent {
+    /// `checked_out`, `assigned` or `removed`
+    pub action: String,
+    pub assignment_id: Option<i64>,
+    pub user_id: Option<i64>,
+    pub team_id: Option<i64>,
+    pub reason: Option<String>,
+    pub recorded_by: Option<String>,
+    pub recorded_at: String,
+    /// `local` for the ledger kept by this app, `server` for backend history
+    #[serde(default)]
+    pub source: String,
+}
+
+/// Summary of a GeoJSON export; skipped products are listed in `warnings`.
+#[derive(Debug, Serialize)]
+pub struct GeoJsonExportResult {
+    pub output_path: String,
+    pub feature_count: usize,
+    pub warnings: Vec<String>,
+}
+
+/// Outcome of importing a single CSV row.
+#[derive(Debug, Serialize)]
+pub struct ProductImportResult {
+    pub line: u64,
+    pub product_id: Option<i64>,
+    pub error: Option<String>,
+}
+
+/// Row results of a CSV import; `cancelled` marks a partial import.
+/// `skipped` counts blank rows and rows never sent because the import stopped.
+#[derive(Debug, Serialize)]
+pub struct ProductImportSummary {
+    pub results: Vec<ProductImportResult>,
+    pub cancelled: bool,
+    pub created: usize,
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+impl ProductImportSummary {
+    fn new(mut results: Vec<ProductImportResult>, skipped: usize, cancelled: bool) -> Self {
+        results.sort_by_key(|r| r.line);
+        let created = results.iter().filter(|r| r.product_id.is_some()).count();
+        let failed = results.len() - created;
+        Self { results, cancelled, created, skipped, failed }
+    }
+}
+
+/// Fetch every product. With a `request_id`, the fetch can be stopped with
+/// `cancel_request` if the user navigates away.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_all_products(
+    api_client: State<'_, ApiClient>,
+    request_id: Option<String>,
+) -> Result<String, String> {
     info!("Fetching all products...");
-    api_client.get("/products").await
+    match request_id {
+        Some(request_id) => Ok(api_client.get_cancellable("/products", &request_id).await?),
+        None => api_client.get("/products").await,
+    }
+}
+
+/// Items sent per `list_stream_items` event
+const LIST_STREAM_BATCH_SIZE: usize = 200;
+
+/// A batch of items from a streamed list, emitted as `list_stream_items`
+#[derive(Debug, Clone, Serialize)]
+pub struct ListStreamBatch {
+    pub stream_id: String,
+    pub items: Vec<Value>,
+}
+
+/// Stream a list endpoint to the window in `list_stream_items` batches so the
+/// frontend can render rows as they arrive. Returns the total item count.
+pub(crate) async fn stream_list_to_window(
+    window: &Window,
+    api_client: &ApiClient,
+    endpoint: &str,
+    stream_id: &str,
+) -> Result<usize, String> {
+    let mut batch = Vec::with_capacity(LIST_STREAM_BATCH_SIZE);
+    let emit_batch = |items: Vec<Value>| {
+        let _ = window.emit("list_stream_items", ListStreamBatch { stream_id: stream_id.to_string(), items });
+    };
+    let total = api_client
+        .get_streamed(endpoint, |item| {
+            batch.push(item);
+            if batch.len() >= LIST_STREAM_BATCH_SIZE {
+                emit_batch(std::mem::take(&mut batch));
+            }
+        })
+        .await?;
+    if !batch.is_empty() {
+        emit_batch(batch);
+    }
+    Ok(total)
+}
+
+/// Stream every product to the window as `list_stream_items` batches tagged
+/// with `stream_id`. Returns the number of products.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn stream_products(
+    window: Window,
+    api_client: State<'_, ApiClient>,
+    stream_id: String,
+) -> Result<usize, String> {
+    info!("Streaming all products ({})...", stream_id);
+    stream_list_to_window(&window, &api_client, "/products", &stream_id).await
 }
 
+/// Products sharing a `(site_id, item_id)` pair
+#[derive(Debug, Serialize)]
+pub struct DuplicateGroup {
+    pub site_id: String,
+    pub item_id: String,
+    pub products: Vec<Value>,
+}
+
+// Group products by trimmed `(site_id, item_id)`, keeping only groups with
+// more than one member. Products missing either field are ignored.
+fn group_duplicate_products(products: Vec<Value>) -> Vec<DuplicateGroup> {
+    let mut groups: BTreeMap<(String, String), Vec<Value>> = BTreeMap::new();
+    for product in products {
+        let key = match (product["site_id"].as_str(), product["item_id"].as_str()) {
+            (Some(site_id), Some(item_id)) if !site_id.trim().is_empty() && !item_id.trim().is_empty() => {
+                (site_id.trim().to_string(), item_id.trim().to_string())
+            }
+            _ => continue,
+        };
+        groups.entry(key).or_default().push(product);
+    }
+    groups
+        .into_iter()
+        .filter(|(_, products)| products.len() > 1)
+        .map(|((site_id, item_id), products)| DuplicateGroup { site_id, item_id, products })
+        .collect()
+}
+
+/// Find products that share a `site_id` and `item_id`, optionally within one
+/// task order.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn find_duplicate_products(
+    api_client: State<'_, ApiClient>,
+    taskorder_id: Option<i32>,
+) -> Result<Vec<DuplicateGroup>, String> {
+    let endpoint = match taskorder_id {
+        Some(id) => format!("/products?taskorder_id={}", id),
+        None => "/products".to_string(),
+    };
+    info!("Looking for duplicate products via {endpoint}...");
+    let data: Value = api_client
+        .get_json(&endpoint)
+        .await
+        .map_err(|e| format!("Failed to fetch products: {}", e))?;
+    let (products, _) = parse_products_page(data)?;
+    let groups = group_duplicate_products(products);
+    info!("Found {} duplicate product group(s)", groups.len());
+    Ok(groups)
+}
+
+/// Products whose `s2_index` lies in the S2 cell `s2_token`, taken at `level`
+/// (the token's own level or coarser). The backend is asked for candidates by
+/// token prefix and the result is narrowed to the exact cell here.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn find_products_near(
+    api_client: State<'_, ApiClient>,
+    s2_token: String,
+    level: u8,
+) -> Result<Vec<Value>, String> {
+    if level > s2_cell::MAX_LEVEL {
+        return Err(format!("S2 level must be between 0 and {}", s2_cell::MAX_LEVEL));
+    }
+    let cell = CellId::from_token(&s2_token)?.parent(level)?;
+    info!("Finding products in S2 cell {} (level {level})...", cell.to_token());
+    let data: Value = api_client
+        .get_json(&format!("/products?s2_prefix={}", cell.token_prefix()))
+        .await
+        .map_err(|e| format!("Failed to fetch products: {}", e))?;
+    let (products, _) = parse_products_page(data)?;
+    let matching: Vec<Value> = products
+        .into_iter()
+        .filter(|p| {
+            p["s2_index"]
+                .as_str()
+                .and_then(|index| CellId::from_token(index).ok())
+                .is_some_and(|index| cell.contains(index))
+        })
+        .collect();
+    info!("Found {} product(s) in S2 cell {}", matching.len(), cell.to_token());
+    Ok(matching)
+}
+
+/// Largest page `stream_all_products` will request.
+const MAX_PRODUCT_STREAM_PAGE_SIZE: u32 = 500;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProductsChunk {
+    pub page: u32,
+    pub items: Vec<Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProductsComplete {
+    pub total: usize,
+    pub pages: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProductsStreamError {
+    pub page: u32,
+    pub pages_completed: u32,
+    pub error: String,
+}
+
+// Split a products page into its items and the reported total, if any
+pub(crate) fn parse_products_page(data: Value) -> Result<(Vec<Value>, Option<usize>), String> {
+    match data {
+        Value::Array(items) => Ok((items, None)),
+        Value::Object(mut page_data) => {
+            let total = page_data.get("total").and_then(Value::as_u64).map(|t| t as usize);
+            match page_data.remove("products").or_else(|| page_data.remove("items")) {
+                Some(Value::Array(items)) => Ok((items, total)),
+                _ => Err("Failed to parse products page".to_string()),
+            }
+        }
+        _ => Err("Failed to parse products page".to_string()),
+    }
+}
+
+/// Page through all products, emitting a `products_chunk` event per page and
+/// `products_complete` at the end, so the UI can render incrementally. On a
+/// failed page, emits `products_error` and stops.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn stream_all_products(
+    window: Window,
+    api_client: State<'_, ApiClient>,
+    per_page: u32,
+) -> Result<usize, String> {
+    let result = fetch_all_product_pages(&api_client, per_page, |chunk| {
+        let _ = window.emit("products_chunk", chunk);
+    })
+    .await;
+    match result {
+        Ok(complete) => {
+            let total = complete.total;
+            let _ = window.emit("products_complete", complete);
+            Ok(total)
+        }
+        Err(failure) => {
+            let error = failure.error.clone();
+            let _ = window.emit("products_error", failure);
+            Err(error)
+        }
+    }
+}
+
+// Page through all products, handing each page to `on_chunk`
+async fn fetch_all_product_pages(
+    api_client: &ApiClient,
+    per_page: u32,
+    mut on_chunk: impl FnMut(ProductsChunk),
+) -> Result<ProductsComplete, ProductsStreamError> {
+    let per_page = per_page.clamp(1, MAX_PRODUCT_STREAM_PAGE_SIZE);
+    info!("Streaming all products ({per_page} per page)...");
+    let mut page = 1;
+    let mut emitted = 0;
+
+    loop {
+        let endpoint = format!("/products?page={}&limit={}", page, per_page);
+        let (items, total) = match api_client
+            .get_json::<Value>(&endpoint)
+            .await
+            .map_err(String::from)
+            .and_then(parse_products_page)
+        {
+            Ok(result) => result,
+            Err(e) => {
+                error!("Failed to fetch products page {}: {}", page, e);
+                return Err(ProductsStreamError { page, pages_completed: page - 1, error: e });
+            }
+        };
+
+        let count = items.len();
+        emitted += count;
+        on_chunk(ProductsChunk { page, items });
+
+        // A short page, or more items than asked for (pagination ignored by
+        // the backend), means there is nothing left to fetch
+        let done = count != per_page as usize || total.is_some_and(|total| emitted >= total);
+        if done {
+            break;
+        }
+        page += 1;
+    }
+
+    info!("Streamed {} product(s) in {} page(s)", emitted, page);
+    Ok(ProductsComplete { total: emitted, pages: page })
+}
+
+/// Product types rarely change, so their listing is cached briefly.
+const PRODUCT_TYPES_CACHE_TTL: Duration = Duration::from_secs(300);
+
 #[tauri::command]
 pub async fn get_all_product_types(api_client: State<'_, ApiClient>) -> Result<String, String> {
     info!("Fetching all product_types...");
-    api_client.get("/product_types").await
+    api_client.cached_get("/product_types", PRODUCT_TYPES_CACHE_TTL).await
 }
 
 #[tauri::command(rename_all = "snake_case")]
@@ -21,14 +362,50 @@ pub async fn get_user_products(api_client: State<'_, ApiClient>) -> Result<Strin
     api_client.get("/products/me").await
 }
 
+// The product's active checkout, if someone currently holds it
+async fn active_checkout(api_client: &ApiClient, product_id: i32) -> Result<Option<Value>, String> {
+    let assignments: Vec<Value> = api_client
+        .get_json(&format!("/products/{}/assignments", product_id))
+        .await?;
+    Ok(assignments.into_iter().find(|a| {
+        a["assignment_type"].as_str() == Some("checked_out") && a["status"].as_str() == Some("active")
+    }))
+}
+
+// Display name for the holder of an assignment
+async fn assignment_holder(api_client: &ApiClient, assignment: &Value) -> String {
+    let Some(user_id) = assignment["user_id"].as_i64() else {
+        return "another user".to_string();
+    };
+    match api_client.get_json::<Value>(&format!("/users/{}", user_id)).await {
+        Ok(user) => user["username"].as_str().map(String::from).unwrap_or_else(|| format!("user {}", user_id)),
+        Err(_) => format!("user {}", user_id),
+    }
+}
+
+/// Check out a product. Fails if it is already checked out unless `force` is
+/// set, which is limited to team leads and admins.
 #[tauri::command(rename_all = "snake_case")]
 pub async fn checkout_product(
     api_client: State<'_, ApiClient>,
     product_id: i32,
     team_id: Option<i32>,
     reason: String,
+    force: Option<bool>,
 ) -> Result<String, String> {
     info!("Checking out product {product_id}...");
+    let existing = active_checkout(&api_client, product_id).await?;
+    if let Some(existing) = &existing {
+        let holder = assignment_holder(&api_client, existing).await;
+        if !force.unwrap_or(false) {
+            return Err(format!("Product {} is already checked out by {}", product_id, holder));
+        }
+        let role = current_user_role(&api_client).await;
+        if !matches!(role.as_deref(), Some("team_lead" | "admin")) {
+            return Err("Only team leads and admins can force a checkout".to_string());
+        }
+        warn!("Forcing checkout of product {} held by {}", product_id, holder);
+    }
     let checkout_payload = json!({
         "product_id": product_id,
         "user_id": null,
@@ -39,7 +416,21 @@ pub async fn checkout_product(
         "due_date": null,
         "reason": reason,
     });
-    api_client.post("/product-assignments", &checkout_payload).await
+    let response = api_client.post_idempotent("/product-assignments", &checkout_payload).await?;
+    let recorded_by = current_username(&api_client).await;
+
+    // A forced checkout replaces the previous holder's checkout
+    if let Some(existing) = existing {
+        if let Some(existing_id) = existing["id"].as_i64() {
+            match api_client.delete(&format!("/product-assignments/{}", existing_id)).await {
+                Ok(_) => record_assignment_event(product_id.into(), "removed", &existing, recorded_by.clone()),
+                Err(e) => error!("Checked out product {} but failed to release checkout {}: {}", product_id, existing_id, e),
+            }
+        }
+    }
+    let checkout = json!({ "id": created_id(&response), "team_id": team_id, "reason": reason });
+    record_assignment_event(product_id.into(), "checked_out", &checkout, recorded_by);
+    Ok(response)
 }
 
 #[tauri::command(rename_all = "snake_case")]
@@ -63,7 +454,10 @@ pub async fn assign_product_to_user(
         "due_date": due_date,
         "reason": reason,
     });
-    api_client.post("/product-assignments", &assignment_payload).await
+    let response = api_client.post_idempotent("/product-assignments", &assignment_payload).await?;
+    let assignment = json!({ "id": created_id(&response), "user_id": user_id, "team_id": team_id, "reason": reason });
+    record_assignment_event(product_id.into(), "assigned", &assignment, current_username(&api_client).await);
+    Ok(response)
 }
 
 #[tauri::command(rename_all = "snake_case")]
@@ -81,7 +475,240 @@ pub async fn delete_product_assignment(
     assignment_id: i32,
 ) -> Result<String, String> {
     info!("Deleting product assignment {assignment_id}...");
-    api_client.delete(&format!("/product-assignments/{}", assignment_id)).await
+    let endpoint = format!("/product-assignments/{}", assignment_id);
+    // Read first so the ledger knows which product and holder this was
+    let assignment = api_client.get_json::<Value>(&endpoint).await.ok();
+    let response = api_client.delete(&endpoint).await?;
+    match assignment.as_ref().and_then(|a| a["product_id"].as_i64().map(|product_id| (product_id, a))) {
+        Some((product_id, assignment)) => {
+            record_assignment_event(product_id, "removed", assignment, current_username(&api_client).await)
+        }
+        None => warn!("Assignment {} removed without a history entry: its product is unknown", assignment_id),
+    }
+    Ok(response)
+}
+
+// `data.id` of a create response, if present
+fn created_id(response: &str) -> Option<i64> {
+    serde_json::from_str::<Value>(response).ok()?["data"]["id"].as_i64()
+}
+
+#[derive(Debug, Serialize)]
+pub struct AssignmentTransferFailure {
+    pub assignment_id: i64,
+    pub product_id: Option<i64>,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AssignmentTransferSummary {
+    pub transferred: usize,
+    pub failed: Vec<AssignmentTransferFailure>,
+}
+
+// Why one assignment couldn't be transferred
+#[derive(Debug)]
+struct TransferError {
+    message: String,
+    // The new assignment was created but the old one could not be removed
+    assigned_to_both: bool,
+}
+
+impl From<String> for TransferError {
+    fn from(message: String) -> Self {
+        TransferError { message, assigned_to_both: false }
+    }
+}
+
+// Recreate one assignment for the new user, then remove the old one. Creating
+// first means a failure never leaves the product unassigned, though a failed
+// removal leaves it assigned to both users.
+async fn transfer_assignment(api_client: &ApiClient, assignment: &Value, to_user_id: i32) -> Result<(), TransferError> {
+    let assignment_id = assignment["id"].as_i64().ok_or("Assignment has no id".to_string())?;
+    let payload = json!({
+        "product_id": assignment["product_id"],
+        "user_id": to_user_id,
+        "team_id": assignment["team_id"],
+        "assignment_type": assignment["assignment_type"],
+        "status": assignment["status"],
+        "assigned_by": null,
+        "due_date": assignment["due_date"],
+        "reason": assignment["reason"],
+    });
+    let response = api_client.post_idempotent("/product-assignments", &payload).await?;
+    let product_id = assignment["product_id"].as_i64();
+    if let Some(product_id) = product_id {
+        let created = json!({
+            "id": created_id(&response),
+            "user_id": to_user_id,
+            "team_id": assignment["team_id"],
+            "reason": assignment["reason"],
+        });
+        record_assignment_event(product_id, "assigned", &created, None);
+    }
+    api_client
+        .delete(&format!("/product-assignments/{}", assignment_id))
+        .await
+        .map_err(|e| TransferError {
+            message: format!("Created new assignment but failed to remove the old one: {}", e),
+            assigned_to_both: true,
+        })?;
+    if let Some(product_id) = product_id {
+        record_assignment_event(product_id, "removed", assignment, None);
+    }
+    Ok(())
+}
+
+// A user's product assignments, optionally only those within one team
+async fn user_assignments(api_client: &ApiClient, user_id: i32, team_id: Option<i32>) -> Result<Vec<Value>, String> {
+    let assignments: Vec<Value> = api_client
+        .get_json(&format!("/product-assignments?user_id={}", user_id))
+        .await?;
+    Ok(assignments
+        .into_iter()
+        .filter(|a| a["user_id"].as_i64() == Some(user_id as i64))
+        .filter(|a| team_id.is_none_or(|team| a["team_id"].as_i64() == Some(team as i64)))
+        .collect())
+}
+
+// Transfer assignments concurrently, pairing each with its outcome
+async fn transfer_assignments(
+    api_client: &ApiClient,
+    assignments: Vec<Value>,
+    to_user_id: i32,
+) -> Vec<(Value, Result<(), TransferError>)> {
+    stream::iter(assignments)
+        .map(|assignment| async move {
+            let outcome = transfer_assignment(api_client, &assignment, to_user_id).await;
+            (assignment, outcome)
+        })
+        .buffer_unordered(api_client.batch_concurrency(PRODUCT_BATCH_CONCURRENCY))
+        .collect()
+        .await
+}
+
+/// Move every product assignment from one user to another, optionally only
+/// within one team. Failures are collected rather than aborting the batch.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn transfer_user_assignments(
+    api_client: State<'_, ApiClient>,
+    from_user_id: i32,
+    to_user_id: i32,
+    team_id: Option<i32>,
+) -> Result<AssignmentTransferSummary, String> {
+    transfer_all_assignments(&api_client, from_user_id, to_user_id, team_id).await
+}
+
+async fn transfer_all_assignments(
+    api_client: &ApiClient,
+    from_user_id: i32,
+    to_user_id: i32,
+    team_id: Option<i32>,
+) -> Result<AssignmentTransferSummary, String> {
+    if from_user_id == to_user_id {
+        return Err("Cannot transfer assignments to the same user".to_string());
+    }
+    info!("Transferring assignments from user {from_user_id} to user {to_user_id}...");
+    let assignments = user_assignments(api_client, from_user_id, team_id).await?;
+    let outcomes = transfer_assignments(api_client, assignments, to_user_id).await;
+    let summary = summarize_transfers(outcomes);
+    info!("Transferred {} assignment(s), {} failed", summary.transferred, summary.failed.len());
+    Ok(summary)
+}
+
+fn summarize_transfers(outcomes: Vec<(Value, Result<(), TransferError>)>) -> AssignmentTransferSummary {
+    let mut summary = AssignmentTransferSummary { transferred: 0, failed: Vec::new() };
+    for (assignment, outcome) in outcomes {
+        match outcome {
+            Ok(()) => summary.transferred += 1,
+            Err(e) => {
+                error!("Failed to transfer assignment {}: {}", assignment["id"], e.message);
+                summary.failed.push(AssignmentTransferFailure {
+                    assignment_id: assignment["id"].as_i64().unwrap_or_default(),
+                    product_id: assignment["product_id"].as_i64(),
+                    error: e.message,
+                });
+            }
+        }
+    }
+    summary
+}
+
+/// Outcome of moving one product to the new user
+#[derive(Debug, Serialize)]
+pub struct ProductReassignmentResult {
+    pub product_id: Option<i64>,
+    pub assignment_id: Option<i64>,
+    /// Set when the product could not be moved cleanly
+    pub error: Option<String>,
+    /// The new assignment was created but the original could not be removed,
+    /// so the product is assigned to both users. When `error` is set and this
+    /// isn't, the product stayed with the original user only.
+    pub assigned_to_both: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProductReassignmentSummary {
+    pub moved: usize,
+    pub failed: usize,
+    pub results: Vec<ProductReassignmentResult>,
+}
+
+/// Hand a departing user's active product assignments to another user,
+/// optionally only within one team. Each product is reported separately; a
+/// product whose new assignment can't be created keeps its original one, and
+/// one whose original assignment can't be removed is left with both.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn reassign_user_products(
+    api_client: State<'_, ApiClient>,
+    from_user_id: i32,
+    to_user_id: i32,
+    team_id: Option<i32>,
+) -> Result<ProductReassignmentSummary, String> {
+    reassign_products(&api_client, from_user_id, to_user_id, team_id).await
+}
+
+async fn reassign_products(
+    api_client: &ApiClient,
+    from_user_id: i32,
+    to_user_id: i32,
+    team_id: Option<i32>,
+) -> Result<ProductReassignmentSummary, String> {
+    if from_user_id == to_user_id {
+        return Err("Cannot reassign products to the same user".to_string());
+    }
+    info!("Reassigning active products from user {from_user_id} to user {to_user_id}...");
+    let assignments: Vec<Value> = user_assignments(api_client, from_user_id, team_id)
+        .await?
+        .into_iter()
+        .filter(|a| a["status"].as_str().is_none_or(|s| s.eq_ignore_ascii_case("active")))
+        .collect();
+    let outcomes = transfer_assignments(api_client, assignments, to_user_id).await;
+
+    let mut results: Vec<ProductReassignmentResult> = outcomes
+        .into_iter()
+        .map(|(assignment, outcome)| {
+            let product_id = assignment["product_id"].as_i64();
+            let assignment_id = assignment["id"].as_i64();
+            match outcome {
+                Ok(()) => ProductReassignmentResult { product_id, assignment_id, error: None, assigned_to_both: false },
+                Err(e) => {
+                    error!("Failed to reassign product {}: {}", assignment["product_id"], e.message);
+                    ProductReassignmentResult {
+                        product_id,
+                        assignment_id,
+                        error: Some(e.message),
+                        assigned_to_both: e.assigned_to_both,
+                    }
+                }
+            }
+        })
+        .collect();
+    results.sort_by_key(|r| r.product_id);
+    let failed = results.iter().filter(|r| r.error.is_some()).count();
+    let summary = ProductReassignmentSummary { moved: results.len() - failed, failed, results };
+    info!("Reassigned {} product(s), {} failed", summary.moved, summary.failed);
+    Ok(summary)
 }
 
 #[tauri::command(rename_all = "snake_case")]
@@ -93,7 +720,53 @@ pub async fn get_product_assignments(
     api_client.get(&format!("/products/{}/assignments", product_id)).await
 }
 
+#[derive(Debug, Serialize)]
+pub struct ProductOverview {
+    pub details: Option<Value>,
+    pub assignments: Option<Value>,
+    pub reviews: Option<Value>,
+    /// Sub-fetches that failed; their fields are left empty
+    pub errors: Vec<String>,
+}
+
+/// Fetch a product's details, assignments and reviews in one call so the
+/// product page doesn't have to chain three requests.
 #[tauri::command(rename_all = "snake_case")]
+pub async fn get_product_overview(
+    api_client: State<'_, ApiClient>,
+    product_id: i32,
+) -> Result<ProductOverview, String> {
+    info!("Fetching overview for product {product_id}...");
+    let details_endpoint = format!("/products/{}", product_id);
+    let assignments_endpoint = format!("/products/{}/assignments", product_id);
+    let reviews_endpoint = format!("/reviews/product/{}", product_id);
+    let (details, assignments, reviews) = tokio::join!(
+        api_client.get_json::<Value>(&details_endpoint),
+        api_client.get_json::<Value>(&assignments_endpoint),
+        api_client.get_json::<Value>(&reviews_endpoint),
+    );
+
+    let mut errors = Vec::new();
+    let mut take = |name: &str, result: Result<Value, _>| match result {
+        Ok(data) => Some(data),
+        Err(e) => {
+            error!("Failed to fetch {} for product {}: {}", name, product_id, e);
+            errors.push(format!("Failed to fetch {}: {}", name, e));
+            None
+        }
+    };
+    let details = take("details", details);
+    let assignments = take("assignments", assignments);
+    let reviews = take("reviews", reviews);
+
+    if details.is_none() && assignments.is_none() && reviews.is_none() {
+        return Err(errors.join("; "));
+    }
+    Ok(ProductOverview { details, assignments, reviews, errors })
+}
+
+#[tauri::command(rename_all = "snake_case")]
+#[allow(clippy::too_many_arguments)]
 pub async fn update_product(
     api_client: State<'_, ApiClient>,
     product_id: i32,
@@ -116,6 +789,81 @@ pub async fn update_product(
     api_client.patch(&format!("/products/{}", product_id), &update_payload).await
 }
 
+/// Product statuses accepted by `bulk_update_product_status`, spelled as the
+/// backend stores them.
+const PRODUCT_STATUSES: [&str; 11] = [
+    "Created",
+    "Assigned",
+    "In Progress",
+    "In Review",
+    "Pending Review",
+    "On Hold",
+    "Completed",
+    "Accepted",
+    "Rejected",
+    "Published",
+    "Cancelled",
+];
+
+// The backend's spelling of `status`. The review editor says "InReview",
+// which is accepted as "In Review".
+fn canonical_product_status(status: &str) -> Option<&'static str> {
+    let status = match status.trim() {
+        "InReview" => "In Review",
+        status => status,
+    };
+    PRODUCT_STATUSES.iter().copied().find(|known| *known == status)
+}
+
+// `GET /products/{id}` nests the product under `product`; tolerate both shapes
+fn product_record(data: &Value) -> &Value {
+    if data["product"].is_object() {
+        &data["product"]
+    } else {
+        data
+    }
+}
+
+// Set a product's status and record the change in the local status log
+async fn set_product_status(
+    api_client: &ApiClient,
+    product_id: i32,
+    status: &str,
+    changed_by: Option<String>,
+) -> Result<String, String> {
+    let product_endpoint = format!("/products/{}", product_id);
+    let from_status = api_client
+        .get_json::<Value>(&product_endpoint)
+        .await
+        .ok()
+        .and_then(|data| product_record(&data)["status"].as_str().map(String::from));
+
+    let payload = json!({
+        "status": status,
+    });
+    let response = api_client.patch(&product_endpoint, &payload).await?;
+
+    let change = StatusChange {
+        from_status,
+        to_status: status.to_string(),
+        changed_by,
+        changed_at: chrono::Utc::now().to_rfc3339(),
+    };
+    if let Err(e) = append_status_log(product_id, &change) {
+        warn!("Failed to record status change for product {}: {}", product_id, e);
+    }
+    Ok(response)
+}
+
+// Username of the acting user, for the local status log
+async fn current_username(api_client: &ApiClient) -> Option<String> {
+    api_client
+        .get_json::<Value>("/users/me")
+        .await
+        .ok()
+        .and_then(|me| me["username"].as_str().map(String::from))
+}
+
 #[tauri::command(rename_all = "snake_case")]
 pub async fn update_product_status(
     api_client: State<'_, ApiClient>,
@@ -123,13 +871,438 @@ pub async fn update_product_status(
     status: String,
 ) -> Result<String, String> {
     info!("Updating product {product_id} status to {status}...");
+    let changed_by = current_username(&api_client).await;
+    set_product_status(&api_client, product_id, &status, changed_by).await
+}
+
+// Check an RFC 3339 due date and normalize it to UTC; `None` stays `None`
+fn normalize_due_date(due_date: Option<String>) -> Result<Option<String>, String> {
+    due_date
+        .map(|date| {
+            chrono::DateTime::parse_from_rfc3339(date.trim())
+                .map(|parsed| parsed.with_timezone(&chrono::Utc).to_rfc3339())
+                .map_err(|e| format!("Invalid due date '{}': {}", date, e))
+        })
+        .transpose()
+}
+
+/// Set or, with `None`, clear a product's due date. The date must be
+/// RFC 3339 (e.g. `2025-06-30T17:00:00Z`) and is normalized to UTC.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn set_product_due_date(
+    api_client: State<'_, ApiClient>,
+    product_id: i32,
+    due_date: Option<String>,
+) -> Result<String, String> {
+    let due_date = normalize_due_date(due_date)?;
+    info!(
+        "Setting due date of product {product_id} to {}...",
+        due_date.as_deref().unwrap_or("none")
+    );
     let payload = json!({
-        "status": status,
+        "due_date": due_date,
     });
     api_client.patch(&format!("/products/{}", product_id), &payload).await
 }
 
+#[derive(Debug, Serialize)]
+pub struct ProductStatusUpdateResult {
+    pub product_id: i32,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkStatusUpdateSummary {
+    pub status: String,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<ProductStatusUpdateResult>,
+}
+
+/// Set the same status on many products at once. Updates run concurrently;
+/// failures are reported per product and nothing is rolled back.
 #[tauri::command(rename_all = "snake_case")]
+pub async fn bulk_update_product_status(
+    api_client: State<'_, ApiClient>,
+    product_ids: Vec<i32>,
+    status: String,
+) -> Result<BulkStatusUpdateSummary, String> {
+    bulk_set_status(&api_client, product_ids, status).await
+}
+
+async fn bulk_set_status(
+    api_client: &ApiClient,
+    product_ids: Vec<i32>,
+    status: String,
+) -> Result<BulkStatusUpdateSummary, String> {
+    let status = canonical_product_status(&status).map(String::from).ok_or_else(|| {
+        format!(
+            "Invalid product status '{}'. Expected one of: {}",
+            status.trim(),
+            PRODUCT_STATUSES.join(", ")
+        )
+    })?;
+    let mut product_ids = product_ids;
+    product_ids.sort_unstable();
+    product_ids.dedup();
+    info!("Updating {} product(s) to status {status}...", product_ids.len());
+
+    let changed_by = current_username(api_client).await;
+    let (status_ref, changed_by_ref) = (&status, &changed_by);
+    let results: Vec<ProductStatusUpdateResult> = stream::iter(product_ids)
+        .map(|product_id| async move {
+            match set_product_status(api_client, product_id, status_ref, changed_by_ref.clone()).await {
+                Ok(_) => ProductStatusUpdateResult { product_id, success: true, error: None },
+                Err(e) => {
+                    error!("Failed to update status of product {}: {}", product_id, e);
+                    ProductStatusUpdateResult { product_id, success: false, error: Some(e) }
+                }
+            }
+        })
+        .buffered(api_client.batch_concurrency(PRODUCT_BATCH_CONCURRENCY))
+        .collect()
+        .await;
+
+    let succeeded = results.iter().filter(|r| r.success).count();
+    info!("Bulk status update: {} succeeded, {} failed", succeeded, results.len() - succeeded);
+    Ok(BulkStatusUpdateSummary { failed: results.len() - succeeded, succeeded, status, results })
+}
+
+// Directory holding the product logs; `None` means `~/.elevation-manager`
+static PRODUCT_LOG_ROOT: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Override the product log root; `None` restores the default
+#[cfg(test)]
+pub(crate) fn set_product_log_root(dir: Option<PathBuf>) {
+    *PRODUCT_LOG_ROOT.write().unwrap_or_else(|e| e.into_inner()) = dir;
+}
+
+fn product_log_root() -> Result<PathBuf, String> {
+    if let Some(dir) = PRODUCT_LOG_ROOT.read().unwrap_or_else(|e| e.into_inner()).clone() {
+        return Ok(dir);
+    }
+    let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
+    Ok(home_dir.join(".elevation-manager"))
+}
+
+/// Local append-only per-product log, one JSON object per line:
+/// `~/.elevation-manager/{log_dir}/{product_id}.jsonl`.
+fn product_log_path(log_dir: &str, product_id: i64) -> Result<PathBuf, String> {
+    Ok(product_log_root()?.join(log_dir).join(format!("{}.jsonl", product_id)))
+}
+
+fn append_product_log<T: Serialize>(log_dir: &str, product_id: i64, entry: &T) -> Result<(), String> {
+    let path = product_log_path(log_dir, product_id)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create log directory: {}", e))?;
+    }
+    let line = serde_json::to_string(entry).map_err(|e| format!("Failed to serialize log entry: {}", e))?;
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open {}: {}", log_dir, e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write {}: {}", log_dir, e))
+}
+
+fn read_product_log<T: DeserializeOwned>(log_dir: &str, product_id: i64) -> Result<Vec<T>, String> {
+    let path = product_log_path(log_dir, product_id)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", log_dir, e))?;
+    Ok(contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                warn!("Skipping malformed {} entry for product {}: {}", log_dir, product_id, e);
+                None
+            }
+        })
+        .collect())
+}
+
+const STATUS_LOG_DIR: &str = "product-status-log";
+const ASSIGNMENT_LOG_DIR: &str = "assignment-log";
+
+fn append_status_log(product_id: i32, change: &StatusChange) -> Result<(), String> {
+    append_product_log(STATUS_LOG_DIR, product_id.into(), change)
+}
+
+fn read_status_log(product_id: i32) -> Result<Vec<StatusChange>, String> {
+    read_product_log(STATUS_LOG_DIR, product_id.into())
+}
+
+// Append to the local assignment ledger. Failures are logged, never returned:
+// the assignment change itself has already happened.
+fn record_assignment_event(product_id: i64, action: &str, assignment: &Value, recorded_by: Option<String>) {
+    let event = AssignmentEvent {
+        action: action.to_string(),
+        assignment_id: assignment["id"].as_i64(),
+        user_id: assignment["user_id"].as_i64(),
+        team_id: assignment["team_id"].as_i64(),
+        reason: assignment["reason"].as_str().map(String::from),
+        recorded_by,
+        recorded_at: chrono::Utc::now().to_rfc3339(),
+        source: "local".to_string(),
+    };
+    if let Err(e) = append_product_log(ASSIGNMENT_LOG_DIR, product_id, &event) {
+        warn!("Failed to record assignment history for product {}: {}", product_id, e);
+    }
+}
+
+// Server history entries may name the action and timestamp differently
+fn server_assignment_event(entry: &Value) -> Option<AssignmentEvent> {
+    let text = |keys: &[&str]| keys.iter().find_map(|key| entry[*key].as_str().map(String::from));
+    Some(AssignmentEvent {
+        action: text(&["action", "event"]).unwrap_or_else(|| "assigned".to_string()),
+        assignment_id: entry["assignment_id"].as_i64().or_else(|| entry["id"].as_i64()),
+        user_id: entry["user_id"].as_i64(),
+        team_id: entry["team_id"].as_i64(),
+        reason: text(&["reason"]),
+        recorded_by: text(&["recorded_by", "assigned_by"]),
+        recorded_at: text(&["recorded_at", "created_at", "assigned_at"])?,
+        source: "server".to_string(),
+    })
+}
+
+/// Who held a product and when, newest first: the local ledger written by
+/// checkouts, assignments and removals, merged with the backend's assignment
+/// history when it has one. Local entries the server also reports are dropped.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_assignment_history(
+    api_client: State<'_, ApiClient>,
+    product_id: i32,
+) -> Result<Vec<AssignmentEvent>, String> {
+    info!("Fetching assignment history for product {product_id}...");
+    let mut history: Vec<AssignmentEvent> =
+        match api_client.get_json::<Vec<Value>>(&format!("/products/{}/assignment-history", product_id)).await {
+            Ok(entries) => entries.iter().filter_map(server_assignment_event).collect(),
+            Err(e) => {
+                info!("No server assignment history for product {}: {}", product_id, e);
+                Vec::new()
+            }
+        };
+    let server_keys: HashSet<(String, Option<i64>)> = history
+        .iter()
+        .filter(|event| event.assignment_id.is_some())
+        .map(|event| (event.action.clone(), event.assignment_id))
+        .collect();
+    history.extend(
+        read_product_log::<AssignmentEvent>(ASSIGNMENT_LOG_DIR, product_id.into())?
+            .into_iter()
+            .filter(|event| !server_keys.contains(&(event.action.clone(), event.assignment_id))),
+    );
+    history.sort_by(|a, b| b.recorded_at.cmp(&a.recorded_at));
+    Ok(history)
+}
+
+// Minimal history from the dates recorded on the product itself. Only the
+// current status is known for `status_date`, so `from_status` is left empty.
+fn derive_status_history(product: &Value) -> Vec<StatusChange> {
+    let current_status = product["status"].as_str().unwrap_or("Unknown");
+    [
+        ("status_date", current_status),
+        ("acceptance_date", "Accepted"),
+        ("publish_date", "Published"),
+    ]
+    .into_iter()
+    .filter_map(|(field, status)| {
+        product[field].as_str().map(|date| StatusChange {
+            from_status: None,
+            to_status: status.to_string(),
+            changed_by: None,
+            changed_at: date.to_string(),
+        })
+    })
+    .collect()
+}
+
+/// Get the status history of a product, newest first.
+///
+/// Falls back to the local log kept by `update_product_status` when the
+/// backend has no status-history endpoint, and to the product's own
+/// status/acceptance/publish dates when there is no local log either.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_product_status_history(
+    api_client: State<'_, ApiClient>,
+    product_id: i32,
+) -> Result<Vec<StatusChange>, String> {
+    status_history(&api_client, product_id).await
+}
+
+async fn status_history(api_client: &ApiClient, product_id: i32) -> Result<Vec<StatusChange>, String> {
+    info!("Fetching status history for product {product_id}...");
+    let endpoint = format!("/products/{}/status-history", product_id);
+    let mut history = match api_client.execute(Method::GET, &endpoint, None::<&()>).await {
+        Ok(response_text) => {
+            let response_value: Value = serde_json::from_str(&response_text)
+                .map_err(|e| format!("Failed to parse response: {}", e))?;
+            serde_json::from_value::<Vec<StatusChange>>(response_value["data"].clone())
+                .map_err(|e| format!("Failed to parse status history: {}", e))?
+        }
+        Err(e) if e.status() == Some(StatusCode::NOT_FOUND) => {
+            info!("Status history endpoint not found, falling back to local log");
+            let logged = read_status_log(product_id)?;
+            if logged.is_empty() {
+                info!("No local status log, deriving history from product dates");
+                let product: Value = api_client.get_json(&format!("/products/{}", product_id)).await?;
+                derive_status_history(product_record(&product))
+            } else {
+                logged
+            }
+        }
+        Err(e) => return Err(e.into()),
+    };
+    history.sort_by(|a, b| b.changed_at.cmp(&a.changed_at));
+    Ok(history)
+}
+
+/// Parse an SRID out of a coordinate system label such as `EPSG:4326` or `WGS84`.
+fn srid_from_coordinate_system(coordinate_system: &str) -> Option<i32> {
+    let label = coordinate_system.trim().to_ascii_uppercase();
+    if matches!(label.as_str(), "WGS84" | "WGS 84" | "CRS84") {
+        return Some(4326);
+    }
+    label
+        .strip_prefix("EPSG:")
+        .or_else(|| label.strip_prefix("URN:OGC:DEF:CRS:EPSG::"))
+        .and_then(|code| code.parse().ok())
+}
+
+/// Validate a single GeoJSON position; geographic SRIDs also get a range check.
+fn validate_position(position: &Value, path: &str, geographic: bool) -> Result<(), String> {
+    let coords = position
+        .as_array()
+        .ok_or_else(|| format!("{path} must be a coordinate array, got {position}"))?;
+    if coords.len() < 2 || coords.len() > 3 {
+        return Err(format!("{path} must have 2 or 3 values, got {}", coords.len()));
+    }
+    let mut values = Vec::with_capacity(coords.len());
+    for (i, c) in coords.iter().enumerate() {
+        match c.as_f64() {
+            Some(v) if v.is_finite() => values.push(v),
+            _ => return Err(format!("{path}[{i}] is not a finite number: {c}")),
+        }
+    }
+    if geographic && (!(-180.0..=180.0).contains(&values[0]) || !(-90.0..=90.0).contains(&values[1])) {
+        return Err(format!(
+            "{path} [{}, {}] is outside longitude/latitude bounds; check the SRID",
+            values[0], values[1]
+        ));
+    }
+    Ok(())
+}
+
+fn validate_positions(value: &Value, path: &str, min: usize, geographic: bool) -> Result<usize, String> {
+    let positions = value
+        .as_array()
+        .ok_or_else(|| format!("{path} must be an array of positions"))?;
+    if positions.len() < min {
+        return Err(format!("{path} needs at least {min} positions, got {}", positions.len()));
+    }
+    for (i, position) in positions.iter().enumerate() {
+        validate_position(position, &format!("{path}[{i}]"), geographic)?;
+    }
+    Ok(positions.len())
+}
+
+fn validate_polygon(value: &Value, path: &str, geographic: bool) -> Result<(), String> {
+    let rings = value
+        .as_array()
+        .ok_or_else(|| format!("{path} must be an array of linear rings"))?;
+    if rings.is_empty() {
+        return Err(format!("{path} must contain at least one ring"));
+    }
+    for (i, ring) in rings.iter().enumerate() {
+        let ring_path = format!("{path}[{i}]");
+        let len = validate_positions(ring, &ring_path, 4, geographic)?;
+        let first = &ring[0];
+        let last = &ring[len - 1];
+        if first != last {
+            return Err(format!("{ring_path} is not closed: first position {first} != last position {last}"));
+        }
+    }
+    Ok(())
+}
+
+fn validate_multi<F>(value: &Value, path: &str, validate: F) -> Result<(), String>
+where
+    F: Fn(&Value, &str) -> Result<(), String>,
+{
+    let members = value
+        .as_array()
+        .ok_or_else(|| format!("{path} must be an array"))?;
+    for (i, member) in members.iter().enumerate() {
+        validate(member, &format!("{path}[{i}]"))?;
+    }
+    Ok(())
+}
+
+fn validate_geometry_at(geometry: &Value, path: &str, geographic: bool) -> Result<(), String> {
+    let geometry_type = geometry
+        .get("type")
+        .and_then(|t| t.as_str())
+        .ok_or_else(|| format!("{path} is not a GeoJSON geometry: missing \"type\""))?;
+
+    if geometry_type == "GeometryCollection" {
+        let geometries = geometry["geometries"]
+            .as_array()
+            .ok_or_else(|| format!("{path}.geometries must be an array"))?;
+        for (i, member) in geometries.iter().enumerate() {
+            validate_geometry_at(member, &format!("{path}.geometries[{i}]"), geographic)?;
+        }
+        return Ok(());
+    }
+
+    let coordinates = geometry
+        .get("coordinates")
+        .ok_or_else(|| format!("{path} ({geometry_type}) is missing \"coordinates\""))?;
+    let coords_path = format!("{path}.coordinates");
+    match geometry_type {
+        "Point" => validate_position(coordinates, &coords_path, geographic),
+        "MultiPoint" => validate_positions(coordinates, &coords_path, 1, geographic).map(|_| ()),
+        "LineString" => validate_positions(coordinates, &coords_path, 2, geographic).map(|_| ()),
+        "MultiLineString" => validate_multi(coordinates, &coords_path, |line, p| {
+            validate_positions(line, p, 2, geographic).map(|_| ())
+        }),
+        "Polygon" => validate_polygon(coordinates, &coords_path, geographic),
+        "MultiPolygon" => validate_multi(coordinates, &coords_path, |polygon, p| {
+            validate_polygon(polygon, p, geographic)
+        }),
+        other => Err(format!("{path} has unrecognized GeoJSON type \"{other}\"")),
+    }
+}
+
+/// Validate a product geometry client-side before it is sent as `geom`.
+///
+/// Checks the GeoJSON structure, polygon ring closure, and that `srid` agrees
+/// with `coordinate_system` when both are given.
+fn validate_product_geometry(
+    geometry: &Value,
+    srid: Option<i32>,
+    coordinate_system: Option<&str>,
+) -> Result<(), String> {
+    let declared_srid = coordinate_system.and_then(srid_from_coordinate_system);
+    if let (Some(srid), Some(declared)) = (srid, declared_srid) {
+        if srid != declared {
+            return Err(format!(
+                "SRID {} does not match coordinate system {}",
+                srid,
+                coordinate_system.unwrap_or_default()
+            ));
+        }
+    }
+    let geographic = srid.or(declared_srid) == Some(4326);
+    validate_geometry_at(geometry, "geometry", geographic)
+        .map_err(|e| format!("Invalid geometry: {}", e))
+}
+
+#[tauri::command(rename_all = "snake_case")]
+#[allow(clippy::too_many_arguments)]
 pub async fn create_product(
     api_client: State<'_, ApiClient>,
     item_id: String,
@@ -146,6 +1319,9 @@ pub async fn create_product(
     srid: Option<i32>,
 ) -> Result<String, String> {
     info!("Creating product {site_id}/{item_id}...");
+    if let Some(geometry) = &geometry {
+        validate_product_geometry(geometry, srid, coordinate_system.as_deref())?;
+    }
     // Map frontend geometry -> backend geom and pass through other fields.
     let payload = json!({
         "taskorder_id": taskorder_id,
@@ -164,7 +1340,7 @@ pub async fn create_product(
         "srid": srid,
         "coordinate_system": coordinate_system,
     });
-    api_client.post("/products", &payload).await
+    api_client.post_idempotent("/products", &payload).await
 }
 
 #[tauri::command(rename_all = "snake_case")]
@@ -178,5 +1354,645 @@ pub async fn create_product_type(
         "name": name,
         "acronym": acronym,
     });
-    api_client.post("/product_types", &payload).await
-}
\ No newline at end of file
+    let response = api_client.post("/product_types", &payload).await?;
+    api_client.invalidate_cache("/product_types");
+    Ok(response)
+}
+
+/// Columns accepted in a product CSV, matching the fields of `ProductCsvRow`
+const PRODUCT_CSV_COLUMNS: [&str; 11] = [
+    "item_id",
+    "site_id",
+    "product_type_id",
+    "status",
+    "status_date",
+    "taskorder_id",
+    "file_path",
+    "s2_index",
+    "classification",
+    "coordinate_system",
+    "srid",
+];
+const REQUIRED_PRODUCT_CSV_COLUMNS: [&str; 3] = ["item_id", "site_id", "status"];
+
+// Reject a header row with unknown, duplicate or missing columns, so a typo
+// doesn't silently turn into empty values on every row
+fn validate_product_csv_headers(headers: &csv::StringRecord) -> Result<(), String> {
+    let mut seen = HashSet::new();
+    for column in headers.iter() {
+        if !PRODUCT_CSV_COLUMNS.contains(&column) {
+            return Err(format!(
+                "Unknown CSV column '{}'. Expected columns: {}",
+                column,
+                PRODUCT_CSV_COLUMNS.join(", ")
+            ));
+        }
+        if !seen.insert(column) {
+            return Err(format!("Duplicate CSV column '{}'", column));
+        }
+    }
+    let missing: Vec<&str> = REQUIRED_PRODUCT_CSV_COLUMNS
+        .into_iter()
+        .filter(|column| !seen.contains(column))
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!("Missing required CSV column(s): {}", missing.join(", ")));
+    }
+    Ok(())
+}
+
+/// Validate a CSV row and turn it into a `/products` payload.
+fn product_payload_from_row(
+    row: ProductCsvRow,
+    default_product_type_id: Option<i32>,
+    taskorder_id: Option<i32>,
+) -> Result<Value, String> {
+    let non_empty = |value: Option<String>| value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+
+    let item_id = non_empty(row.item_id).ok_or("Missing item_id")?;
+    let site_id = non_empty(row.site_id).ok_or("Missing site_id")?;
+    let status = non_empty(row.status).ok_or("Missing status")?;
+    let product_type_id = row
+        .product_type_id
+        .or(default_product_type_id)
+        .ok_or("Missing product_type_id and no default was provided")?;
+
+    Ok(json!({
+        "taskorder_id": row.taskorder_id.or(taskorder_id),
+        "item_id": item_id,
+        "site_id": site_id,
+        "product_type_id": product_type_id,
+        "status": status,
+        "status_date": non_empty(row.status_date),
+        "acceptance_date": null,
+        "publish_date": null,
+        "file_path": non_empty(row.file_path),
+        "s2_index": non_empty(row.s2_index),
+        "geom": null,
+        "classification": non_empty(row.classification),
+        "srid": row.srid,
+        "coordinate_system": non_empty(row.coordinate_system),
+    }))
+}
+
+/// Rows of a product CSV, split into `/products` payloads (with their line
+/// numbers) and results for the rows that failed validation
+struct ParsedProductCsv {
+    payloads: Vec<(u64, Value)>,
+    invalid: Vec<ProductImportResult>,
+    blank_rows: usize,
+}
+
+fn read_product_csv(
+    file_path: &str,
+    default_product_type_id: Option<i32>,
+    taskorder_id: Option<i32>,
+) -> Result<ParsedProductCsv, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_path(file_path)
+        .map_err(|e| format!("Failed to open CSV file: {}", e))?;
+    let headers = reader
+        .headers()
+        .map_err(|e| format!("Failed to read CSV headers: {}", e))?
+        .clone();
+    validate_product_csv_headers(&headers)?;
+
+    let mut parsed = ParsedProductCsv { payloads: Vec::new(), invalid: Vec::new(), blank_rows: 0 };
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                let line = e.position().map(|p| p.line()).unwrap_or(0);
+                parsed.invalid.push(ProductImportResult { line, product_id: None, error: Some(e.to_string()) });
+                continue;
+            }
+        };
+        if record.iter().all(str::is_empty) {
+            parsed.blank_rows += 1;
+            continue;
+        }
+        let line = record.position().map(|p| p.line()).unwrap_or(0);
+        let payload = record
+            .deserialize::<ProductCsvRow>(Some(&headers))
+            .map_err(|e| e.to_string())
+            .and_then(|row| product_payload_from_row(row, default_product_type_id, taskorder_id));
+        match payload {
+            Ok(payload) => parsed.payloads.push((line, payload)),
+            Err(e) => parsed.invalid.push(ProductImportResult { line, product_id: None, error: Some(e) }),
+        }
+    }
+    Ok(parsed)
+}
+
+/// Create products in bulk from a CSV file whose headers match `create_product`.
+///
+/// The whole file is rejected if its header has unknown, duplicate or missing
+/// columns. Rows are validated up front and then created concurrently. Every
+/// non-blank row gets a result entry; with `stop_on_error` the import halts at
+/// the first failure.
+/// Progress is emitted as `operation_progress`, and the import can be stopped
+/// with `cancel_operation(operation_id)`.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn import_products_csv(
+    window: Window,
+    api_client: State<'_, ApiClient>,
+    file_path: String,
+    default_product_type_id: Option<i32>,
+    taskorder_id: Option<i32>,
+    stop_on_error: Option<bool>,
+    operation_id: Option<String>,
+) -> Result<ProductImportSummary, String> {
+    info!("Importing products from CSV {file_path}...");
+    let stop_on_error = stop_on_error.unwrap_or(false);
+    let operation_id = operation_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let operations = window.state::<Arc<OperationRegistry>>();
+    let operation = operations.register(&operation_id);
+
+    let ParsedProductCsv { payloads, invalid: mut results, blank_rows } =
+        read_product_csv(&file_path, default_product_type_id, taskorder_id)?;
+
+    if stop_on_error && !results.is_empty() {
+        warn!("CSV validation failed for {} row(s); nothing was created", results.len());
+        return Ok(ProductImportSummary::new(results, blank_rows + payloads.len(), false));
+    }
+
+    let total = payloads.len();
+    let mut completed = 0;
+    let mut cancelled = false;
+    let api_client = api_client.inner();
+    let mut creations = stream::iter(payloads)
+        .map(|(line, payload)| async move {
+            let outcome = api_client.post_idempotent("/products", &payload).await.and_then(|response| {
+                let value: Value = serde_json::from_str(&response)
+                    .map_err(|e| format!("Failed to parse response: {}", e))?;
+                value["data"]
+                    .as_i64()
+                    .or_else(|| value["data"]["id"].as_i64())
+                    .ok_or_else(|| "Failed to extract product ID from response".to_string())
+            });
+            (line, outcome)
+        })
+        .buffer_unordered(api_client.batch_concurrency(PRODUCT_BATCH_CONCURRENCY));
+
+    loop {
+        // Requests still in flight when cancelled are dropped; the backend
+        // may or may not have created those products.
+        let next = tokio::select! {
+            biased;
+            _ = operation.token.cancelled() => {
+                warn!("CSV import cancelled after {} of {} row(s)", completed, total);
+                cancelled = true;
+                break;
+            }
+            next = creations.next() => next,
+        };
+        let Some((line, outcome)) = next else {
+            break;
+        };
+        completed += 1;
+        let _ = window.emit(
+            "operation_progress",
+            OperationProgress { operation_id: operation_id.clone(), completed, total },
+        );
+        match outcome {
+            Ok(product_id) => results.push(ProductImportResult { line, product_id: Some(product_id), error: None }),
+            Err(e) => {
+                error!("Failed to import CSV line {}: {}", line, e);
+                results.push(ProductImportResult { line, product_id: None, error: Some(e) });
+                if stop_on_error {
+                    break;
+                }
+            }
+        }
+    }
+
+    let summary = ProductImportSummary::new(results, blank_rows + total - completed, cancelled);
+    info!(
+        "CSV import finished: {} created, {} skipped, {} failed",
+        summary.created, summary.skipped, summary.failed
+    );
+    Ok(summary)
+}
+
+/// Export product footprints as a GeoJSON `FeatureCollection` for QGIS.
+///
+/// Products without a geometry, or whose SRID differs from the first exported
+/// product, are skipped and reported in the returned warnings.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn export_products_geojson(
+    api_client: State<'_, ApiClient>,
+    product_ids: Vec<i32>,
+    output_path: String,
+) -> Result<GeoJsonExportResult, String> {
+    info!("Exporting {} product(s) to GeoJSON at {output_path}...", product_ids.len());
+
+    let api_client = api_client.inner();
+    let details: Vec<(i32, Result<String, String>)> = stream::iter(product_ids)
+        .map(|product_id| async move {
+            (product_id, api_client.get(&format!("/products/{}", product_id)).await)
+        })
+        .buffered(api_client.batch_concurrency(PRODUCT_BATCH_CONCURRENCY))
+        .collect()
+        .await;
+
+    let mut features = Vec::new();
+    let mut warnings = Vec::new();
+    let mut collection_srid: Option<i64> = None;
+    for (product_id, response) in details {
+        let product = match response.and_then(|text| {
+            serde_json::from_str::<Value>(&text).map_err(|e| format!("Failed to parse response: {}", e))
+        }) {
+            Ok(value) => value["data"]["product"].clone(),
+            Err(e) => {
+                warnings.push(format!("Product {}: {}", product_id, e));
+                continue;
+            }
+        };
+
+        // The backend may hand geometry back as a GeoJSON string.
+        let geometry = match &product["geom"] {
+            Value::String(text) => serde_json::from_str::<Value>(text).unwrap_or(Value::Null),
+            other => other.clone(),
+        };
+        if !geometry.is_object() {
+            warnings.push(format!("Product {}: no geometry, skipped", product_id));
+            continue;
+        }
+
+        let srid = product["srid"].as_i64().unwrap_or(4326);
+        match collection_srid {
+            None => collection_srid = Some(srid),
+            Some(expected) if expected != srid => {
+                warnings.push(format!(
+                    "Product {}: SRID {} differs from collection SRID {}, skipped",
+                    product_id, srid, expected
+                ));
+                continue;
+            }
+            Some(_) => {}
+        }
+
+        features.push(json!({
+            "type": "Feature",
+            "id": product_id,
+            "geometry": geometry,
+            "properties": {
+                "product_id": product_id,
+                "item_id": product["item_id"],
+                "site_id": product["site_id"],
+                "status": product["status"],
+                "classification": product["classification"],
+            },
+        }));
+    }
+
+    let feature_count = features.len();
+    let mut collection = json!({
+        "type": "FeatureCollection",
+        "features": features,
+    });
+    if let Some(srid) = collection_srid.filter(|srid| *srid != 4326) {
+        collection["crs"] = json!({
+            "type": "name",
+            "properties": { "name": format!("urn:ogc:def:crs:EPSG::{}", srid) },
+        });
+    }
+
+    let path = std::path::Path::new(&output_path);
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create output directory: {}", e))?;
+    }
+    let contents = serde_json::to_string_pretty(&collection)
+        .map_err(|e| format!("Failed to serialize GeoJSON: {}", e))?;
+    fs::write(path, contents).map_err(|e| format!("Failed to write GeoJSON file: {}", e))?;
+
+    for warning in &warnings {
+        warn!("GeoJSON export: {}", warning);
+    }
+    info!("Exported {} feature(s) to {}", feature_count, output_path);
+    Ok(GeoJsonExportResult { output_path, feature_count, warnings })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{mock_server_with, temp_dir, test_client, MockResponse, ProductLogRootOverride};
+
+    // Serves `total` products, honouring `page` and `limit`; `failing_page` answers 500
+    async fn paginated_products(total: usize, failing_page: Option<usize>) -> crate::test_support::MockServer {
+        mock_server_with(move |request| {
+            let query = request.path.split_once('?').map_or("", |(_, q)| q);
+            let param = |name: &str| {
+                query
+                    .split('&')
+                    .find_map(|pair| pair.strip_prefix(name)?.strip_prefix('='))
+                    .and_then(|v| v.parse::<usize>().ok())
+                    .unwrap_or(1)
+            };
+            let (page, limit) = (param("page"), param("limit"));
+            if Some(page) == failing_page {
+                return MockResponse::json(500, r#"{"message":"boom"}"#);
+            }
+            let items: Vec<Value> = ((page - 1) * limit..(page * limit).min(total)).map(|id| json!({ "id": id })).collect();
+            MockResponse::json(200, json!({ "data": { "products": items, "total": total } }).to_string())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn every_page_is_handed_over_as_a_chunk() {
+        let server = paginated_products(25, None).await;
+        let api_client = test_client(&server.base_url).await;
+        let mut chunks = Vec::new();
+
+        let complete = fetch_all_product_pages(&api_client, 10, |chunk| chunks.push((chunk.page, chunk.items.len())))
+            .await
+            .unwrap();
+
+        assert_eq!(chunks, [(1, 10), (2, 10), (3, 5)]);
+        assert_eq!((complete.total, complete.pages), (25, 3));
+    }
+
+    #[tokio::test]
+    async fn a_failed_page_stops_the_stream() {
+        let server = paginated_products(25, Some(2)).await;
+        let api_client = test_client(&server.base_url).await;
+        let mut chunks = 0;
+
+        let failure = fetch_all_product_pages(&api_client, 10, |_| chunks += 1).await.unwrap_err();
+
+        assert_eq!(chunks, 1);
+        assert_eq!((failure.page, failure.pages_completed), (2, 1));
+    }
+
+    #[test]
+    fn duplicates_are_grouped_by_trimmed_site_and_item() {
+        let products = vec![
+            json!({ "id": 1, "site_id": "S1", "item_id": "A" }),
+            json!({ "id": 2, "site_id": " S1 ", "item_id": "A" }),
+            json!({ "id": 3, "site_id": "S1", "item_id": "B" }),
+            json!({ "id": 4, "site_id": "S2", "item_id": "A" }),
+            json!({ "id": 5, "site_id": "S2", "item_id": "A" }),
+            json!({ "id": 6, "site_id": "S2", "item_id": "A" }),
+            json!({ "id": 7, "site_id": "", "item_id": "A" }),
+            json!({ "id": 8, "item_id": "A" }),
+        ];
+
+        let groups = group_duplicate_products(products);
+
+        let summary: Vec<(&str, &str, Vec<i64>)> = groups
+            .iter()
+            .map(|g| (g.site_id.as_str(), g.item_id.as_str(), g.products.iter().filter_map(|p| p["id"].as_i64()).collect()))
+            .collect();
+        assert_eq!(summary, [("S1", "A", vec![1, 2]), ("S2", "A", vec![4, 5, 6])]);
+    }
+
+    fn product_csv(contents: &str) -> (PathBuf, String) {
+        let dir = temp_dir();
+        let path = dir.join("products.csv");
+        fs::write(&path, contents).unwrap();
+        (dir, path.display().to_string())
+    }
+
+    #[test]
+    fn csv_rows_are_validated_individually() {
+        let (dir, path) = product_csv("item_id,site_id,status,product_type_id\nA-1,SITE1,Created,\n,,,\nA-2,,Created,4\n");
+
+        let parsed = read_product_csv(&path, Some(2), Some(9));
+
+        let _ = fs::remove_dir_all(&dir);
+        let parsed = parsed.unwrap();
+        assert_eq!(parsed.blank_rows, 1);
+        assert_eq!(parsed.payloads.len(), 1);
+        let (line, payload) = &parsed.payloads[0];
+        assert_eq!(*line, 2);
+        assert_eq!((payload["item_id"].as_str(), payload["product_type_id"].as_i64()), (Some("A-1"), Some(2)));
+        assert_eq!(payload["taskorder_id"], 9);
+        assert_eq!(parsed.invalid.len(), 1);
+        assert_eq!(parsed.invalid[0].line, 4);
+        assert_eq!(parsed.invalid[0].error.as_deref(), Some("Missing site_id"));
+    }
+
+    #[test]
+    fn csv_with_an_unknown_column_is_rejected() {
+        let (dir, path) = product_csv("item_id,site_id,status,colour\nA-1,SITE1,Created,red\n");
+
+        let result = read_product_csv(&path, Some(2), None);
+
+        let _ = fs::remove_dir_all(&dir);
+        assert!(result.err().is_some_and(|e| e.starts_with("Unknown CSV column 'colour'")));
+    }
+
+    #[tokio::test]
+    async fn bulk_status_update_reports_each_product() {
+        // Successful updates write to the status log
+        let logs = ProductLogRootOverride::temp().await;
+        let (ok_a, failing, ok_b) = (1, 2, 3);
+        let server = mock_server_with(move |request| match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/users/me") => MockResponse::json(200, r#"{"data":{"username":"ana"}}"#),
+            ("GET", _) => MockResponse::json(200, r#"{"data":{"status":"In Progress"}}"#),
+            ("PATCH", path) if path == format!("/products/{}", failing) => {
+                MockResponse::json(409, r#"{"message":"Product is locked"}"#)
+            }
+            _ => MockResponse::json(200, r#"{"data":{}}"#),
+        })
+        .await;
+        let api_client = test_client(&server.base_url).await;
+
+        let summary = bulk_set_status(&api_client, vec![ok_b, failing, ok_a, ok_b], " Completed ".to_string())
+            .await
+            .unwrap();
+
+        assert_eq!((summary.succeeded, summary.failed), (2, 1));
+        assert_eq!(summary.status, "Completed");
+        let outcomes: Vec<(i32, bool)> = summary.results.iter().map(|r| (r.product_id, r.success)).collect();
+        assert_eq!(outcomes, [(ok_a, true), (failing, false), (ok_b, true)]);
+        assert!(summary.results[1].error.as_deref().is_some_and(|e| e.contains("Product is locked")));
+        let logged: Vec<i32> = [ok_a, failing, ok_b]
+            .into_iter()
+            .filter(|id| logs.path().join(STATUS_LOG_DIR).join(format!("{}.jsonl", id)).exists())
+            .collect();
+        assert_eq!(logged, [ok_a, ok_b]);
+    }
+
+    #[tokio::test]
+    async fn bulk_status_update_rejects_unknown_statuses_without_a_request() {
+        let server = mock_server_with(|_| MockResponse::json(200, r#"{"data":{}}"#)).await;
+        let api_client = test_client(&server.base_url).await;
+
+        assert!(bulk_set_status(&api_client, vec![1, 2], "Shipped".to_string()).await.is_err());
+        assert!(server.requests.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn review_status_is_stored_in_the_backend_spelling() {
+        assert_eq!(canonical_product_status(" InReview "), Some("In Review"));
+        assert_eq!(canonical_product_status("In Review"), Some("In Review"));
+        assert_eq!(canonical_product_status("in review"), None);
+    }
+
+    #[tokio::test]
+    async fn status_history_comes_from_the_endpoint_when_there_is_one() {
+        let server = mock_server_with(|_| {
+            let history = json!([
+                { "from_status": null, "to_status": "Draft", "changed_by": "ana", "changed_at": "2024-01-01T00:00:00Z" },
+                { "from_status": "Draft", "to_status": "Accepted", "changed_by": "ben", "changed_at": "2024-03-01T00:00:00Z" },
+            ]);
+            MockResponse::json(200, json!({ "data": history }).to_string())
+        })
+        .await;
+        let api_client = test_client(&server.base_url).await;
+
+        let history = status_history(&api_client, 41).await.unwrap();
+
+        let statuses: Vec<&str> = history.iter().map(|c| c.to_status.as_str()).collect();
+        assert_eq!(statuses, ["Accepted", "Draft"]);
+        assert_eq!(*server.requests.lock().unwrap(), ["GET /products/41/status-history"]);
+    }
+
+    #[tokio::test]
+    async fn status_history_is_derived_from_product_dates_without_an_endpoint() {
+        // No local status log exists in a fresh log root
+        let _logs = ProductLogRootOverride::temp().await;
+        let product_id = 7;
+        let server = mock_server_with(move |request| match request.path.ends_with("/status-history") {
+            true => MockResponse::json(404, r#"{"message":"Not found"}"#),
+            false => MockResponse::json(
+                200,
+                json!({ "data": {
+                    "id": product_id,
+                    "status": "In Review",
+                    "status_date": "2024-02-01",
+                    "acceptance_date": "2024-03-01",
+                } })
+                .to_string(),
+            ),
+        })
+        .await;
+        let api_client = test_client(&server.base_url).await;
+
+        let history = status_history(&api_client, product_id).await.unwrap();
+
+        let changes: Vec<(&str, &str)> = history.iter().map(|c| (c.to_status.as_str(), c.changed_at.as_str())).collect();
+        assert_eq!(changes, [("Accepted", "2024-03-01"), ("In Review", "2024-02-01")]);
+        assert!(history.iter().all(|c| c.from_status.is_none()));
+        assert_eq!(server.requests.lock().unwrap()[1], format!("GET /products/{}", product_id));
+    }
+
+    #[test]
+    fn valid_polygon_passes_geometry_validation() {
+        let polygon = json!({
+            "type": "Polygon",
+            "coordinates": [[[-77.0, 38.0], [-76.0, 38.0], [-76.0, 39.0], [-77.0, 38.0]]],
+        });
+        assert_eq!(validate_product_geometry(&polygon, Some(4326), Some("EPSG:4326")), Ok(()));
+    }
+
+    #[test]
+    fn unclosed_ring_is_reported_by_position() {
+        let polygon = json!({
+            "type": "Polygon",
+            "coordinates": [[[-77.0, 38.0], [-76.0, 38.0], [-76.0, 39.0], [-77.0, 39.0]]],
+        });
+        let error = validate_product_geometry(&polygon, None, None).unwrap_err();
+        assert!(error.contains("geometry.coordinates[0] is not closed"), "{error}");
+    }
+
+    #[test]
+    fn non_geojson_blob_is_rejected() {
+        let blob = json!({ "shape": "square", "size": 4 });
+        let error = validate_product_geometry(&blob, None, None).unwrap_err();
+        assert!(error.contains("missing \"type\""), "{error}");
+    }
+
+    #[tokio::test]
+    async fn assignments_are_transferred_and_failures_reported() {
+        let _logs = ProductLogRootOverride::temp().await;
+        let server = mock_server_with(|request| match (request.method.as_str(), request.path.as_str()) {
+            ("GET", _) => {
+                let assignments = json!([
+                    { "id": 1, "product_id": 10, "user_id": 5, "team_id": 2 },
+                    { "id": 2, "product_id": 11, "user_id": 5, "team_id": 2 },
+                    { "id": 3, "product_id": 12, "user_id": 5, "team_id": 2 },
+                ]);
+                MockResponse::json(200, json!({ "data": assignments }).to_string())
+            }
+            ("POST", _) if request.body.contains(r#""product_id":11"#) => {
+                MockResponse::json(422, r#"{"message":"Product is archived"}"#)
+            }
+            ("POST", _) => MockResponse::json(201, r#"{"data":{"id":100}}"#),
+            _ => MockResponse::json(200, r#"{"data":{}}"#),
+        })
+        .await;
+        let api_client = test_client(&server.base_url).await;
+
+        let summary = transfer_all_assignments(&api_client, 5, 6, None).await.unwrap();
+
+        assert_eq!(summary.transferred, 2);
+        assert_eq!(summary.failed.len(), 1);
+        assert_eq!((summary.failed[0].assignment_id, summary.failed[0].product_id), (2, Some(11)));
+        assert!(summary.failed[0].error.contains("Product is archived"));
+        let mut requests = server.requests.lock().unwrap().clone();
+        requests.sort();
+        assert_eq!(
+            requests,
+            [
+                "DELETE /product-assignments/1",
+                "DELETE /product-assignments/3",
+                "GET /product-assignments?user_id=5",
+                "POST /product-assignments",
+                "POST /product-assignments",
+                "POST /product-assignments",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn product_left_with_both_users_is_reported_as_such() {
+        let _logs = ProductLogRootOverride::temp().await;
+        let server = mock_server_with(|request| match (request.method.as_str(), request.path.as_str()) {
+            ("GET", _) => {
+                let assignments = json!([
+                    { "id": 1, "product_id": 10, "user_id": 5, "status": "active" },
+                    { "id": 2, "product_id": 11, "user_id": 5, "status": "active" },
+                    { "id": 3, "product_id": 12, "user_id": 5, "status": "active" },
+                ]);
+                MockResponse::json(200, json!({ "data": assignments }).to_string())
+            }
+            ("POST", _) if request.body.contains(r#""product_id":11"#) => {
+                MockResponse::json(422, r#"{"message":"Product is archived"}"#)
+            }
+            ("POST", _) => MockResponse::json(201, r#"{"data":{"id":100}}"#),
+            ("DELETE", "/product-assignments/3") => MockResponse::json(409, r#"{"message":"Assignment is locked"}"#),
+            _ => MockResponse::json(200, r#"{"data":{}}"#),
+        })
+        .await;
+        let api_client = test_client(&server.base_url).await;
+
+        let summary = reassign_products(&api_client, 5, 6, None).await.unwrap();
+
+        assert_eq!((summary.moved, summary.failed), (1, 2));
+        let outcomes: Vec<(Option<i64>, bool, bool)> =
+            summary.results.iter().map(|r| (r.product_id, r.error.is_some(), r.assigned_to_both)).collect();
+        assert_eq!(outcomes, [(Some(10), false, false), (Some(11), true, false), (Some(12), true, true)]);
+    }
+
+    #[test]
+    fn due_dates_are_validated_and_normalized() {
+        assert_eq!(
+            normalize_due_date(Some("2025-06-30T12:00:00-05:00".to_string())),
+            Ok(Some("2025-06-30T17:00:00+00:00".to_string()))
+        );
+        assert_eq!(normalize_due_date(None), Ok(None));
+        let error = normalize_due_date(Some("30/06/2025".to_string())).unwrap_err();
+        assert!(error.starts_with("Invalid due date '30/06/2025'"), "{error}");
+    }
+
+    #[test]
+    fn srid_must_match_coordinate_system() {
+        let point = json!({ "type": "Point", "coordinates": [-77.0, 38.0] });
+        assert!(validate_product_geometry(&point, Some(3857), Some("EPSG:4326")).is_err());
+    }
+}
@@ -0,0 +1,104 @@
+use crate::auth::login::AuthState;
+use crate::commands::notifications::{restart_notification_polling, PollingState};
+use crate::services::{api_client::ApiClient, profiles};
+use log::info;
+use serde::Serialize;
+use std::sync::Arc;
+use tauri::{State, Window};
+
+#[derive(Debug, Serialize)]
+pub struct ProfileSummary {
+    pub name: String,
+    pub api_base_url: String,
+    pub active: bool,
+}
+
+/// List saved profiles. Tokens are never returned to the frontend.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn list_profiles(auth_state: State<'_, AuthState>) -> Result<Vec<ProfileSummary>, String> {
+    let active = auth_state.profile.read().await.clone();
+    Ok(profile_summaries(profiles::list_profiles()?, active.as_deref()))
+}
+
+// Only the profile the session came from is active, even when several
+// profiles share its server
+fn profile_summaries(saved: Vec<(String, String)>, active: Option<&str>) -> Vec<ProfileSummary> {
+    saved
+        .into_iter()
+        .map(|(name, api_base_url)| ProfileSummary {
+            active: active == Some(name.as_str()),
+            name,
+            api_base_url,
+        })
+        .collect()
+}
+
+/// Save the current server and session token under `name`.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn save_current_as_profile(
+    auth_state: State<'_, AuthState>,
+    api_client: State<'_, ApiClient>,
+    name: String,
+) -> Result<(), String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+    let token = auth_state
+        .token
+        .read()
+        .await
+        .clone()
+        .ok_or("Log in before saving a profile")?;
+    profiles::save_profile(&profiles::Profile {
+        name: name.clone(),
+        api_base_url: api_client.base_url(),
+        token,
+    })?;
+    *auth_state.profile.write().await = Some(name.clone());
+    info!("Saved profile '{}'", name);
+    Ok(())
+}
+
+/// Switch the app to a saved profile's server and session, restarting
+/// notification polling against the new server if it was running.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn switch_profile(
+    window: Window,
+    auth_state: State<'_, AuthState>,
+    api_client: State<'_, ApiClient>,
+    polling_state: State<'_, Arc<PollingState>>,
+    name: String,
+) -> Result<ProfileSummary, String> {
+    let profile = profiles::load_profile(&name)?;
+    info!("Switching to profile '{}' ({})", profile.name, profile.api_base_url);
+    api_client
+        .switch_session(profile.api_base_url.clone(), Some(profile.token))
+        .await;
+    *auth_state.profile.write().await = Some(profile.name.clone());
+    restart_notification_polling(window, &api_client, &polling_state).await;
+    Ok(ProfileSummary {
+        name: profile.name,
+        api_base_url: profile.api_base_url,
+        active: true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_active_profile_is_marked_when_accounts_share_a_server() {
+        let saved = vec![
+            ("alice".to_string(), "https://elevation.example".to_string()),
+            ("bob".to_string(), "https://elevation.example".to_string()),
+        ];
+
+        let summaries = profile_summaries(saved.clone(), Some("bob"));
+        let active: Vec<(&str, bool)> = summaries.iter().map(|p| (p.name.as_str(), p.active)).collect();
+        assert_eq!(active, [("alice", false), ("bob", true)]);
+
+        assert!(profile_summaries(saved, None).iter().all(|p| !p.active));
+    }
+}
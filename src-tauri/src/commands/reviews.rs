@@ -1,13 +1,24 @@
 // src-tauri/src/commands/reviews.rs
-use crate::auth::login::AuthState;
-use crate::utils::get_auth_header;
-use log::{error, info};
-use reqwest::Client;
+use crate::auth::login::{role_allows, AuthState};
+use crate::commands::draft_watch::record_own_save;
+use crate::commands::settings::reviews_dir;
+use crate::commands::users::lead_team_ids;
+use crate::services::api_client::{ApiClient, UPLOAD_TIMEOUT};
+use crate::services::config::AppConfig;
+use crate::services::html_sanitizer::{sanitize_review_html, sanitize_review_html_inlining_images};
+use crate::utils::{get_auth_header, parse_server_timestamp, safe_file_name, write_atomic};
+use futures::stream::{self, StreamExt};
+use log::{error, info, warn};
+use reqwest::multipart::{Form, Part};
+use reqwest::{Body, Client, Method, StatusCode};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use similar::TextDiff;
 use std::fs;
-use std::path::PathBuf;
-use tauri::State;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{Emitter, State, Window};
+use tokio::io::AsyncReadExt;
 use base64::Engine;
 
 /// Represents the metadata of a review in the system
@@ -64,49 +75,39 @@ pub struct ReviewResponse {
     pub content: String,
 }
 
-/// Local paths for reviews and images
-pub fn get_review_local_path(product_id: i32, review_id: Option<i32>) -> PathBuf {
-    let home_dir = dirs::home_dir().expect("Could not find home directory");
-    let base_dir = home_dir
-        .join(".elevation-manager")
-        .join("reviews")
-        .join(product_id.to_string());
-
-    // Create base directory if it doesn't exist
-    if !base_dir.exists() {
-        info!("Creating base directory at: {}", base_dir.display());
-        if let Err(e) = std::fs::create_dir_all(&base_dir) {
-            error!("Failed to create base directory: {}", e);
-        }
-    }
-
-    if let Some(id) = review_id {
-        base_dir.join(format!("review_{}.html", id))
-    } else {
-        base_dir.join("draft.html")
+// Create a directory under the reviews directory on first use
+fn create_review_dir(dir: &Path) -> Result<(), String> {
+    if !dir.exists() {
+        info!("Creating review directory at: {}", dir.display());
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Reviews directory {} is not writable: {}", dir.display(), e))?;
     }
+    Ok(())
 }
 
-#[allow(dead_code)]
-pub fn get_review_image_dir(product_id: i32, review_id: Option<i32>) -> PathBuf {
-    let home_dir = dirs::home_dir().expect("Could not find home directory");
-    let base_dir = home_dir
-        .join(".elevation-manager")
-        .join("reviews")
-        .join(product_id.to_string())
-        .join("images");
+/// Local paths for reviews and images, under the configured reviews directory
+/// (see `DataSettings::reviews_dir`)
+pub fn get_review_local_path(product_id: i32, review_id: Option<i32>) -> Result<PathBuf, String> {
+    let base_dir = reviews_dir()?.join(product_id.to_string());
+    create_review_dir(&base_dir)?;
 
     if let Some(id) = review_id {
-        let dir = base_dir.join(id.to_string());
-        fs::create_dir_all(&dir).expect("Failed to create image directory");
-        dir
+        Ok(base_dir.join(format!("review_{}.html", id)))
     } else {
-        let dir = base_dir.join("draft");
-        fs::create_dir_all(&dir).expect("Failed to create image directory");
-        dir
+        Ok(base_dir.join("draft.html"))
     }
 }
 
+pub fn get_review_image_dir(product_id: i32, review_id: Option<i32>) -> Result<PathBuf, String> {
+    let base_dir = reviews_dir()?.join(product_id.to_string()).join("images");
+    let dir = match review_id {
+        Some(id) => base_dir.join(id.to_string()),
+        None => base_dir.join("draft"),
+    };
+    create_review_dir(&dir)?;
+    Ok(dir)
+}
+
 /// Convert an image file to base64 for embedding in the review
 #[tauri::command(rename_all = "snake_case")]
 pub fn convert_image_to_base64(path: String) -> Result<String, String> {
@@ -123,15 +124,20 @@ pub fn convert_image_to_base64(path: String) -> Result<String, String> {
 }
 #[tauri::command(rename_all = "snake_case")]
 pub async fn delete_review(state: State<'_, AuthState>, review_id: i32) -> Result<String, String> {
-    let path = get_review_local_path(0, Some(review_id));
-    if path.exists() {
-        fs::remove_file(&path).map_err(|e| format!("Failed to delete local review file: {}", e))?;
-    }
-
     let client = Client::new();
     let url = format!("http://localhost:3000/reviews/{}", review_id);
     let auth_header = get_auth_header(&state).await?;
 
+    // The local copy is filed under the review's product
+    let product_id = match client.get(&url).header("Authorization", &auth_header).send().await {
+        Ok(response) if response.status().is_success() => response
+            .json::<Value>()
+            .await
+            .ok()
+            .and_then(|body| body["data"]["review"]["product_id"].as_i64()),
+        _ => None,
+    };
+
     let response = client
         .delete(&url)
         .header("Authorization", auth_header)
@@ -144,6 +150,9 @@ pub async fn delete_review(state: State<'_, AuthState>, review_id: i32) -> Resul
 
     if status.is_success() {
         info!("Review {} deleted successfully", review_id);
+        if let Some(product_id) = product_id {
+            remove_local_review_copy(product_id as i32, review_id);
+        }
         Ok(format!("Review {} deleted successfully", review_id))
     } else {
         error!(
@@ -154,11 +163,24 @@ pub async fn delete_review(state: State<'_, AuthState>, review_id: i32) -> Resul
     }
 }
 
+// Best effort: a leftover local copy must not fail an already completed delete
+fn remove_local_review_copy(product_id: i32, review_id: i32) {
+    let Ok(dir) = reviews_dir() else {
+        return;
+    };
+    let path = dir.join(product_id.to_string()).join(format!("review_{}.html", review_id));
+    if path.exists() {
+        if let Err(e) = fs::remove_file(&path) {
+            warn!("Failed to delete local copy of review {}: {}", review_id, e);
+        }
+    }
+}
+
 /// Save a draft review locally
 #[tauri::command(rename_all = "snake_case")]
 pub fn save_review_draft(product_id: i32, content: String) -> Result<String, String> {
     info!("Starting save_review_draft for product_id: {}", product_id);
-    let path = get_review_local_path(product_id, None);
+    let path = get_review_local_path(product_id, None)?;
     info!("Target path: {}", path.display());
     
     // Create all parent directories
@@ -182,7 +204,9 @@ pub fn save_review_draft(product_id: i32, content: String) -> Result<String, Str
     }
 
     info!("Attempting to write content to file: {}", path.display());
-    match std::fs::write(&path, &content) {
+    record_own_save(product_id, &content);
+    // Atomic so a crash mid-save can't truncate the previous draft
+    match write_atomic(&path, &content) {
         Ok(_) => {
             info!("Successfully wrote {} bytes to {}", content.len(), path.display());
             Ok(path.to_string_lossy().to_string())
@@ -198,7 +222,7 @@ pub fn save_review_draft(product_id: i32, content: String) -> Result<String, Str
 /// Load a draft review from local storage
 #[tauri::command(rename_all = "snake_case")]
 pub fn load_review_draft(product_id: i32) -> Result<String, String> {
-    let path = get_review_local_path(product_id, None);
+    let path = get_review_local_path(product_id, None)?;
 
     if !path.exists() {
         return Err("No draft exists for this product".to_string());
@@ -216,13 +240,78 @@ pub fn load_review_draft(product_id: i32) -> Result<String, String> {
     }
 }
 
+// Local file behind an image source in a draft: a `file://` URL, an absolute
+// path, or a path relative to the draft (or its image folder)
+fn local_image_path(src: &str, product_id: i32) -> Option<PathBuf> {
+    let src = src.trim();
+    if src.starts_with("file:") {
+        return reqwest::Url::parse(src).ok()?.to_file_path().ok();
+    }
+    // Anything else with a scheme (http, data, ...) is not a local file
+    if reqwest::Url::parse(src).is_ok() {
+        return None;
+    }
+    let path = Path::new(src);
+    if path.is_absolute() {
+        return Some(path.to_path_buf());
+    }
+    let draft_dir = get_review_local_path(product_id, None).ok()?.parent()?.to_path_buf();
+    [draft_dir.join(path), get_review_image_dir(product_id, None).ok()?.join(path)]
+        .into_iter()
+        .find(|candidate| candidate.is_file())
+}
+
+/// Render the local draft as a self-contained HTML document for previewing:
+/// sanitized like a submitted review, with local images embedded as data URIs
+/// so it renders offline.
+#[tauri::command(rename_all = "snake_case")]
+pub fn render_review_preview(config: State<'_, Arc<AppConfig>>, product_id: i32) -> Result<String, String> {
+    render_preview(&config.review_html_extra_tags, product_id)
+}
+
+fn render_preview(extra_tags: &[String], product_id: i32) -> Result<String, String> {
+    let draft = load_review_draft(product_id)?;
+    let body = sanitize_review_html_inlining_images(&draft, extra_tags, move |src| {
+        let path = local_image_path(src, product_id)?;
+        let mime = image_mime_type(&path);
+        if !mime.starts_with("image/") {
+            return None;
+        }
+        match convert_image_to_base64(path.to_string_lossy().to_string()) {
+            Ok(data) => Some(format!("data:{};base64,{}", mime, data)),
+            Err(e) => {
+                error!("Could not embed {} in preview: {}", path.display(), e);
+                None
+            }
+        }
+    });
+    info!("Rendered review preview for product {}", product_id);
+    Ok(format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Review preview</title>\n</head>\n<body>\n{}\n</body>\n</html>\n",
+        body
+    ))
+}
+
 /// Create a new review on the server
 #[tauri::command(rename_all = "snake_case")]
 pub async fn create_review(
-    state: State<'_, AuthState>,
+    api_client: State<'_, ApiClient>,
+    config: State<'_, Arc<AppConfig>>,
+    product_id: i32,
+    review: NewReview,
+) -> Result<Value, String> {
+    post_review(&api_client, &config.review_html_extra_tags, product_id, review).await
+}
+
+// Create a review on the server and keep a local copy under its new ID
+async fn post_review(
+    api_client: &ApiClient,
+    extra_tags: &[String],
     product_id: i32,
     review: NewReview,
 ) -> Result<Value, String> {
+    let content = sanitize_review_html(&review.content, extra_tags);
+
     // Convert enum to string for API
     let review_status = match review.review_status {
         ReviewStatus::Draft => "Draft",
@@ -236,27 +325,13 @@ pub async fn create_review(
         ProductStatus::Accepted => "Accepted",
     };
 
-    let client = Client::new();
-    let url = "http://localhost:3000/reviews".to_string();
-    let auth_header = get_auth_header(&state).await?;
-
     info!("Creating new review for product {}", product_id);
 
-    // Get user ID from the auth state
-    let user_response = client
-        .get("http://localhost:3000/users/me")
-        .header("Authorization", &auth_header)
-        .send()
+    let me: Value = api_client
+        .get_json("/users/me")
         .await
         .map_err(|e| format!("Failed to get user info: {}", e))?;
-
-    let user_data = user_response.text().await.unwrap_or_default();
-    let user_json: Value = serde_json::from_str(&user_data)
-        .map_err(|e| format!("Failed to parse user response: {}", e))?;
-
-    let reviewer_id = user_json["data"]["id"]
-        .as_i64()
-        .ok_or("Failed to get reviewer ID")?;
+    let reviewer_id = me["id"].as_i64().ok_or("Failed to get reviewer ID")?;
 
     // Create the request payload with reviewer_id
     let payload = json!({
@@ -264,48 +339,29 @@ pub async fn create_review(
         "reviewer_id": reviewer_id,
         "review_status": review_status,
         "product_status": product_status,
-        "content": review.content,
+        "content": content,
     });
 
-    let response = client
-        .post(&url)
-        .header("Authorization", auth_header)
-        .header("Content-Type", "application/json")
-        .json(&payload)
-        .send()
-        .await
-        .map_err(|e| {
-            error!("Request failed: {}", e);
-            format!("Request failed: {}", e)
-        })?;
-
-    let status = response.status();
-    let response_text = response.text().await.unwrap_or_default();
-
-    if status.is_success() {
-        info!("Review created successfully");
+    // A retried create must not leave a duplicate review behind
+    let response_text = api_client.post_idempotent("/reviews", &payload).await.map_err(|e| {
+        error!("Failed to create review: {}", e);
+        format!("Failed to create review: {}", e)
+    })?;
+    info!("Review created successfully");
 
-        // Save a copy locally
-        let response_value: Value = serde_json::from_str(&response_text)
-            .map_err(|e| format!("Failed to parse response: {}", e))?;
+    let response_value: Value = serde_json::from_str(&response_text)
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
 
-        let review_id = response_value["data"]
-            .as_i64()
-            .ok_or_else(|| "Failed to extract review ID from response".to_string())?;
+    let review_id = response_value["data"]
+        .as_i64()
+        .ok_or_else(|| "Failed to extract review ID from response".to_string())?;
 
-        // Save the content locally with the official review ID
-        let local_path = get_review_local_path(product_id, Some(review_id as i32));
-        fs::write(&local_path, &review.content)
-            .map_err(|e| format!("Failed to save local copy: {}", e))?;
+    // Save the content locally with the official review ID
+    let local_path = get_review_local_path(product_id, Some(review_id as i32))?;
+    fs::write(&local_path, &content)
+        .map_err(|e| format!("Failed to save local copy: {}", e))?;
 
-        Ok(response_value)
-    } else {
-        error!(
-            "Failed to create review. Status: {:?}, Response: {}",
-            status, response_text
-        );
-        Err(format!("Failed to create review: {}", response_text))
-    }
+    Ok(response_value)
 }
 
 /// Get a review from the server
@@ -350,7 +406,7 @@ pub async fn get_review(
             .to_string();
 
         // Save a copy locally
-        let local_path = get_review_local_path(review.product_id, Some(review.id));
+        let local_path = get_review_local_path(review.product_id, Some(review.id))?;
         fs::write(&local_path, &content)
             .map_err(|e| format!("Failed to save local copy: {}", e))?;
 
@@ -368,6 +424,7 @@ pub async fn get_review(
 #[tauri::command(rename_all = "snake_case")]
 pub async fn update_review(
     state: State<'_, AuthState>,
+    config: State<'_, Arc<AppConfig>>,
     review_id: i32,
     review: UpdateReview,
 ) -> Result<Value, String> {
@@ -389,6 +446,7 @@ pub async fn update_review(
     }
 
     if let Some(content) = &review.content {
+        let content = sanitize_review_html(content, &config.review_html_extra_tags);
         payload["content"] = json!(content);
 
         // Get the product_id first to save locally
@@ -412,8 +470,8 @@ pub async fn update_review(
                 .ok_or_else(|| "Failed to extract product ID from response".to_string())?;
 
             // Save the content locally
-            let local_path = get_review_local_path(product_id as i32, Some(review_id));
-            fs::write(&local_path, content)
+            let local_path = get_review_local_path(product_id as i32, Some(review_id))?;
+            fs::write(&local_path, &content)
                 .map_err(|e| format!("Failed to save local copy: {}", e))?;
         }
     }
@@ -500,7 +558,7 @@ pub async fn get_user_reviews(state: State<'_, AuthState>) -> Result<Value, Stri
     let auth_header = get_auth_header(&state).await?;
 
     // First get the user ID from the auth state
-    let token_guard = state.token.lock().await;
+    let token_guard = state.token.read().await;
 
     if token_guard.is_none() {
         return Err("Not authenticated".to_string());
@@ -564,60 +622,301 @@ pub async fn get_user_reviews(state: State<'_, AuthState>) -> Result<Value, Stri
     }
 }
 
-/// Upload an image for a review
+/// Images larger than this are uploaded in resumable chunks when the server
+/// supports it
+const RESUMABLE_UPLOAD_THRESHOLD: u64 = 8 * 1024 * 1024;
+const RESUMABLE_CHUNK_SIZE: u64 = 2 * 1024 * 1024;
+/// Failed chunks tolerated per upload before giving up
+const RESUMABLE_MAX_FAILURES: u32 = 5;
+
+enum ChunkedUploadError {
+    /// The server has no chunk endpoint; use a single-shot upload instead
+    Unsupported,
+    Failed(String),
+}
+
+/// Bytes of a chunked upload the server has stored so far
+#[derive(Deserialize)]
+struct ChunkUploadStatus {
+    received: u64,
+}
+
+async fn read_chunk(file: &mut tokio::fs::File, offset: u64, len: u64) -> Result<Vec<u8>, String> {
+    use tokio::io::AsyncSeekExt;
+    file.seek(std::io::SeekFrom::Start(offset))
+        .await
+        .map_err(|e| format!("Failed to read image file: {}", e))?;
+    let mut chunk = vec![0; len as usize];
+    file.read_exact(&mut chunk)
+        .await
+        .map_err(|e| format!("Failed to read image file: {}", e))?;
+    Ok(chunk)
+}
+
+// Upload `path` to `/reviews/{id}/images/chunk` in pieces. After a failed
+// chunk the server is asked how much it received and the upload resumes from
+// there. A 404/405/501 on the first chunk means chunking isn't supported.
+async fn upload_image_in_chunks(
+    api_client: &ApiClient,
+    review_id: i32,
+    path: &Path,
+    size: u64,
+    sha256: &str,
+) -> Result<String, ChunkedUploadError> {
+    let filename = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| ChunkedUploadError::Failed("Invalid image filename".to_string()))?;
+    let upload_id = uuid::Uuid::new_v4().to_string();
+    let status_endpoint = format!("/reviews/{}/images/chunk/{}", review_id, upload_id);
+    let mut file = tokio::fs::File::open(path)
+        .await
+        .map_err(|e| ChunkedUploadError::Failed(format!("Failed to open image file: {}", e)))?;
+
+    let mut offset = 0;
+    let mut failures = 0;
+    while offset < size {
+        let len = RESUMABLE_CHUNK_SIZE.min(size - offset);
+        let chunk = read_chunk(&mut file, offset, len).await.map_err(ChunkedUploadError::Failed)?;
+        let query = serde_urlencoded::to_string([
+            ("upload_id", upload_id.clone()),
+            ("offset", offset.to_string()),
+            ("total_size", size.to_string()),
+            ("filename", filename.to_string()),
+        ])
+        .map_err(|e| ChunkedUploadError::Failed(format!("Failed to encode chunk query: {}", e)))?;
+        match api_client.post_bytes(&format!("/reviews/{}/images/chunk?{}", review_id, query), chunk).await {
+            Ok(_) => offset += len,
+            Err((_, Some(status))) if offset == 0 && matches!(status.as_u16(), 404 | 405 | 501) => {
+                info!("Server does not support chunked uploads, uploading in one request");
+                return Err(ChunkedUploadError::Unsupported);
+            }
+            Err((message, _)) => {
+                failures += 1;
+                if failures > RESUMABLE_MAX_FAILURES {
+                    return Err(ChunkedUploadError::Failed(format!(
+                        "Upload failed at byte {} of {}: {}",
+                        offset, size, message
+                    )));
+                }
+                warn!("Chunk at byte {} failed ({}), resuming", offset, message);
+                tokio::time::sleep(std::time::Duration::from_secs(failures as u64)).await;
+                // Without an answer, resend the same chunk
+                match api_client.get_json::<ChunkUploadStatus>(&status_endpoint).await {
+                    Ok(status) if status.received <= size => offset = status.received,
+                    Ok(status) => warn!("Server reports {} bytes received of {}", status.received, size),
+                    Err(e) => warn!("Could not query upload progress: {}", e),
+                }
+            }
+        }
+    }
+
+    // The server assembles and checksums the whole image before answering
+    api_client
+        .post_with_timeout(
+            &format!("{}/complete", status_endpoint),
+            &json!({ "filename": filename, "total_size": size, "sha256": sha256 }),
+            UPLOAD_TIMEOUT,
+        )
+        .await
+        .map_err(ChunkedUploadError::Failed)
+}
+
+// Hex SHA-256 of a file, read in chunks
+fn sha256_file(path: &Path) -> Result<String, String> {
+    use sha2::{Digest, Sha256};
+    use std::io::Read;
+    let mut file = fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; UPLOAD_CHUNK_SIZE];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// SHA-256 of a local image, for spotting corrupted copies
+#[tauri::command(rename_all = "snake_case")]
+pub fn verify_local_image(path: String) -> Result<String, String> {
+    sha256_file(Path::new(&path))
+}
+
+/// Upload an image for a review. Large images go up in resumable chunks when
+/// the server supports it. The file's SHA-256 is sent as `sha256`, and if the
+/// server echoes a `sha256` back it must match.
 #[tauri::command(rename_all = "snake_case")]
 pub async fn upload_review_image(
-    state: State<'_, AuthState>,
+    api_client: State<'_, ApiClient>,
     review_id: i32,
     image_path: String,
 ) -> Result<String, String> {
-    let client = Client::new();
-    let url = format!("http://localhost:3000/reviews/{}/images", review_id);
-    let auth_header = get_auth_header(&state).await?;
-
     info!("Uploading image for review {}", review_id);
+    upload_image(&api_client, review_id, Path::new(&image_path)).await
+}
 
-    // Create a multipart form
-    let form = reqwest::multipart::Form::new()
-        .file("file", &image_path)
-        .await
-        .map_err(|e| format!("Failed to create form: {}", e))?;
+async fn upload_image(api_client: &ApiClient, review_id: i32, path: &Path) -> Result<String, String> {
+    let size = fs::metadata(path)
+        .map_err(|e| format!("Failed to read image file: {}", e))?
+        .len();
+    let checksum = sha256_file(path)?;
+    let chunked = if size > RESUMABLE_UPLOAD_THRESHOLD {
+        upload_image_in_chunks(api_client, review_id, path, size, &checksum).await
+    } else {
+        Err(ChunkedUploadError::Unsupported)
+    };
+    let response_text = match chunked {
+        Ok(response_text) => response_text,
+        Err(ChunkedUploadError::Failed(e)) => {
+            error!("Failed to upload image: {}", e);
+            return Err(format!("Failed to upload image: {}", e));
+        }
+        // Retried uploads re-read the file, so flaky connections don't lose it
+        Err(ChunkedUploadError::Unsupported) => api_client
+            .post_multipart_file(&format!("/reviews/{}/images", review_id), "file", path, &[("sha256", &checksum)])
+            .await
+            .map_err(|e| {
+                error!("Failed to upload image: {}", e);
+                format!("Failed to upload image: {}", e)
+            })?,
+    };
 
-    let response = client
-        .post(&url)
-        .header("Authorization", auth_header)
-        .multipart(form)
-        .send()
-        .await
-        .map_err(|e| {
-            error!("Request failed: {}", e);
-            format!("Request failed: {}", e)
-        })?;
+    info!("Image uploaded successfully");
+    let response_value: Value = serde_json::from_str(&response_text)
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
 
-    let status = response.status();
-    let response_text = response.text().await.unwrap_or_default();
+    if let Some(stored) = response_value["sha256"].as_str() {
+        if !stored.eq_ignore_ascii_case(&checksum) {
+            error!("Checksum mismatch for {}: sent {}, server stored {}", path.display(), checksum, stored);
+            return Err(format!(
+                "Image was corrupted in transit (checksum {} does not match the server's {})",
+                checksum, stored
+            ));
+        }
+    }
 
-    if status.is_success() {
-        info!("Image uploaded successfully");
+    // The response should contain the image URL or ID
+    let filename = response_value["data"][0]
+        .as_str()
+        .ok_or_else(|| "Failed to extract image filename from response".to_string())?;
 
-        let response_value: Value = serde_json::from_str(&response_text)
-            .map_err(|e| format!("Failed to parse response: {}", e))?;
+    Ok(filename.to_string())
+}
 
-        // The response should contain the image URL or ID
-        let filename = response_value["data"][0]
-            .as_str()
-            .ok_or_else(|| "Failed to extract image filename from response".to_string())?;
+/// Size of each chunk read from disk while streaming an image upload
+const UPLOAD_CHUNK_SIZE: usize = 64 * 1024;
 
-        Ok(filename.to_string())
-    } else {
-        error!(
-            "Failed to upload image. Status: {:?}, Response: {}",
-            status, response_text
-        );
-        Err(format!("Failed to upload image: {}", response_text))
+/// Payload of the `upload_progress` event
+#[derive(Debug, Serialize, Clone)]
+pub struct UploadProgress {
+    pub review_id: i32,
+    pub bytes_sent: u64,
+    pub total_bytes: u64,
+}
+
+/// Payload of the `upload_complete` event
+#[derive(Debug, Serialize, Clone)]
+pub struct UploadComplete {
+    pub review_id: i32,
+    pub filename: String,
+}
+
+fn image_mime_type(path: &Path) -> &'static str {
+    match path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("tif") | Some("tiff") => "image/tiff",
+        _ => "application/octet-stream",
     }
 }
 
+/// Upload an image for a review, streaming the file from disk and emitting
+/// `upload_progress` events as chunks are sent and `upload_complete` at the end
+#[tauri::command(rename_all = "snake_case")]
+pub async fn upload_review_image_with_progress(
+    window: Window,
+    api_client: State<'_, ApiClient>,
+    review_id: i32,
+    image_path: String,
+) -> Result<String, String> {
+    info!("Uploading image for review {} with progress", review_id);
+
+    let path = Path::new(&image_path);
+    let file = tokio::fs::File::open(path)
+        .await
+        .map_err(|e| format!("Failed to open image file: {}", e))?;
+    let total_bytes = file
+        .metadata()
+        .await
+        .map_err(|e| format!("Failed to read image metadata: {}", e))?
+        .len();
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("image")
+        .to_string();
+
+    let progress_window = window.clone();
+    let body_stream = stream::unfold((file, 0u64), move |(mut file, bytes_sent)| {
+        let window = progress_window.clone();
+        async move {
+            let mut chunk = vec![0u8; UPLOAD_CHUNK_SIZE];
+            match file.read(&mut chunk).await {
+                Ok(0) => None,
+                Ok(read) => {
+                    chunk.truncate(read);
+                    let bytes_sent = bytes_sent + read as u64;
+                    let _ = window.emit(
+                        "upload_progress",
+                        UploadProgress { review_id, bytes_sent, total_bytes },
+                    );
+                    Some((Ok(chunk), (file, bytes_sent)))
+                }
+                Err(e) => {
+                    error!("Failed to read image chunk: {}", e);
+                    Some((Err(e), (file, bytes_sent)))
+                }
+            }
+        }
+    });
+
+    let part = Part::stream_with_length(Body::wrap_stream(body_stream), total_bytes)
+        .file_name(file_name)
+        .mime_str(image_mime_type(path))
+        .map_err(|e| format!("Failed to create form: {}", e))?;
+    let form = Form::new().part("file", part);
+
+    let response_text = api_client
+        .post_multipart(&format!("/reviews/{}/images", review_id), form)
+        .await?;
+
+    let response_value: Value = serde_json::from_str(&response_text)
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+    let filename = response_value["data"][0]
+        .as_str()
+        .ok_or_else(|| "Failed to extract image filename from response".to_string())?
+        .to_string();
+
+    info!("Image uploaded successfully");
+    let _ = window.emit(
+        "upload_complete",
+        UploadComplete { review_id, filename: filename.clone() },
+    );
+    Ok(filename)
+}
+
 /// Get all images for a review
 #[tauri::command(rename_all = "snake_case")]
 pub async fn get_review_images(
@@ -666,6 +965,250 @@ pub async fn get_review_images(
     }
 }
 
+/// Download a review image into the local image directory and return its path.
+/// A cached copy is reused when its ETag (or size) still matches the server.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn download_review_image(
+    api_client: State<'_, ApiClient>,
+    review_id: i32,
+    filename: String,
+) -> Result<String, String> {
+    safe_file_name(&filename)?;
+
+    info!("Downloading image {} for review {}", filename, review_id);
+
+    let product_id = review_product_id(&api_client, review_id).await?;
+    let (local_path, _) = cache_review_image(&api_client, review_id, product_id, &filename).await?;
+    Ok(local_path.to_string_lossy().to_string())
+}
+
+// Product a review belongs to, from `GET /reviews/{id}`
+async fn review_product_id(api_client: &ApiClient, review_id: i32) -> Result<i32, String> {
+    let review_text = api_client.get(&format!("/reviews/{}", review_id)).await?;
+    let review_value: Value = serde_json::from_str(&review_text)
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+    review_value["data"]["review"]["product_id"]
+        .as_i64()
+        .map(|id| id as i32)
+        .ok_or_else(|| "Failed to extract product ID from response".to_string())
+}
+
+// Bring one image into the review's local image directory; `Ok((path, true))`
+// when the cached copy was still current
+async fn cache_review_image(
+    api_client: &ApiClient,
+    review_id: i32,
+    product_id: i32,
+    filename: &str,
+) -> Result<(PathBuf, bool), String> {
+    let image_dir = get_review_image_dir(product_id, Some(review_id))?;
+    let local_path = image_dir.join(filename);
+    let etag_path = image_dir.join(format!(".{}.etag", filename));
+
+    let cached_len = fs::metadata(&local_path).ok().map(|m| m.len());
+    let cached_etag = cached_len
+        .and_then(|_| fs::read_to_string(&etag_path).ok())
+        .map(|e| e.trim().to_string());
+
+    let download = api_client
+        .get_bytes_if_changed(
+            &format!("/reviews/{}/image/{}", review_id, filename),
+            cached_etag.as_deref(),
+            cached_len,
+        )
+        .await?;
+
+    match download {
+        None => {
+            info!("Using cached image at {}", local_path.display());
+            return Ok((local_path, true));
+        }
+        Some(download) => {
+            fs::write(&local_path, &download.bytes)
+                .map_err(|e| format!("Failed to save image: {}", e))?;
+            match download.etag {
+                Some(etag) => {
+                    let _ = fs::write(&etag_path, etag);
+                }
+                None => {
+                    let _ = fs::remove_file(&etag_path);
+                }
+            }
+            info!("Saved {} bytes to {}", download.bytes.len(), local_path.display());
+        }
+    }
+    Ok((local_path, false))
+}
+
+/// Images downloaded at once per review
+const REVIEW_IMAGE_DOWNLOAD_CONCURRENCY: usize = 4;
+
+#[derive(Debug, Serialize)]
+pub struct ReviewImageArchive {
+    /// Local path of every image now cached, downloaded or already current
+    pub paths: Vec<String>,
+    /// Filenames whose cached copy was still current
+    pub skipped: Vec<String>,
+    pub failures: Vec<ImageDownloadFailure>,
+    /// `manifest.json` mapping each cached filename to its local path
+    pub manifest_path: String,
+}
+
+/// Pull every image of a review into its local image directory for
+/// archiving, a few at a time, and write a `manifest.json` next to them.
+/// Images whose cached copy is current are not downloaded again.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn download_all_review_images(
+    api_client: State<'_, ApiClient>,
+    review_id: i32,
+) -> Result<ReviewImageArchive, String> {
+    archive_review_images(&api_client, review_id).await
+}
+
+async fn archive_review_images(api_client: &ApiClient, review_id: i32) -> Result<ReviewImageArchive, String> {
+    info!("Downloading all images for review {}", review_id);
+    let product_id = review_product_id(api_client, review_id).await?;
+    let mut filenames: Vec<String> = api_client
+        .get_json(&format!("/reviews/{}/images", review_id))
+        .await
+        .map_err(|e| format!("Failed to fetch review images: {}", e))?;
+    filenames.sort();
+    filenames.dedup();
+
+    let outcomes: Vec<_> = stream::iter(filenames)
+        .map(|filename| async move {
+            let outcome = match safe_file_name(&filename) {
+                Ok(name) => cache_review_image(api_client, review_id, product_id, name).await,
+                Err(e) => Err(e),
+            };
+            (filename, outcome)
+        })
+        .buffered(api_client.batch_concurrency(REVIEW_IMAGE_DOWNLOAD_CONCURRENCY))
+        .collect()
+        .await;
+
+    let mut manifest = serde_json::Map::new();
+    let mut archive = ReviewImageArchive {
+        paths: Vec::new(),
+        skipped: Vec::new(),
+        failures: Vec::new(),
+        manifest_path: String::new(),
+    };
+    for (filename, outcome) in outcomes {
+        match outcome {
+            Ok((path, cached)) => {
+                let path = path.to_string_lossy().to_string();
+                manifest.insert(filename.clone(), Value::String(path.clone()));
+                archive.paths.push(path);
+                if cached {
+                    archive.skipped.push(filename);
+                }
+            }
+            Err(error) => {
+                error!("Failed to download image {} of review {}: {}", filename, review_id, error);
+                archive.failures.push(ImageDownloadFailure { filename, error });
+            }
+        }
+    }
+
+    let manifest_path = get_review_image_dir(product_id, Some(review_id))?.join("manifest.json");
+    let manifest_json = serde_json::to_string_pretty(&Value::Object(manifest))
+        .map_err(|e| format!("Failed to serialize image manifest: {}", e))?;
+    write_atomic(&manifest_path, manifest_json).map_err(|e| format!("Failed to write image manifest: {}", e))?;
+    archive.manifest_path = manifest_path.to_string_lossy().to_string();
+
+    info!(
+        "Cached {} image(s) for review {} ({} already current, {} failed)",
+        archive.paths.len(),
+        review_id,
+        archive.skipped.len(),
+        archive.failures.len()
+    );
+    Ok(archive)
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImageDownloadFailure {
+    pub filename: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReviewImagesDownload {
+    /// Local path of every image now in `dest_dir`, downloaded or already present
+    pub paths: Vec<String>,
+    /// Images left untouched because a file of the same size already existed
+    pub skipped: usize,
+    pub failures: Vec<ImageDownloadFailure>,
+}
+
+// Download one image into `dest_dir`; `Ok((path, true))` when an existing
+// file of the same size was kept
+async fn download_image_to(
+    api_client: &ApiClient,
+    review_id: i32,
+    filename: &str,
+    dest_dir: &Path,
+) -> Result<(PathBuf, bool), String> {
+    safe_file_name(filename)?;
+    let local_path = dest_dir.join(filename);
+    let existing_len = fs::metadata(&local_path).ok().map(|m| m.len());
+    let download = api_client
+        .get_bytes_if_changed(&format!("/reviews/{}/image/{}", review_id, filename), None, existing_len)
+        .await?;
+    match download {
+        None => Ok((local_path, true)),
+        // Servers that send an ETag can't be size-checked up front
+        Some(download) if Some(download.bytes.len() as u64) == existing_len => Ok((local_path, true)),
+        Some(download) => {
+            fs::write(&local_path, &download.bytes).map_err(|e| format!("Failed to save image: {}", e))?;
+            Ok((local_path, false))
+        }
+    }
+}
+
+/// Download every image of a review into `dest_dir`, creating it if needed.
+/// Files already there with the same size are kept; a failed image is
+/// reported without stopping the others.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn download_review_images(
+    api_client: State<'_, ApiClient>,
+    review_id: i32,
+    dest_dir: String,
+) -> Result<ReviewImagesDownload, String> {
+    info!("Downloading images for review {} to {}", review_id, dest_dir);
+    let dest_dir = PathBuf::from(dest_dir);
+    fs::create_dir_all(&dest_dir).map_err(|e| format!("Failed to create destination folder: {}", e))?;
+    let filenames: Vec<String> = api_client
+        .get_json(&format!("/reviews/{}/images", review_id))
+        .await
+        .map_err(|e| format!("Failed to fetch review images: {}", e))?;
+
+    let mut result = ReviewImagesDownload { paths: Vec::new(), skipped: 0, failures: Vec::new() };
+    for filename in filenames {
+        match download_image_to(&api_client, review_id, &filename, &dest_dir).await {
+            Ok((path, skipped)) => {
+                if skipped {
+                    result.skipped += 1;
+                }
+                result.paths.push(path.to_string_lossy().to_string());
+            }
+            Err(error) => {
+                error!("Failed to download image {} of review {}: {}", filename, review_id, error);
+                result.failures.push(ImageDownloadFailure { filename, error });
+            }
+        }
+    }
+    info!(
+        "Downloaded {} image(s) for review {} ({} already present, {} failed)",
+        result.paths.len() - result.skipped,
+        review_id,
+        result.skipped,
+        result.failures.len()
+    );
+    Ok(result)
+}
+
 /// Delete an image from a review
 #[tauri::command(rename_all = "snake_case")]
 pub async fn delete_review_image(
@@ -707,36 +1250,82 @@ pub async fn delete_review_image(
     }
 }
 
+// Reviews may be approved through the global role or by leading a team.
+// Fail fast only when the role rules it out and the user leads no team; if
+// teams can't be fetched the server is left to decide.
+async fn require_review_permission(
+    state: &AuthState,
+    api_client: &ApiClient,
+    action: &str,
+) -> Result<(), String> {
+    match state.current_role().await {
+        Some(role) if !role_allows(&role, action) => {
+            match api_client.get_json::<Vec<Value>>("/users/me/teams").await {
+                Ok(teams) if lead_team_ids(&teams).is_empty() => state.require_permission(action).await,
+                Ok(_) => Ok(()),
+                Err(e) => {
+                    warn!("Could not check team roles before {}: {}", action, e);
+                    Ok(())
+                }
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
 /// Team Lead functions to approve or reject reviews
 #[tauri::command(rename_all = "snake_case")]
-pub async fn approve_review(state: State<'_, AuthState>, review_id: i32) -> Result<Value, String> {
+pub async fn approve_review(
+    state: State<'_, AuthState>,
+    api_client: State<'_, ApiClient>,
+    config: State<'_, Arc<AppConfig>>,
+    review_id: i32,
+) -> Result<Value, String> {
+    require_review_permission(&state, &api_client, "approve_review").await?;
     let update = UpdateReview {
         review_status: Some("Approved".to_string()),
         product_status: None,
         content: None,
     };
 
-    update_review(state, review_id, update).await
+    update_review(state, config, review_id, update).await
 }
 
 #[tauri::command(rename_all = "snake_case")]
-pub async fn reject_review(state: State<'_, AuthState>, review_id: i32) -> Result<Value, String> {
+pub async fn reject_review(
+    state: State<'_, AuthState>,
+    api_client: State<'_, ApiClient>,
+    config: State<'_, Arc<AppConfig>>,
+    review_id: i32,
+) -> Result<Value, String> {
+    require_review_permission(&state, &api_client, "reject_review").await?;
     let update = UpdateReview {
         review_status: Some("Rejected".to_string()),
         product_status: None,
         content: None,
     };
 
-    update_review(state, review_id, update).await
+    update_review(state, config, review_id, update).await
 }
 
 #[tauri::command(rename_all = "snake_case")]
 pub async fn submit_review_from_file(
-    state: tauri::State<'_, AuthState>,
+    api_client: State<'_, ApiClient>,
+    config: State<'_, Arc<AppConfig>>,
     product_id: i32,
     product_status: String,
 ) -> Result<i32, String> {
-    let content_path = get_review_local_path(product_id, None);
+    submit_draft(&api_client, &config.review_html_extra_tags, product_id, &product_status).await
+}
+
+// Submit the product's local draft as a pending review
+async fn submit_draft(
+    api_client: &ApiClient,
+    extra_tags: &[String],
+    product_id: i32,
+    product_status: &str,
+) -> Result<i32, String> {
+    let content_path = get_review_local_path(product_id, None)?;
 
     if !content_path.exists() {
         return Err("Draft file not found".to_string());
@@ -744,10 +1333,9 @@ pub async fn submit_review_from_file(
 
     let content = fs::read_to_string(&content_path)
         .map_err(|e| format!("Failed to read draft file: {e}"))?;
+    let content = sanitize_review_html(&content, extra_tags);
 
-
-
-    let product_status_enum = match product_status.as_str() {
+    let product_status_enum = match product_status {
         "InReview" | "In Review" => ProductStatus::InReview,
         "Rejected" => ProductStatus::Rejected,
         "Accepted" => ProductStatus::Accepted,
@@ -764,7 +1352,7 @@ pub async fn submit_review_from_file(
         reviewer_id: None,
     };
 
-    let result = create_review(state, product_id, new_review).await?;
+    let result = post_review(api_client, extra_tags, product_id, new_review).await?;
     let review_id = result["data"]
         .as_i64()
         .ok_or_else(|| "Failed to extract review ID".to_string())? as i32;
@@ -772,9 +1360,56 @@ pub async fn submit_review_from_file(
     Ok(review_id)
 }
 
+// True if the text contains at least one HTML tag
+fn looks_like_html(content: &str) -> bool {
+    content
+        .split('<')
+        .skip(1)
+        .any(|rest| rest.starts_with(|c: char| c.is_ascii_alphabetic() || c == '!') && rest.contains('>'))
+}
+
+/// Submit a review authored outside the app. The file is copied into the
+/// product's local draft and then submitted like any other draft.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn submit_review_from_path(
+    api_client: State<'_, ApiClient>,
+    config: State<'_, Arc<AppConfig>>,
+    product_id: i32,
+    file_path: String,
+    product_status: String,
+) -> Result<i32, String> {
+    submit_file(&api_client, &config.review_html_extra_tags, product_id, &file_path, &product_status).await
+}
+
+async fn submit_file(
+    api_client: &ApiClient,
+    extra_tags: &[String],
+    product_id: i32,
+    file_path: &str,
+    product_status: &str,
+) -> Result<i32, String> {
+    let source = Path::new(file_path);
+    if !source.is_file() {
+        return Err(format!("Review file not found: {}", file_path));
+    }
+    let content = fs::read_to_string(source)
+        .map_err(|e| format!("Failed to read review file {}: {}", file_path, e))?;
+    if content.trim().is_empty() {
+        return Err(format!("Review file is empty: {}", file_path));
+    }
+    if !looks_like_html(&content) {
+        return Err(format!("Review file does not contain HTML: {}", file_path));
+    }
+
+    info!("Importing review for product {} from {}", product_id, file_path);
+    save_review_draft(product_id, content)?;
+    submit_draft(api_client, extra_tags, product_id, product_status).await
+}
+
 #[tauri::command(rename_all = "snake_case")]
 pub async fn update_review_from_file(
     state: tauri::State<'_, AuthState>,
+    config: State<'_, Arc<AppConfig>>,
     review_id: i32,
     product_status: String,
 ) -> Result<(), String> {
@@ -799,7 +1434,7 @@ pub async fn update_review_from_file(
         .ok_or("Missing product ID in response")? as i32;
 
     // Step 2: Load draft content
-    let content_path = get_review_local_path(product_id, None);
+    let content_path = get_review_local_path(product_id, None)?;
     let content = fs::read_to_string(&content_path)
         .map_err(|e| format!("Failed to read local draft file: {}", e))?;
 
@@ -810,14 +1445,14 @@ pub async fn update_review_from_file(
         content: Some(content),
     };
 
-    update_review(state, review_id, update).await.map(|_| ())
+    update_review(state, config, review_id, update).await.map(|_| ())
 }
 
 /// Sync a review draft from a local file
 #[tauri::command(rename_all = "snake_case")]
 pub async fn sync_review_from_file(state: State<'_, AuthState>, product_id: i32) -> Result<(), String> {
     // Ensure the directory exists first
-    let content_path = get_review_local_path(product_id, None);
+    let content_path = get_review_local_path(product_id, None)?;
     
     // Create parent directories if they don't exist
     if let Some(parent) = content_path.parent() {
@@ -905,3 +1540,421 @@ pub async fn get_pending_reviews_for_team_lead(
         Err(format!("Failed to fetch pending reviews: {}", response_text))
     }
 }
+
+/// Review statuses accepted by `get_team_reviews`.
+const REVIEW_STATUSES: [&str; 4] = ["draft", "pending", "approved", "rejected"];
+
+// `/reviews/team_lead`, filtered by `status` only when one is given
+fn team_reviews_endpoint(status: Option<&str>) -> String {
+    match status {
+        Some(status) => format!("/reviews/team_lead?status={}", status),
+        None => "/reviews/team_lead".to_string(),
+    }
+}
+
+/// Reviews visible to a team lead, optionally limited to one status
+/// (`draft`, `pending`, `approved` or `rejected`). `None` returns all.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_team_reviews(
+    api_client: State<'_, ApiClient>,
+    status: Option<String>,
+) -> Result<Vec<Review>, String> {
+    let status = match status {
+        Some(status) => {
+            let normalized = status.trim().to_lowercase();
+            if !REVIEW_STATUSES.contains(&normalized.as_str()) {
+                return Err(format!(
+                    "Invalid review status '{}'. Expected one of: {}",
+                    status,
+                    REVIEW_STATUSES.join(", ")
+                ));
+            }
+            Some(normalized)
+        }
+        None => None,
+    };
+
+    info!("Fetching team reviews (status: {})", status.as_deref().unwrap_or("any"));
+    api_client
+        .get_json::<Vec<Review>>(&team_reviews_endpoint(status.as_deref()))
+        .await
+        .map_err(|e| format!("Failed to fetch team reviews: {}", e))
+}
+
+/// Produce a unified diff between two HTML documents, line by line.
+fn unified_review_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(3)
+        .header(old_label, new_label)
+        .to_string()
+}
+
+/// How the local draft relates to the server copy of a review
+#[derive(Debug, Serialize)]
+pub struct DraftComparison {
+    pub identical: bool,
+    /// The draft differs and was modified after the server copy was last updated
+    pub local_newer: bool,
+    /// e.g. "3 line(s) added, 1 line(s) removed in the local draft"
+    pub diff_summary: String,
+    pub local_modified_at: Option<String>,
+    pub server_updated_at: Option<String>,
+}
+
+/// Compare a product's local draft with the server copy of `review_id`, by
+/// content and by modification time, to decide whether to sync or reload.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn compare_draft_to_server(
+    api_client: State<'_, ApiClient>,
+    product_id: i32,
+    review_id: i32,
+) -> Result<DraftComparison, String> {
+    compare_draft(&api_client, product_id, review_id).await
+}
+
+async fn compare_draft(api_client: &ApiClient, product_id: i32, review_id: i32) -> Result<DraftComparison, String> {
+    info!("Comparing draft for product {} with review {}", product_id, review_id);
+    let draft_path = get_review_local_path(product_id, None)?;
+    let local_content = fs::read_to_string(&draft_path).map_err(|e| format!("Failed to read local draft: {}", e))?;
+    let local_modified_at: Option<chrono::DateTime<chrono::Utc>> = fs::metadata(&draft_path)
+        .and_then(|m| m.modified())
+        .ok()
+        .map(Into::into);
+
+    let server: Value = api_client
+        .get_json(&format!("/reviews/{}", review_id))
+        .await
+        .map_err(|e| format!("Failed to fetch review: {}", e))?;
+    if server["review"]["product_id"].as_i64().is_some_and(|id| id != product_id as i64) {
+        return Err(format!("Review {} does not belong to product {}", review_id, product_id));
+    }
+    let server_content = server["content"]
+        .as_str()
+        .ok_or_else(|| "Failed to extract content from response".to_string())?;
+    let server_updated_at = server["review"]["updated_at"].as_str().and_then(parse_server_timestamp);
+
+    // Line endings differ between editors without changing the review
+    let normalize = |content: &str| content.replace("\r\n", "\n");
+    let (local, remote) = (normalize(&local_content), normalize(server_content));
+    let identical = local == remote;
+    let (mut added, mut removed) = (0, 0);
+    for change in TextDiff::from_lines(&remote, &local).iter_all_changes() {
+        match change.tag() {
+            similar::ChangeTag::Insert => added += 1,
+            similar::ChangeTag::Delete => removed += 1,
+            similar::ChangeTag::Equal => {}
+        }
+    }
+    let diff_summary = if identical {
+        "Local draft matches the server copy".to_string()
+    } else {
+        format!("{} line(s) added, {} line(s) removed in the local draft", added, removed)
+    };
+    let local_newer = !identical
+        && match (local_modified_at, server_updated_at) {
+            (Some(local), Some(server)) => local > server,
+            // Without a server timestamp, an unsynced edit is assumed local
+            (Some(_), None) => true,
+            _ => false,
+        };
+
+    Ok(DraftComparison {
+        identical,
+        local_newer,
+        diff_summary,
+        local_modified_at: local_modified_at.map(|t| t.to_rfc3339()),
+        server_updated_at: server_updated_at.map(|t| t.to_rfc3339()),
+    })
+}
+
+/// Get a unified diff of a review's content between two versions.
+///
+/// Falls back to diffing the local cached copy against the current server copy
+/// when the backend does not expose `/reviews/{id}/versions`.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_review_version_diff(
+    api_client: State<'_, ApiClient>,
+    review_id: i32,
+    from_version: i32,
+    to_version: i32,
+) -> Result<String, String> {
+    info!("Diffing review {} versions {} -> {}", review_id, from_version, to_version);
+
+    let versions_endpoint = format!("/reviews/{}/versions", review_id);
+    match api_client.execute(Method::GET, &versions_endpoint, None::<&()>).await {
+        Ok(response_text) => {
+            let response_value: Value = serde_json::from_str(&response_text)
+                .map_err(|e| format!("Failed to parse response: {}", e))?;
+            let versions = response_value["data"]
+                .as_array()
+                .ok_or_else(|| "Failed to extract versions from response".to_string())?;
+
+            let content_for = |version: i32| {
+                versions
+                    .iter()
+                    .find(|v| v["version"].as_i64() == Some(version as i64))
+                    .and_then(|v| v["content"].as_str())
+                    .ok_or_else(|| format!("Version {} not found for review {}", version, review_id))
+            };
+
+            let from_content = content_for(from_version)?;
+            let to_content = content_for(to_version)?;
+            Ok(unified_review_diff(
+                from_content,
+                to_content,
+                &format!("review_{}@v{}", review_id, from_version),
+                &format!("review_{}@v{}", review_id, to_version),
+            ))
+        }
+        Err(e) if e.status() == Some(StatusCode::NOT_FOUND) => {
+            info!("Review versions endpoint not found, diffing local copy against server");
+            let response_text = api_client.get(&format!("/reviews/{}", review_id)).await?;
+            let response_value: Value = serde_json::from_str(&response_text)
+                .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+            let product_id = response_value["data"]["review"]["product_id"]
+                .as_i64()
+                .ok_or_else(|| "Failed to extract product ID from response".to_string())?;
+            let server_content = response_value["data"]["content"]
+                .as_str()
+                .ok_or_else(|| "Failed to extract content from response".to_string())?;
+
+            let local_path = get_review_local_path(product_id as i32, Some(review_id))?;
+            let local_content = fs::read_to_string(&local_path)
+                .map_err(|e| format!("Failed to read local review copy: {}", e))?;
+
+            Ok(unified_review_diff(
+                &local_content,
+                server_content,
+                &format!("review_{} (local)", review_id),
+                &format!("review_{} (server)", review_id),
+            ))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{mock_server_with, temp_dir, test_client, MockResponse, ReviewsDirOverride};
+
+    #[test]
+    fn local_image_checksum_detects_corruption() {
+        use sha2::{Digest, Sha256};
+        let dir = temp_dir();
+        let path = dir.join("tile.png");
+        // Spans several read chunks
+        let mut bytes: Vec<u8> = (0..UPLOAD_CHUNK_SIZE * 2 + 17).map(|i| (i % 251) as u8).collect();
+        fs::write(&path, &bytes).unwrap();
+        let original = verify_local_image(path.display().to_string()).unwrap();
+
+        bytes[UPLOAD_CHUNK_SIZE + 3] ^= 0xff;
+        fs::write(&path, &bytes).unwrap();
+        let corrupted = verify_local_image(path.display().to_string()).unwrap();
+
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(corrupted, format!("{:x}", Sha256::digest(&bytes)));
+        assert_ne!(original, corrupted);
+        assert_eq!(original.len(), 64);
+    }
+
+    #[tokio::test]
+    async fn upload_rejects_a_checksum_the_server_disagrees_with() {
+        let server = mock_server_with(|_| {
+            MockResponse::json(201, format!(r#"{{"data":["tile.png"],"sha256":"{}"}}"#, "0".repeat(64)))
+        })
+        .await;
+        let client = test_client(&server.base_url).await;
+        let dir = temp_dir();
+        let path = dir.join("tile.png");
+        fs::write(&path, b"tile bytes").unwrap();
+        let checksum = sha256_file(&path).unwrap();
+
+        let result = upload_image(&client, 5, &path).await;
+
+        let _ = fs::remove_dir_all(&dir);
+        let error = result.unwrap_err();
+        assert!(error.starts_with("Image was corrupted in transit"), "{error}");
+        assert!(error.contains(&checksum), "{error}");
+        let received = server.received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert!(received[0].body.contains(&checksum));
+    }
+
+    #[test]
+    fn missing_image_checksum_is_an_error() {
+        assert!(sha256_file(Path::new("/nonexistent/tile.png")).is_err());
+    }
+
+    #[test]
+    fn team_reviews_are_filtered_by_status_only_when_given() {
+        assert_eq!(team_reviews_endpoint(None), "/reviews/team_lead");
+        assert_eq!(team_reviews_endpoint(Some("pending")), "/reviews/team_lead?status=pending");
+    }
+
+    #[tokio::test]
+    async fn draft_is_compared_with_the_server_copy() {
+        let _reviews = ReviewsDirOverride::temp().await;
+        fs::write(get_review_local_path(8, None).unwrap(), "<p>one</p>\n<p>two</p>\n").unwrap();
+        let server = mock_server_with(|request| {
+            let (content, updated_at) = match request.path.as_str() {
+                "/reviews/1" => ("<p>one</p>\r\n<p>two</p>\r\n", "2000-01-01T00:00:00Z"),
+                "/reviews/2" => ("<p>one</p>\n", "2000-01-01T00:00:00Z"),
+                _ => ("<p>one</p>\n<p>three</p>\n<p>four</p>\n", "2999-01-01T00:00:00"),
+            };
+            let review = serde_json::json!({ "content": content, "review": { "product_id": 8, "updated_at": updated_at } });
+            MockResponse::json(200, serde_json::json!({ "data": review }).to_string())
+        })
+        .await;
+        let api_client = test_client(&server.base_url).await;
+
+        let identical = compare_draft(&api_client, 8, 1).await.unwrap();
+        assert!(identical.identical && !identical.local_newer);
+        assert_eq!(identical.diff_summary, "Local draft matches the server copy");
+
+        let local_newer = compare_draft(&api_client, 8, 2).await.unwrap();
+        assert!(!local_newer.identical && local_newer.local_newer);
+        assert_eq!(local_newer.diff_summary, "1 line(s) added, 0 line(s) removed in the local draft");
+
+        let server_newer = compare_draft(&api_client, 8, 3).await.unwrap();
+        assert!(!server_newer.identical && !server_newer.local_newer);
+        assert_eq!(server_newer.diff_summary, "1 line(s) added, 2 line(s) removed in the local draft");
+        assert!(server_newer.server_updated_at.is_some_and(|t| t.starts_with("2999-01-01")));
+    }
+
+    #[tokio::test]
+    async fn preview_inlines_local_images_as_data_uris() {
+        let _reviews = ReviewsDirOverride::temp().await;
+        let image_dir = get_review_image_dir(12, None).unwrap();
+        fs::write(image_dir.join("north.png"), b"png bytes").unwrap();
+        fs::write(image_dir.join("notes.txt"), b"text").unwrap();
+        fs::write(
+            get_review_local_path(12, None).unwrap(),
+            r#"<p>Tile</p><img src="north.png"><img src="notes.txt"><script>alert(1)</script>"#,
+        )
+        .unwrap();
+
+        let preview = render_preview(&[], 12).unwrap();
+
+        let expected = format!(r#"<img src="data:image/png;base64,{}">"#, base64::engine::general_purpose::STANDARD.encode(b"png bytes"));
+        assert!(preview.contains(&expected), "{preview}");
+        assert!(preview.contains("<p>Tile</p>"), "{preview}");
+        // Not an image, so its source is left as written
+        assert!(preview.contains(r#"<img src="notes.txt">"#), "{preview}");
+        assert!(!preview.contains("<script>"), "{preview}");
+        assert!(preview.starts_with("<!DOCTYPE html>"));
+    }
+
+    #[test]
+    fn unwritable_reviews_dir_is_an_error_not_a_panic() {
+        // Nothing can be created beneath a regular file, even as root
+        let blocker = std::env::temp_dir().join(format!("elevation_manager_blocker_{}", uuid::Uuid::new_v4()));
+        fs::write(&blocker, b"").unwrap();
+        let reviews = ReviewsDirOverride::blocking_at(blocker.join("reviews"));
+
+        let local_path = get_review_local_path(1, Some(2));
+        let image_dir = get_review_image_dir(1, None);
+        drop(reviews);
+        fs::remove_file(&blocker).unwrap();
+
+        assert!(local_path.is_err_and(|e| e.contains("is not writable")));
+        assert!(image_dir.is_err_and(|e| e.contains("is not writable")));
+    }
+
+    #[tokio::test]
+    async fn downloaded_image_lands_in_the_review_image_dir() {
+        let reviews = ReviewsDirOverride::temp().await;
+        let server = mock_server_with(|request| match request.path.as_str() {
+            "/reviews/3" => MockResponse::json(200, r#"{"data":{"review":{"product_id":7}}}"#),
+            "/reviews/3/image/site.png" if request.header("If-None-Match") == Some("\"v1\"") => {
+                MockResponse::bytes(304, b"")
+            }
+            "/reviews/3/image/site.png" => MockResponse::bytes(200, b"png bytes").header("ETag", "\"v1\""),
+            _ => MockResponse::json(404, "{}"),
+        })
+        .await;
+        let api_client = test_client(&server.base_url).await;
+
+        let product_id = review_product_id(&api_client, 3).await.unwrap();
+        let (path, cached) = cache_review_image(&api_client, 3, product_id, "site.png").await.unwrap();
+        assert_eq!(path, reviews.path().join("7").join("images").join("3").join("site.png"));
+        assert!(!cached);
+        assert_eq!(fs::read(&path).unwrap(), b"png bytes");
+
+        // The stored ETag makes the second download a 304
+        let (_, cached) = cache_review_image(&api_client, 3, product_id, "site.png").await.unwrap();
+        assert!(cached);
+    }
+
+    #[tokio::test]
+    async fn all_review_images_are_archived_with_a_manifest() {
+        let reviews = ReviewsDirOverride::temp().await;
+        let server = mock_server_with(|request| match request.path.as_str() {
+            "/reviews/4" => MockResponse::json(200, r#"{"data":{"review":{"product_id":7}}}"#),
+            "/reviews/4/images" => MockResponse::json(200, r#"{"data":["c.png","a.png","b.png","a.png"]}"#),
+            path => match path.strip_prefix("/reviews/4/image/") {
+                Some(filename) => MockResponse::bytes(200, filename.as_bytes()),
+                None => MockResponse::json(404, "{}"),
+            },
+        })
+        .await;
+        let api_client = test_client(&server.base_url).await;
+
+        let archive = archive_review_images(&api_client, 4).await.unwrap();
+
+        let image_dir = reviews.path().join("7").join("images").join("4");
+        assert!(archive.failures.is_empty() && archive.skipped.is_empty());
+        for filename in ["a.png", "b.png", "c.png"] {
+            assert_eq!(fs::read(image_dir.join(filename)).unwrap(), filename.as_bytes());
+        }
+        assert_eq!(archive.paths.len(), 3);
+        assert_eq!(archive.manifest_path, image_dir.join("manifest.json").to_string_lossy());
+        let manifest: Value = serde_json::from_str(&fs::read_to_string(&archive.manifest_path).unwrap()).unwrap();
+        let mut names: Vec<&String> = manifest.as_object().unwrap().keys().collect();
+        names.sort();
+        assert_eq!(names, ["a.png", "b.png", "c.png"]);
+        assert_eq!(manifest["b.png"], image_dir.join("b.png").to_string_lossy().as_ref());
+    }
+
+    #[tokio::test]
+    async fn review_is_created_from_a_file() {
+        let reviews = ReviewsDirOverride::temp().await;
+        let source = reviews.path().join("import.html");
+        fs::write(&source, "<p>Voids in tile 3</p>").unwrap();
+        let server = mock_server_with(|request| match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/users/me") => MockResponse::json(200, r#"{"data":{"id":3}}"#),
+            ("POST", "/reviews") => MockResponse::json(201, r#"{"data":42}"#),
+            _ => MockResponse::json(404, "{}"),
+        })
+        .await;
+        let api_client = test_client(&server.base_url).await;
+
+        let review_id = submit_file(&api_client, &[], 7, &source.to_string_lossy(), "In Review").await;
+        assert_eq!(review_id, Ok(42));
+        let created = server.received.lock().unwrap().last().cloned().unwrap();
+        let payload: Value = serde_json::from_str(&created.body).unwrap();
+        assert_eq!(payload["content"], "<p>Voids in tile 3</p>");
+        assert_eq!(payload["product_id"], 7);
+        assert_eq!(payload["review_status"], "Pending");
+        let local_copy = reviews.path().join("7").join("review_42.html");
+        assert_eq!(fs::read_to_string(local_copy).unwrap(), "<p>Voids in tile 3</p>");
+
+        let missing = reviews.path().join("missing.html");
+        let error = submit_file(&api_client, &[], 7, &missing.to_string_lossy(), "In Review").await;
+        assert!(error.is_err_and(|e| e.starts_with("Review file not found")));
+    }
+
+    #[test]
+    fn review_diff_marks_added_and_removed_lines() {
+        let old = "<h1>Review</h1>\n<p>Elevation looks good</p>\n<p>Checked tiles 1-4</p>\n";
+        let new = "<h1>Review</h1>\n<p>Elevation has voids</p>\n<p>Checked tiles 1-4</p>\n";
+        let diff = unified_review_diff(old, new, "review_1@v1", "review_1@v2");
+
+        assert!(diff.starts_with("--- review_1@v1\n+++ review_1@v2\n"));
+        assert!(diff.contains("\n-<p>Elevation looks good</p>\n"));
+        assert!(diff.contains("\n+<p>Elevation has voids</p>\n"));
+        assert!(diff.contains("\n <h1>Review</h1>\n"));
+    }
+}
@@ -0,0 +1,124 @@
+// src-tauri/src/commands/session.rs
+
+use crate::auth::login::AuthState;
+use crate::commands::settings::{SecuritySettings, Settings};
+use crate::services::api_client::ApiClient;
+use log::info;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// How often the monitor checks for inactivity and session expiry
+const SESSION_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Why a session was ended by the monitor
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SessionEnd {
+    Locked,
+    Expired,
+}
+
+/// Tracks user activity and enforces `SecuritySettings` lock/session timeouts.
+/// Settings are re-read on every check, so changes apply without a restart.
+pub struct SessionMonitor {
+    security: RwLock<SecuritySettings>,
+    last_activity: Mutex<Instant>,
+    session_started: Mutex<Option<Instant>>,
+}
+
+impl Default for SessionMonitor {
+    fn default() -> Self {
+        Self::new(Settings::default().security)
+    }
+}
+
+impl SessionMonitor {
+    pub fn new(security: SecuritySettings) -> Self {
+        Self {
+            security: RwLock::new(security),
+            last_activity: Mutex::new(Instant::now()),
+            session_started: Mutex::new(None),
+        }
+    }
+
+    pub fn update_settings(&self, security: SecuritySettings) {
+        info!(
+            "Session settings updated (auto_lock: {}, lock_timeout: {}m, session_timeout: {}m)",
+            security.auto_lock, security.lock_timeout, security.session_timeout
+        );
+        *self.security.write().unwrap_or_else(|e| e.into_inner()) = security;
+    }
+
+    pub fn record_activity(&self) {
+        *self.last_activity.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+    }
+
+    // Decide whether the current session should end. A newly seen login
+    // starts the session clock and counts as activity.
+    fn check(&self, logged_in: bool, now: Instant) -> Option<SessionEnd> {
+        let mut started = self.session_started.lock().unwrap_or_else(|e| e.into_inner());
+        if !logged_in {
+            *started = None;
+            return None;
+        }
+        let session_start = *started.get_or_insert_with(|| {
+            self.record_activity();
+            now
+        });
+
+        let security = self.security.read().unwrap_or_else(|e| e.into_inner()).clone();
+        let last_activity = *self.last_activity.lock().unwrap_or_else(|e| e.into_inner());
+        let minutes = |m: i32| Duration::from_secs(m as u64 * 60);
+
+        let end = if security.session_timeout > 0
+            && now.duration_since(session_start) >= minutes(security.session_timeout)
+        {
+            Some(SessionEnd::Expired)
+        } else if security.auto_lock
+            && security.lock_timeout > 0
+            && now.duration_since(last_activity) >= minutes(security.lock_timeout)
+        {
+            Some(SessionEnd::Locked)
+        } else {
+            None
+        };
+        if end.is_some() {
+            *started = None;
+        }
+        end
+    }
+}
+
+/// Called by the frontend on user interaction to reset the inactivity timer.
+#[tauri::command]
+pub async fn record_activity(monitor: State<'_, Arc<SessionMonitor>>) -> Result<(), String> {
+    monitor.record_activity();
+    Ok(())
+}
+
+/// Spawn the background task that locks or logs out idle sessions.
+pub fn spawn_session_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(SESSION_CHECK_INTERVAL).await;
+            let monitor = app.state::<Arc<SessionMonitor>>();
+            let logged_in = app.state::<AuthState>().token.read().await.is_some();
+            let Some(end) = monitor.check(logged_in, Instant::now()) else {
+                continue;
+            };
+
+            // Clearing the shared token forces re-authentication
+            app.state::<ApiClient>().clear_token().await;
+            match end {
+                SessionEnd::Locked => {
+                    info!("🔒 Session locked after inactivity");
+                    let _ = app.emit("session_locked", "inactivity");
+                }
+                SessionEnd::Expired => {
+                    info!("👋 Session timed out, logging out");
+                    let _ = app.emit("session_expired", "session_timeout");
+                }
+            }
+        }
+    });
+}
@@ -1,8 +1,12 @@
 // src-tauri/src/commands/settings.rs
 
+use crate::commands::session::SessionMonitor;
 use crate::services::api_client::ApiClient;
-use log::{debug, info};
+use crate::utils::write_atomic;
+use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
 use tauri::State;
 use tauri::{AppHandle, Manager};
 
@@ -47,6 +51,51 @@ pub struct DataSettings {
     pub save_interval: i32,
     pub max_history_items: i32,
     pub clear_cache_on_exit: bool,
+    /// Where review drafts and images are kept; unset means
+    /// `~/.elevation-manager/reviews`
+    #[serde(default)]
+    pub reviews_dir: Option<String>,
+}
+
+// Reviews directory from the applied settings; `None` means the default
+static REVIEWS_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Make the data settings take effect for this process
+pub fn apply_data_settings(data: &DataSettings) {
+    let dir = data
+        .reviews_dir
+        .as_deref()
+        .map(str::trim)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from);
+    if let Some(dir) = &dir {
+        info!("Using reviews directory {}", dir.display());
+    }
+    set_reviews_dir(dir);
+}
+
+/// Override the reviews directory; `None` restores the default
+pub(crate) fn set_reviews_dir(dir: Option<PathBuf>) {
+    *REVIEWS_DIR.write().unwrap_or_else(|e| e.into_inner()) = dir;
+}
+
+/// Directory holding review drafts and images, per `DataSettings::reviews_dir`
+pub fn reviews_dir() -> Result<PathBuf, String> {
+    if let Some(dir) = REVIEWS_DIR.read().unwrap_or_else(|e| e.into_inner()).clone() {
+        return Ok(dir);
+    }
+    let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
+    Ok(home_dir.join(".elevation-manager").join("reviews"))
+}
+
+// Create `dir` if needed and check files can be written there
+fn ensure_writable_dir(dir: &Path) -> Result<(), String> {
+    let not_writable = |e: std::io::Error| format!("Reviews directory {} is not writable: {}", dir.display(), e);
+    std::fs::create_dir_all(dir).map_err(not_writable)?;
+    let probe = dir.join(".write-test");
+    std::fs::write(&probe, b"").map_err(not_writable)?;
+    let _ = std::fs::remove_file(probe);
+    Ok(())
 }
 
 impl Default for Settings {
@@ -78,26 +127,29 @@ impl Default for Settings {
                 save_interval: 5,
                 max_history_items: 100,
                 clear_cache_on_exit: false,
+                reviews_dir: None,
             },
         }
     }
 }
 
+/// Load stored settings, if any
+pub fn load_stored_settings(app_handle: &AppHandle) -> Option<Settings> {
+    let settings_path = app_handle.path().app_data_dir().ok()?.join("settings.json");
+    let contents = std::fs::read_to_string(settings_path).ok()?;
+    serde_json::from_str::<Settings>(&contents).ok()
+}
+
 /// Tauri command to get user settings
 #[tauri::command]
 pub async fn get_settings(app_handle: AppHandle, _api_client: State<'_, ApiClient>) -> Result<String, String> {
     info!("Fetching user settings...");
     
     // Try to load from local storage first
-    if let Ok(stored_settings) = app_handle.path().app_data_dir() {
-        let settings_path = stored_settings.join("settings.json");
-        if let Ok(contents) = std::fs::read_to_string(settings_path) {
-            if let Ok(settings) = serde_json::from_str::<Settings>(&contents) {
-                debug!("Loaded settings from storage");
-                return Ok(serde_json::to_string(&settings)
-                    .map_err(|e| format!("Failed to serialize settings: {}", e))?);
-            }
-        }
+    if let Some(settings) = load_stored_settings(&app_handle) {
+        debug!("Loaded settings from storage");
+        return serde_json::to_string(&settings)
+            .map_err(|e| format!("Failed to serialize settings: {}", e));
     }
     
     // Return default settings if no stored settings found
@@ -113,6 +165,7 @@ pub async fn get_settings(app_handle: AppHandle, _api_client: State<'_, ApiClien
 pub async fn save_settings(
     app_handle: AppHandle,
     _api_client: State<'_, ApiClient>,
+    session_monitor: State<'_, Arc<SessionMonitor>>,
     settings: String,
 ) -> Result<(), String> {
     info!("Saving user settings...");
@@ -120,6 +173,11 @@ pub async fn save_settings(
     // Parse the settings JSON
     let settings: Settings = serde_json::from_str(&settings)
         .map_err(|e| format!("Failed to parse settings: {}", e))?;
+    if let Some(dir) = settings.data.reviews_dir.as_deref().map(str::trim).filter(|dir| !dir.is_empty()) {
+        ensure_writable_dir(Path::new(dir))?;
+    }
+    session_monitor.update_settings(settings.security.clone());
+    apply_data_settings(&settings.data);
 
     // Save to local storage
     if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
@@ -133,7 +191,7 @@ pub async fn save_settings(
         let settings_json = serde_json::to_string_pretty(&settings)
             .map_err(|e| format!("Failed to serialize settings: {}", e))?;
         
-        std::fs::write(settings_path, settings_json)
+        write_atomic(&settings_path, settings_json)
             .map_err(|e| format!("Failed to write settings file: {}", e))?;
         
         debug!("Settings saved to storage: {:?}", settings);
@@ -144,8 +202,14 @@ pub async fn save_settings(
 
 /// Tauri command to reset settings to defaults
 #[tauri::command]
-pub async fn reset_settings(app_handle: AppHandle, _api_client: State<'_, ApiClient>) -> Result<(), String> {
+pub async fn reset_settings(
+    app_handle: AppHandle,
+    _api_client: State<'_, ApiClient>,
+    session_monitor: State<'_, Arc<SessionMonitor>>,
+) -> Result<(), String> {
     info!("Resetting settings to defaults...");
+    session_monitor.update_settings(Settings::default().security);
+    apply_data_settings(&Settings::default().data);
     
     // Delete the settings file if it exists
     if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
@@ -207,6 +271,7 @@ pub async fn export_settings(app_handle: AppHandle, _api_client: State<'_, ApiCl
 pub async fn import_settings(
     app_handle: AppHandle,
     _api_client: State<'_, ApiClient>,
+    session_monitor: State<'_, Arc<SessionMonitor>>,
     settings_data: String,
 ) -> Result<(), String> {
     info!("Importing settings...");
@@ -229,18 +294,18 @@ pub async fn import_settings(
         .map_err(|e| format!("Failed to serialize imported settings: {}", e))?;
 
     // Save the imported settings
-    save_settings(app_handle, _api_client, settings_string).await?;
+    save_settings(app_handle, _api_client, session_monitor, settings_string).await?;
 
     Ok(())
 }
 
 /// Tauri command to apply font size setting
 #[tauri::command]
-pub async fn apply_font_size(fontSize: i32) -> Result<(), String> {
-    info!("Applying font size: {}", fontSize);
+pub async fn apply_font_size(font_size: i32) -> Result<(), String> {
+    info!("Applying font size: {}", font_size);
     
     // Validate font size range
-    if fontSize < 10 || fontSize > 24 {
+    if !(10..=24).contains(&font_size) {
         return Err("Font size must be between 10 and 24".to_string());
     }
     
@@ -266,7 +331,7 @@ pub async fn update_notification_polling(interval: i32) -> Result<(), String> {
     info!("Updating notification polling interval: {}", interval);
     
     // Validate interval range
-    if interval < 10 || interval > 300 {
+    if !(10..=300).contains(&interval) {
         return Err("Polling interval must be between 10 and 300 seconds".to_string());
     }
     
@@ -274,6 +339,131 @@ pub async fn update_notification_polling(interval: i32) -> Result<(), String> {
     Ok(())
 }
 
+/// Tauri command to set (or, with no value, remove) a header sent on every
+/// API request, e.g. `X-Tenant-Id`. Takes effect immediately.
+#[tauri::command]
+pub async fn set_default_header(
+    api_client: State<'_, ApiClient>,
+    key: String,
+    value: Option<String>,
+) -> Result<(), String> {
+    let key = key.trim();
+    if key.is_empty() {
+        return Err("Header name cannot be empty".to_string());
+    }
+    info!("Setting default request header: {}", key);
+    api_client.set_default_header(key, value)
+}
+
+/// Disk used by one top-level entry of the data directory
+#[derive(Debug, Serialize)]
+pub struct DirectoryUsage {
+    pub name: String,
+    pub bytes: u64,
+}
+
+/// Disk used by the app's local data
+#[derive(Debug, Serialize)]
+pub struct StorageUsage {
+    pub root: String,
+    pub total_bytes: u64,
+    /// Per subdirectory of `root`, largest first; loose files are grouped
+    /// under "(files)"
+    pub breakdown: Vec<DirectoryUsage>,
+    /// What `clear_application_cache` would reclaim
+    pub cache_bytes: u64,
+}
+
+// Total size of the regular files under `path`. Symlinks and unreadable
+// entries are skipped with a warning.
+fn directory_size(path: &Path) -> u64 {
+    let entries = match std::fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(e) => {
+            warn!("Skipping unreadable directory {}: {}", path.display(), e);
+            return 0;
+        }
+    };
+    entries
+        .filter_map(|entry| match entry {
+            Ok(entry) => Some(entry.path()),
+            Err(e) => {
+                warn!("Skipping unreadable entry in {}: {}", path.display(), e);
+                None
+            }
+        })
+        .map(|entry| entry_size(&entry))
+        .sum()
+}
+
+fn entry_size(path: &Path) -> u64 {
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_symlink() => {
+            warn!("Skipping symlink {}", path.display());
+            0
+        }
+        Ok(metadata) if metadata.is_dir() => directory_size(path),
+        Ok(metadata) => metadata.len(),
+        Err(e) => {
+            warn!("Skipping unreadable file {}: {}", path.display(), e);
+            0
+        }
+    }
+}
+
+/// Tauri command reporting how much disk review drafts, images and other
+/// local data take up under `~/.elevation-manager`.
+#[tauri::command]
+pub async fn get_storage_usage(app_handle: AppHandle) -> Result<StorageUsage, String> {
+    info!("Computing storage usage...");
+    let root = dirs::home_dir()
+        .ok_or("Could not find home directory")?
+        .join(".elevation-manager");
+
+    let mut breakdown = Vec::new();
+    let mut loose_files = 0;
+    if root.exists() {
+        let entries = std::fs::read_dir(&root).map_err(|e| format!("Failed to read data directory: {}", e))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() && !path.is_symlink() {
+                breakdown.push(DirectoryUsage {
+                    name: entry.file_name().to_string_lossy().into_owned(),
+                    bytes: directory_size(&path),
+                });
+            } else {
+                loose_files += entry_size(&path);
+            }
+        }
+    }
+    if loose_files > 0 {
+        breakdown.push(DirectoryUsage { name: "(files)".to_string(), bytes: loose_files });
+    }
+    // A reviews directory moved elsewhere still counts as local data
+    if let Ok(reviews) = reviews_dir() {
+        if !reviews.starts_with(&root) && reviews.is_dir() {
+            breakdown.push(DirectoryUsage { name: "reviews".to_string(), bytes: directory_size(&reviews) });
+        }
+    }
+    breakdown.sort_by_key(|d| std::cmp::Reverse(d.bytes));
+
+    let cache_bytes = app_handle
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join("cache"))
+        .ok()
+        .filter(|dir| dir.exists())
+        .map(|dir| directory_size(&dir))
+        .unwrap_or(0);
+
+    Ok(StorageUsage {
+        root: root.to_string_lossy().into_owned(),
+        total_bytes: breakdown.iter().map(|d| d.bytes).sum(),
+        breakdown,
+        cache_bytes,
+    })
+}
+
 /// Tauri command to clear application cache
 #[tauri::command]
 pub async fn clear_application_cache(app_handle: AppHandle) -> Result<(), String> {
@@ -286,4 +476,34 @@ pub async fn clear_application_cache(app_handle: AppHandle) -> Result<(), String
     }
     
     Ok(())
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+
+    #[test]
+    fn directory_size_sums_nested_files_and_skips_symlinks() {
+        let dir = temp_dir();
+        std::fs::write(dir.join("a.bin"), [0u8; 100]).unwrap();
+        std::fs::create_dir_all(dir.join("nested/deeper")).unwrap();
+        std::fs::write(dir.join("nested/b.bin"), [0u8; 250]).unwrap();
+        std::fs::write(dir.join("nested/deeper/c.bin"), [0u8; 7]).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(dir.join("a.bin"), dir.join("link.bin")).unwrap();
+
+        let size = directory_size(&dir);
+
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(size, 357);
+    }
+
+    #[test]
+    fn missing_directory_has_no_size() {
+        let dir = temp_dir();
+        let size = directory_size(&dir.join("missing"));
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(size, 0);
+    }
+}
@@ -0,0 +1,68 @@
+// src-tauri/src/commands/shutdown.rs
+
+use crate::commands::notifications::PollingState;
+use crate::commands::reviews::save_review_draft;
+use log::{info, warn};
+use std::sync::Arc;
+use tauri::State;
+
+/// Abort notification polling without waiting. Used from the window close
+/// handler, which can't await; returns whether a task was stopped.
+pub fn abort_polling(polling_state: &PollingState) -> bool {
+    match polling_state.task_handle.try_lock() {
+        Ok(mut task_handle) => match task_handle.take() {
+            Some(handle) => {
+                handle.abort();
+                true
+            }
+            None => false,
+        },
+        Err(_) => {
+            warn!("Polling state busy during shutdown; leaving task to the runtime");
+            false
+        }
+    }
+}
+
+/// Stop background work and write out the open draft, if the frontend has
+/// unsaved changes. The frontend awaits this before closing the window.
+/// There is no queue of offline operations to flush: requests are sent as
+/// they are made, and the draft is the only state not yet on disk.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn prepare_shutdown(
+    polling_state: State<'_, Arc<PollingState>>,
+    dirty_draft_product_id: Option<i32>,
+    dirty_draft_content: Option<String>,
+) -> Result<(), String> {
+    info!("Preparing for shutdown...");
+    if let Some(handle) = polling_state.task_handle.lock().await.take() {
+        handle.abort();
+        info!("Notification polling stopped");
+    }
+
+    if let (Some(product_id), Some(content)) = (dirty_draft_product_id, dirty_draft_content) {
+        save_review_draft(product_id, content)?;
+        info!("Flushed unsaved draft for product {}", product_id);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn abort_polling_aborts_and_takes_the_task() {
+        let polling_state = PollingState::default();
+        let task = tokio::spawn(std::future::pending::<()>());
+        let abort_handle = task.abort_handle();
+        *polling_state.task_handle.lock().await = Some(task);
+
+        assert!(abort_polling(&polling_state));
+        assert!(polling_state.task_handle.lock().await.is_none());
+        tokio::task::yield_now().await;
+        assert!(abort_handle.is_finished());
+
+        assert!(!abort_polling(&polling_state), "nothing left to stop");
+    }
+}
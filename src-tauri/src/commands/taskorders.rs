@@ -1,7 +1,17 @@
+use crate::commands::operations::{OperationProgress, OperationRegistry};
+use crate::commands::products::{parse_products_page, stream_list_to_window};
+use crate::commands::team::fetch_latest_review;
 use crate::services::api_client::ApiClient;
-use log::info;
-use tauri::State;
+use crate::utils::safe_file_name;
+use log::{error, info, warn};
 use serde::Serialize;
+use serde_json::{json, Value};
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::sync::Arc;
+use tauri::{Emitter, Manager, State, Window};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
 
 #[derive(Serialize)]
 struct NewTaskOrderRequest {
@@ -26,6 +36,7 @@ struct UpdateTaskOrderRequest {
 }
 
 #[tauri::command(rename_all="snake_case")]
+#[allow(clippy::too_many_arguments)]
 pub async fn create_task_order(
     api_client: State<'_, ApiClient>,
     contract_id: Option<i32>,
@@ -61,6 +72,18 @@ pub async fn get_all_taskorders(
     api_client.get("/taskorders").await
 }
 
+/// Stream every task order to the window as `list_stream_items` batches
+/// tagged with `stream_id`. Returns the number of task orders.
+#[tauri::command(rename_all="snake_case")]
+pub async fn stream_taskorders(
+    window: Window,
+    api_client: State<'_, ApiClient>,
+    stream_id: String,
+) -> Result<usize, String> {
+    info!("Streaming all task orders ({})...", stream_id);
+    stream_list_to_window(&window, &api_client, "/taskorders", &stream_id).await
+}
+
 #[tauri::command(rename_all="snake_case")]
 pub async fn get_task_order(
     api_client: State<'_, ApiClient>,
@@ -88,7 +111,199 @@ pub async fn check_task_order_edit_permission(
     api_client.get(&format!("/taskorders/{}/permissions", taskorder_id)).await
 }
 
+#[derive(Debug, Serialize)]
+pub struct TaskOrderBundleSummary {
+    pub output_path: String,
+    pub products: usize,
+    pub reviews: usize,
+    pub images: usize,
+    /// Reviews or images that could not be fetched and were left out
+    pub warnings: Vec<String>,
+}
+
+type BundleWriter = ZipWriter<BufWriter<File>>;
+
+fn add_zip_entry(zip: &mut BundleWriter, name: &str, method: CompressionMethod, bytes: &[u8]) -> Result<(), String> {
+    zip.start_file(name, SimpleFileOptions::default().compression_method(method))
+        .and_then(|_| zip.write_all(bytes).map_err(Into::into))
+        .map_err(|e| format!("Failed to write {} to bundle: {}", name, e))
+}
+
+// Folder for a product inside the bundle: its id plus a filesystem-safe site id
+fn product_folder(product: &Value, product_id: i64) -> String {
+    let site: String = product["site_id"]
+        .as_str()
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    if site.is_empty() {
+        format!("products/{}", product_id)
+    } else {
+        format!("products/{}_{}", product_id, site)
+    }
+}
+
+// Write one product's latest review and images into the bundle and return
+// its manifest entry. Fetch failures become warnings rather than errors.
+async fn add_product_to_bundle(
+    api_client: &ApiClient,
+    zip: &mut BundleWriter,
+    product: &Value,
+    summary: &mut TaskOrderBundleSummary,
+) -> Result<Value, String> {
+    let product_id = product["id"]
+        .as_i64()
+        .ok_or_else(|| "Product without an id in task order".to_string())?;
+    let folder = product_folder(product, product_id);
+    let mut entry = json!({ "product_id": product_id, "site_id": product["site_id"], "folder": folder });
+
+    let Some(latest) = fetch_latest_review(api_client, product_id).await else {
+        return Ok(entry);
+    };
+    let Some(review_id) = latest["id"].as_i64() else {
+        return Ok(entry);
+    };
+    entry["review_id"] = json!(review_id);
+    entry["review_status"] = latest["review_status"].clone();
+
+    match api_client.get_json::<Value>(&format!("/reviews/{}", review_id)).await {
+        Ok(review) => {
+            let content = review["content"].as_str().unwrap_or_default();
+            add_zip_entry(zip, &format!("{}/review.html", folder), CompressionMethod::Deflated, content.as_bytes())?;
+            summary.reviews += 1;
+        }
+        Err(e) => summary.warnings.push(format!("Review {} of product {}: {}", review_id, product_id, e)),
+    }
+
+    let filenames: Vec<String> = match api_client.get_json(&format!("/reviews/{}/images", review_id)).await {
+        Ok(filenames) => filenames,
+        Err(e) => {
+            summary.warnings.push(format!("Images of review {}: {}", review_id, e));
+            Vec::new()
+        }
+    };
+    let mut images = Vec::new();
+    for filename in filenames {
+        if safe_file_name(&filename).is_err() {
+            summary.warnings.push(format!("Skipped image with invalid name {:?} in review {}", filename, review_id));
+            continue;
+        }
+        let endpoint = format!("/reviews/{}/image/{}", review_id, filename);
+        match api_client.get_bytes_if_changed(&endpoint, None, None).await {
+            Ok(Some(download)) => {
+                // Images are already compressed
+                add_zip_entry(zip, &format!("{}/images/{}", folder, filename), CompressionMethod::Stored, &download.bytes)?;
+                images.push(filename);
+                summary.images += 1;
+            }
+            Ok(None) => {}
+            Err(e) => summary.warnings.push(format!("Image {} of review {}: {}", filename, review_id, e)),
+        }
+    }
+    entry["images"] = json!(images);
+    Ok(entry)
+}
+
+// The bundle is written here and renamed into place once complete, so a
+// failed export never clobbers an existing file
+fn partial_bundle_path(output_path: &str) -> String {
+    format!("{}.partial", output_path)
+}
+
+async fn write_task_order_bundle(
+    window: &Window,
+    api_client: &ApiClient,
+    taskorder_id: i32,
+    output_path: &str,
+    operation_id: &str,
+) -> Result<TaskOrderBundleSummary, String> {
+    let task_order: Value = api_client
+        .get_json(&format!("/taskorders/{}", taskorder_id))
+        .await
+        .map_err(|e| format!("Failed to fetch task order: {}", e))?;
+    let products: Value = api_client
+        .get_json(&format!("/products?taskorder_id={}", taskorder_id))
+        .await
+        .map_err(|e| format!("Failed to fetch task order products: {}", e))?;
+    let (products, _) = parse_products_page(products)?;
+
+    let operations = window.state::<Arc<OperationRegistry>>();
+    let operation = operations.register(operation_id);
+    let file = File::create(partial_bundle_path(output_path)).map_err(|e| format!("Failed to create bundle file: {}", e))?;
+    let mut zip = ZipWriter::new(BufWriter::new(file));
+    let mut summary = TaskOrderBundleSummary {
+        output_path: output_path.to_string(),
+        products: products.len(),
+        reviews: 0,
+        images: 0,
+        warnings: Vec::new(),
+    };
+
+    // Each product's files go into the archive as soon as they are fetched
+    let mut manifest_products = Vec::with_capacity(products.len());
+    for (index, product) in products.iter().enumerate() {
+        if operation.token.is_cancelled() {
+            return Err("Task order export cancelled".to_string());
+        }
+        manifest_products.push(add_product_to_bundle(api_client, &mut zip, product, &mut summary).await?);
+        let _ = window.emit(
+            "operation_progress",
+            OperationProgress { operation_id: operation_id.to_string(), completed: index + 1, total: products.len() },
+        );
+    }
+
+    let manifest = json!({
+        "taskorder_id": taskorder_id,
+        "task_order": task_order,
+        "exported_at": chrono::Utc::now().to_rfc3339(),
+        "products": manifest_products,
+        "warnings": summary.warnings,
+    });
+    let manifest = serde_json::to_vec_pretty(&manifest).map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    add_zip_entry(&mut zip, "manifest.json", CompressionMethod::Deflated, &manifest)?;
+    zip.finish()
+        .and_then(|writer| writer.into_inner().map_err(|e| e.into_error().into()))
+        .and_then(|file| file.sync_all().map_err(Into::into))
+        .map_err(|e| format!("Failed to finish bundle: {}", e))?;
+    fs::rename(partial_bundle_path(output_path), output_path).map_err(|e| format!("Failed to save bundle: {}", e))?;
+    Ok(summary)
+}
+
+/// Export a task order, its products and each product's latest review
+/// (content and images) as a ZIP with a `manifest.json`. Progress is emitted
+/// as `operation_progress`; `cancel_operation(operation_id)` stops the export.
 #[tauri::command(rename_all="snake_case")]
+pub async fn export_task_order_bundle(
+    window: Window,
+    api_client: State<'_, ApiClient>,
+    taskorder_id: i32,
+    output_path: String,
+    operation_id: Option<String>,
+) -> Result<TaskOrderBundleSummary, String> {
+    info!("Exporting task order {} to {}", taskorder_id, output_path);
+    let operation_id = operation_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    match write_task_order_bundle(&window, &api_client, taskorder_id, &output_path, &operation_id).await {
+        Ok(summary) => {
+            if !summary.warnings.is_empty() {
+                warn!("Task order {} exported with {} warnings", taskorder_id, summary.warnings.len());
+            }
+            info!(
+                "Exported task order {}: {} products, {} reviews, {} images",
+                taskorder_id, summary.products, summary.reviews, summary.images
+            );
+            Ok(summary)
+        }
+        Err(e) => {
+            error!("Failed to export task order {}: {}", taskorder_id, e);
+            let _ = fs::remove_file(partial_bundle_path(&output_path));
+            Err(e)
+        }
+    }
+}
+
+#[tauri::command(rename_all="snake_case")]
+#[allow(clippy::too_many_arguments)]
 pub async fn update_task_order(
     api_client: State<'_, ApiClient>,
     taskorder_id: i32,
@@ -112,3 +327,51 @@ pub async fn update_task_order(
 
     api_client.put(&format!("/taskorders/{}", taskorder_id), &request).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{mock_server_with, temp_dir, test_client, MockResponse};
+    use std::io::Read;
+
+    #[tokio::test]
+    async fn product_review_and_images_are_written_to_the_bundle() {
+        let server = mock_server_with(|request| match request.path.as_str() {
+            "/reviews/product/7" => MockResponse::json(200, r#"{"data":[{"id":3,"review_status":"approved"}]}"#),
+            "/reviews/3" => MockResponse::json(200, r#"{"data":{"id":3,"content":"<p>Looks good</p>"}}"#),
+            "/reviews/3/images" => MockResponse::json(200, r#"{"data":["north.png","../escape.png"]}"#),
+            "/reviews/3/image/north.png" => MockResponse::bytes(200, b"png bytes"),
+            _ => MockResponse::json(404, r#"{"message":"Not found"}"#),
+        })
+        .await;
+        let api_client = test_client(&server.base_url).await;
+        let dir = temp_dir();
+        let path = dir.join("bundle.zip");
+        let mut zip = ZipWriter::new(BufWriter::new(File::create(&path).unwrap()));
+        let mut summary = TaskOrderBundleSummary {
+            output_path: path.display().to_string(),
+            products: 0,
+            reviews: 0,
+            images: 0,
+            warnings: Vec::new(),
+        };
+
+        let product = json!({ "id": 7, "site_id": "AB 12" });
+        let entry = add_product_to_bundle(&api_client, &mut zip, &product, &mut summary).await.unwrap();
+        zip.finish().unwrap();
+
+        assert_eq!(entry["folder"], "products/7_AB_12");
+        assert_eq!(entry["review_id"], 3);
+        assert_eq!(entry["images"], json!(["north.png"]));
+        assert_eq!((summary.reviews, summary.images, summary.warnings.len()), (1, 1, 1));
+
+        let mut archive = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let mut names: Vec<String> = archive.file_names().map(String::from).collect();
+        names.sort();
+        assert_eq!(names, ["products/7_AB_12/images/north.png", "products/7_AB_12/review.html"]);
+        let mut review = String::new();
+        archive.by_name("products/7_AB_12/review.html").unwrap().read_to_string(&mut review).unwrap();
+        assert_eq!(review, "<p>Looks good</p>");
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
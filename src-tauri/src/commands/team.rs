@@ -1,8 +1,28 @@
+use crate::commands::users::{list_items, PartialFetch};
 use crate::services::api_client::ApiClient;
-use log::{debug, error, info};
-use serde::Serialize;
+use crate::utils::parse_server_date;
+use futures::stream::{self, StreamExt};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
 use tauri::State;
 
+/// Team lists rarely change, so they are cached briefly.
+const TEAMS_CACHE_TTL: Duration = Duration::from_secs(120);
+
+/// Maximum number of per-product review lookups in flight at once.
+const TEAM_PRODUCT_REVIEW_CONCURRENCY: usize = 8;
+
+/// Maximum number of per-team lookups in flight at once.
+const TEAM_FETCH_CONCURRENCY: usize = 4;
+
+/// Maximum number of member additions in flight at once.
+const TEAM_MEMBER_ADD_CONCURRENCY: usize = 4;
+
 #[derive(Serialize)]
 struct NewTeam {
     pub name: String,
@@ -12,6 +32,7 @@ struct NewTeam {
 pub async fn create_team(api_client: State<'_, ApiClient>, name: String) -> Result<String, String> {
     info!("Creating a new team: {name}");
     let response = api_client.post("/teams", &NewTeam { name: name.clone() }).await?;
+    api_client.invalidate_cache("/teams");
     let parsed_response: serde_json::Value = serde_json::from_str(&response).map_err(|e| e.to_string())?;
     if let Some(team_id) = parsed_response["data"].as_i64() {
         let response_json = serde_json::json!({
@@ -36,31 +57,120 @@ pub async fn get_team(api_client: State<'_, ApiClient>, team_id: i32) -> Result<
 #[tauri::command(rename_all = "snake_case")]
 pub async fn get_all_teams(api_client: State<'_, ApiClient>) -> Result<String, String> {
     info!("Fetching all teams...");
-    api_client.get("/teams").await
+    api_client.cached_get("/teams", TEAMS_CACHE_TTL).await
+}
+
+#[derive(Serialize)]
+struct UpdateTeamRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active: Option<bool>,
 }
 
 #[tauri::command(rename_all = "snake_case")]
 pub async fn update_team(api_client: State<'_, ApiClient>, team_id: i32, name: String) -> Result<(), String> {
-    info!("Updating team ID {} with name: {}", team_id, name);
-    api_client.put(&format!("/teams/{}", team_id), &NewTeam { name }).await?;
+    update_team_details(api_client, team_id, Some(name), None, None).await
+}
+
+/// Update any of a team's name, description and active flag; fields left as
+/// `None` are not sent, so they keep their current values.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn update_team_details(
+    api_client: State<'_, ApiClient>,
+    team_id: i32,
+    name: Option<String>,
+    description: Option<String>,
+    active: Option<bool>,
+) -> Result<(), String> {
+    let request = UpdateTeamRequest { name, description, active };
+    if request.name.is_none() && request.description.is_none() && request.active.is_none() {
+        return Err("No team fields to update".to_string());
+    }
+    info!("Updating team ID {}", team_id);
+    api_client.put(&format!("/teams/{}", team_id), &request).await?;
+    api_client.invalidate_cache("/teams");
     Ok(())
 }
 
 #[tauri::command(rename_all = "snake_case")]
 pub async fn delete_team(api_client: State<'_, ApiClient>, team_id: i32) -> Result<String, String> {
     info!("Deleting team ID: {}", team_id);
-    api_client.delete(&format!("/teams/{}", team_id)).await
+    let response = api_client.delete(&format!("/teams/{}", team_id)).await?;
+    api_client.invalidate_cache("/teams");
+    Ok(response)
+}
+
+/// Role of a user within a team. Serializes to the same strings the backend
+/// has always received.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TeamRole {
+    Viewer,
+    Member,
+    Editor,
+    SeniorMember,
+    #[serde(rename = "team_lead")]
+    Lead,
+    Manager,
+    Admin,
+}
+
+impl TeamRole {
+    const ALL: [TeamRole; 7] = [
+        TeamRole::Viewer,
+        TeamRole::Member,
+        TeamRole::Editor,
+        TeamRole::SeniorMember,
+        TeamRole::Lead,
+        TeamRole::Manager,
+        TeamRole::Admin,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TeamRole::Viewer => "viewer",
+            TeamRole::Member => "member",
+            TeamRole::Editor => "editor",
+            TeamRole::SeniorMember => "senior_member",
+            TeamRole::Lead => "team_lead",
+            TeamRole::Manager => "manager",
+            TeamRole::Admin => "admin",
+        }
+    }
+}
+
+impl fmt::Display for TeamRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TeamRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TeamRole::ALL
+            .into_iter()
+            .find(|role| role.as_str() == s)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = TeamRole::ALL.iter().map(TeamRole::as_str).collect();
+                format!("Unknown team role '{}'. Expected one of: {}", s, valid.join(", "))
+            })
+    }
 }
 
 #[derive(Serialize)]
 struct AddUser {
     pub user_id: i32,
-    pub role: String,
+    pub role: TeamRole,
 }
 
 #[derive(Serialize)]
 struct UpdateUserRole {
-    pub role: String,
+    pub role: TeamRole,
 }
 
 #[tauri::command(rename_all = "snake_case")]
@@ -71,11 +181,67 @@ pub async fn get_team_users(api_client: State<'_, ApiClient>, team_id: i32) -> R
 
 #[tauri::command(rename_all = "snake_case")]
 pub async fn add_user_to_team(api_client: State<'_, ApiClient>, team_id: i32, user_id: i32, role: String) -> Result<(), String> {
+    let role: TeamRole = role.parse()?;
     info!("Adding user {} to team {} with role {}", user_id, team_id, role);
     api_client.post(&format!("/teams/{}/users", team_id), &AddUser { user_id, role }).await?;
     Ok(())
 }
 
+#[derive(Debug, Deserialize)]
+pub struct TeamMemberInput {
+    pub user_id: i32,
+    pub role: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TeamMemberAddResult {
+    pub user_id: i32,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// Add several users to a team at once. Invalid roles are rejected locally
+/// and the remaining adds run concurrently; each user gets its own result.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn add_users_to_team(
+    api_client: State<'_, ApiClient>,
+    team_id: i32,
+    members: Vec<TeamMemberInput>,
+) -> Result<Vec<TeamMemberAddResult>, String> {
+    info!("Adding {} user(s) to team {}", members.len(), team_id);
+    Ok(add_members(&api_client, team_id, members).await)
+}
+
+// Add members a few at a time, keeping results in input order; invalid roles
+// fail locally without a request
+async fn add_members(api_client: &ApiClient, team_id: i32, members: Vec<TeamMemberInput>) -> Vec<TeamMemberAddResult> {
+    let endpoint = format!("/teams/{}/users", team_id);
+    stream::iter(members)
+        .map(|member| {
+            let endpoint = &endpoint;
+            async move {
+                let outcome = match member.role.parse::<TeamRole>() {
+                    Ok(role) => api_client
+                        .post(endpoint, &AddUser { user_id: member.user_id, role })
+                        .await
+                        .map(|_| ()),
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = &outcome {
+                    error!("Failed to add user {} to team {}: {}", member.user_id, team_id, e);
+                }
+                TeamMemberAddResult {
+                    user_id: member.user_id,
+                    success: outcome.is_ok(),
+                    error: outcome.err(),
+                }
+            }
+        })
+        .buffered(api_client.batch_concurrency(TEAM_MEMBER_ADD_CONCURRENCY))
+        .collect()
+        .await
+}
+
 #[tauri::command(rename_all = "snake_case")]
 pub async fn remove_user_from_team(api_client: State<'_, ApiClient>, team_id: i32, user_id: i32) -> Result<(), String> {
     info!("Removing user {} from team {}", user_id, team_id);
@@ -83,10 +249,71 @@ pub async fn remove_user_from_team(api_client: State<'_, ApiClient>, team_id: i3
     Ok(())
 }
 
+async fn set_team_role(api_client: &ApiClient, team_id: i32, user_id: i32, role: TeamRole) -> Result<(), String> {
+    api_client.put(&format!("/teams/{}/users/{}", team_id, user_id), &UpdateUserRole { role }).await?;
+    Ok(())
+}
+
 #[tauri::command(rename_all = "snake_case")]
 pub async fn update_user_role(api_client: State<'_, ApiClient>, team_id: i32, user_id: i32, role: String) -> Result<(), String> {
+    let role: TeamRole = role.parse()?;
     info!("Updating user {} role in team {} to {}", user_id, team_id, role);
-    api_client.put(&format!("/teams/{}/users/{}", team_id, user_id), &UpdateUserRole { role }).await?;
+    set_team_role(&api_client, team_id, user_id, role).await
+}
+
+#[derive(Deserialize)]
+struct TeamMember {
+    user_id: i32,
+    role: String,
+}
+
+#[derive(Deserialize)]
+struct TeamMembers {
+    members: Vec<TeamMember>,
+}
+
+/// Hand the team lead role from one member to another. The current lead is
+/// demoted first and re-promoted if promoting the new lead fails.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn transfer_team_lead(
+    api_client: State<'_, ApiClient>,
+    team_id: i32,
+    from_user_id: i32,
+    to_user_id: i32,
+) -> Result<(), String> {
+    if from_user_id == to_user_id {
+        return Err("The new team lead must be a different user".to_string());
+    }
+    let team: TeamMembers = api_client.get_json(&format!("/teams/{}/users", team_id)).await?;
+    let role_of = |user_id: i32| {
+        team.members
+            .iter()
+            .find(|m| m.user_id == user_id)
+            .map(|m| m.role.as_str())
+    };
+    match role_of(from_user_id) {
+        Some(role) if role == TeamRole::Lead.as_str() => {}
+        Some(_) => return Err(format!("User {} is not the lead of team {}", from_user_id, team_id)),
+        None => return Err(format!("User {} is not a member of team {}", from_user_id, team_id)),
+    }
+    if role_of(to_user_id).is_none() {
+        return Err(format!("User {} is not a member of team {}", to_user_id, team_id));
+    }
+
+    info!("Transferring lead of team {} from user {} to user {}", team_id, from_user_id, to_user_id);
+    set_team_role(&api_client, team_id, from_user_id, TeamRole::Member).await?;
+    if let Err(e) = set_team_role(&api_client, team_id, to_user_id, TeamRole::Lead).await {
+        error!("Failed to promote user {}, restoring user {} as lead: {}", to_user_id, from_user_id, e);
+        set_team_role(&api_client, team_id, from_user_id, TeamRole::Lead)
+            .await
+            .map_err(|rollback| {
+                format!(
+                    "Failed to promote new lead ({}) and failed to restore the original lead ({})",
+                    e, rollback
+                )
+            })?;
+        return Err(format!("Failed to promote new lead: {}", e));
+    }
     Ok(())
 }
 
@@ -96,6 +323,158 @@ pub async fn get_team_products(api_client: State<'_, ApiClient>, team_id: i32) -
     api_client.get(&format!("/teams/{}/products", team_id)).await
 }
 
+// Most recently updated review for a product, if it has any
+pub(crate) async fn fetch_latest_review(api_client: &ApiClient, product_id: i64) -> Option<Value> {
+    let reviews: Vec<Value> = match api_client.get_json(&format!("/reviews/product/{}", product_id)).await {
+        Ok(reviews) => reviews,
+        Err(e) => {
+            warn!("Failed to fetch reviews for product {}: {}", product_id, e);
+            return None;
+        }
+    };
+    // ISO 8601 timestamps compare correctly as strings
+    reviews.into_iter().max_by_key(|r| {
+        r["updated_at"].as_str().or(r["created_at"].as_str()).unwrap_or_default().to_string()
+    })
+}
+
+/// Team products annotated with the status of each product's latest review.
+/// Products without reviews get null `review_status`/`product_status`.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_team_products_with_status(
+    api_client: State<'_, ApiClient>,
+    team_id: i32,
+) -> Result<Vec<Value>, String> {
+    products_with_status(api_client.inner(), team_id).await
+}
+
+async fn products_with_status(api_client: &ApiClient, team_id: i32) -> Result<Vec<Value>, String> {
+    info!("Fetching products with review status for team ID: {}", team_id);
+    let response: Value = api_client.get_json(&format!("/teams/{}/products", team_id)).await?;
+    let products = match response["products"].clone() {
+        Value::Array(products) => products,
+        _ => Vec::new(),
+    };
+
+    let enriched: Vec<Value> = stream::iter(products)
+        .map(|mut product| async move {
+            let latest = match product["id"].as_i64() {
+                Some(product_id) => fetch_latest_review(api_client, product_id).await,
+                None => None,
+            };
+            if let Value::Object(fields) = &mut product {
+                let field = |name: &str| latest.as_ref().map_or(Value::Null, |r| r[name].clone());
+                fields.insert("latest_review_id".to_string(), field("id"));
+                fields.insert("review_status".to_string(), field("review_status"));
+                fields.insert("product_status".to_string(), field("product_status"));
+            }
+            product
+        })
+        .buffered(TEAM_PRODUCT_REVIEW_CONCURRENCY)
+        .collect()
+        .await;
+
+    info!("Fetched {} product(s) with review status for team {}", enriched.len(), team_id);
+    Ok(enriched)
+}
+
+/// One-call overview of a team for its lead
+#[derive(Debug, Serialize)]
+pub struct TeamStats {
+    pub team_id: i32,
+    pub member_count: usize,
+    pub product_count: usize,
+    pub task_count: usize,
+    /// Pending reviews across the team's products
+    pub pending_reviews: usize,
+    /// Mean days from a product's `created_at` to its `acceptance_date`, over
+    /// accepted products; `None` when no product has both
+    pub average_cycle_time_days: Option<f64>,
+    /// Sub-fetches that failed; their figures are reported as zero
+    pub warnings: Vec<String>,
+}
+
+fn average_cycle_time_days(products: &[Value]) -> Option<f64> {
+    let durations: Vec<f64> = products
+        .iter()
+        .filter_map(|p| {
+            // Product timestamps may also be plain dates
+            let created = parse_server_date(p["created_at"].as_str()?)?;
+            let accepted = parse_server_date(p["acceptance_date"].as_str()?)?;
+            Some((accepted - created).num_seconds() as f64 / 86_400.0)
+        })
+        .filter(|days| *days >= 0.0)
+        .collect();
+    if durations.is_empty() {
+        None
+    } else {
+        Some(durations.iter().sum::<f64>() / durations.len() as f64)
+    }
+}
+
+/// Member, product, task and pending-review counts plus average cycle time
+/// for a team. The sub-fetches run concurrently; any that fail are listed in
+/// `warnings` and the rest of the stats are still returned.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_team_statistics(api_client: State<'_, ApiClient>, team_id: i32) -> Result<TeamStats, String> {
+    Ok(team_statistics(api_client.inner(), team_id).await)
+}
+
+async fn team_statistics(api_client: &ApiClient, team_id: i32) -> TeamStats {
+    info!("Fetching statistics for team ID: {}", team_id);
+    let users_endpoint = format!("/teams/{}/users", team_id);
+    let products_endpoint = format!("/teams/{}/products", team_id);
+    let tasks_endpoint = format!("/teams/{}/tasks", team_id);
+    let (members, products, tasks) = tokio::join!(
+        api_client.get_json::<Value>(&users_endpoint),
+        api_client.get_json::<Value>(&products_endpoint),
+        api_client.get_json::<Value>(&tasks_endpoint),
+    );
+
+    let mut fetched = PartialFetch::new(format!("team {}", team_id));
+    let members = fetched.take("members", "members", members);
+    let products = fetched.take("products", "products", products);
+    let tasks = fetched.take("tasks", "tasks", tasks);
+
+    let product_ids: Vec<i64> = products.iter().filter_map(|p| p["id"].as_i64()).collect();
+    let review_counts: Vec<Result<usize, String>> = stream::iter(product_ids)
+        .map(|product_id| async move {
+            api_client
+                .get_json::<Vec<Value>>(&format!("/reviews/product/{}", product_id))
+                .await
+                .map(|reviews| {
+                    reviews
+                        .iter()
+                        .filter(|r| r["review_status"].as_str().is_some_and(|s| s.eq_ignore_ascii_case("pending")))
+                        .count()
+                })
+                .map_err(|e| format!("Failed to fetch reviews for product {}: {}", product_id, e))
+        })
+        .buffer_unordered(TEAM_PRODUCT_REVIEW_CONCURRENCY)
+        .collect()
+        .await;
+    let mut pending_reviews = 0;
+    for count in review_counts {
+        match count {
+            Ok(count) => pending_reviews += count,
+            Err(e) => {
+                warn!("{}", e);
+                fetched.warnings.push(e);
+            }
+        }
+    }
+
+    TeamStats {
+        team_id,
+        member_count: members.len(),
+        product_count: products.len(),
+        task_count: tasks.len(),
+        pending_reviews,
+        average_cycle_time_days: average_cycle_time_days(&products),
+        warnings: fetched.warnings,
+    }
+}
+
 #[tauri::command(rename_all = "snake_case")]
 pub async fn assign_product_to_team(api_client: State<'_, ApiClient>, team_id: i32, site_id: String) -> Result<(), String> {
     info!("Assigning product {} to team {}", site_id, team_id);
@@ -147,6 +526,49 @@ pub async fn get_team_tasks(api_client: State<'_, ApiClient>, team_id: i32) -> R
     api_client.get(&format!("/teams/{}/tasks", team_id)).await
 }
 
+// Keep the first occurrence of each task order id; entries without an id are kept
+fn dedup_task_orders(task_orders: impl IntoIterator<Item = Value>) -> Vec<Value> {
+    let mut seen = HashSet::new();
+    task_orders
+        .into_iter()
+        .filter(|task_order| task_order["id"].as_i64().is_none_or(|id| seen.insert(id)))
+        .collect()
+}
+
+/// Task orders of every team the current user belongs to, each listed once.
+/// A user without teams gets an empty list.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_my_task_orders(api_client: State<'_, ApiClient>) -> Result<Vec<Value>, String> {
+    my_task_orders(api_client.inner()).await
+}
+
+async fn my_task_orders(api_client: &ApiClient) -> Result<Vec<Value>, String> {
+    info!("Fetching task orders for the current user's teams");
+    let teams: Vec<Value> = api_client
+        .get_json("/users/me/teams")
+        .await
+        .map_err(|e| format!("Failed to fetch user teams: {}", e))?;
+    let team_ids: Vec<i64> = teams
+        .iter()
+        .filter_map(|t| t["id"].as_i64().or_else(|| t["team_id"].as_i64()))
+        .collect();
+
+    let per_team: Vec<Result<Vec<Value>, String>> = stream::iter(team_ids)
+        .map(|team_id| async move {
+            api_client
+                .get_json::<Value>(&format!("/teams/{}/tasks", team_id))
+                .await
+                .map(|data| list_items(data, "tasks"))
+                .map_err(|e| format!("Failed to fetch task orders for team {}: {}", team_id, e))
+        })
+        .buffered(api_client.batch_concurrency(TEAM_FETCH_CONCURRENCY))
+        .collect()
+        .await;
+
+    let task_orders = per_team.into_iter().collect::<Result<Vec<_>, _>>()?;
+    Ok(dedup_task_orders(task_orders.into_iter().flatten()))
+}
+
 #[tauri::command(rename_all = "snake_case")]
 pub async fn assign_task_order_to_team(api_client: State<'_, ApiClient>, team_id: i32, task_name: String) -> Result<(), String> {
     info!("Assigning task order {} to team {}", task_name, team_id);
@@ -166,3 +588,151 @@ pub async fn get_team_notifications(api_client: State<'_, ApiClient>, team_id: i
     info!("Fetching notifications for team ID: {}", team_id);
     api_client.get(&format!("/teams/{}/notifications", team_id)).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{mock_server, mock_server_with, test_client, MockResponse};
+
+    #[test]
+    fn team_update_omits_fields_that_are_not_set() {
+        let request = UpdateTeamRequest { name: None, description: Some("Survey".to_string()), active: Some(false) };
+        assert_eq!(serde_json::to_value(&request).unwrap(), serde_json::json!({ "description": "Survey", "active": false }));
+    }
+
+    #[tokio::test]
+    async fn invalid_roles_are_rejected_without_a_request() {
+        let (base_url, requests) = mock_server(|_, _, _| (201, r#"{"data":{}}"#.to_string())).await;
+        let api_client = test_client(&base_url).await;
+        let members = vec![
+            TeamMemberInput { user_id: 1, role: "member".to_string() },
+            TeamMemberInput { user_id: 2, role: "owner".to_string() },
+            TeamMemberInput { user_id: 3, role: "team_lead".to_string() },
+        ];
+
+        let results = add_members(&api_client, 5, members).await;
+
+        let outcomes: Vec<(i32, bool)> = results.iter().map(|r| (r.user_id, r.success)).collect();
+        assert_eq!(outcomes, [(1, true), (2, false), (3, true)]);
+        assert!(results[1].error.as_deref().is_some_and(|e| e.contains("Unknown team role 'owner'")));
+        assert_eq!(*requests.lock().unwrap(), ["POST /teams/5/users", "POST /teams/5/users"]);
+    }
+
+    #[tokio::test]
+    async fn member_additions_are_capped() {
+        let server = mock_server_with(|_| MockResponse::json(201, r#"{"data":{}}"#).delay(Duration::from_millis(20))).await;
+        let api_client = test_client(&server.base_url).await;
+        let members = (1..=20).map(|user_id| TeamMemberInput { user_id, role: "member".to_string() }).collect();
+
+        let results = add_members(&api_client, 5, members).await;
+
+        assert!(results.iter().all(|r| r.success));
+        assert_eq!(results.iter().map(|r| r.user_id).collect::<Vec<_>>(), (1..=20).collect::<Vec<_>>());
+        assert!(server.max_in_flight() <= TEAM_MEMBER_ADD_CONCURRENCY, "{}", server.max_in_flight());
+    }
+
+    #[tokio::test]
+    async fn product_status_lookups_are_capped_and_annotate_each_product() {
+        let server = mock_server_with(|request| {
+            if request.path == "/teams/3/products" {
+                let products: Vec<Value> = (1..=20).map(|id| serde_json::json!({ "id": id })).collect();
+                return MockResponse::json(200, serde_json::json!({ "data": { "products": products } }).to_string());
+            }
+            let reviews = match request.path.as_str() {
+                "/reviews/product/1" => serde_json::json!([
+                    { "id": 10, "review_status": "approved", "product_status": "complete", "updated_at": "2024-01-01T00:00:00Z" },
+                    { "id": 11, "review_status": "pending", "product_status": "in_review", "updated_at": "2024-02-01T00:00:00Z" },
+                ]),
+                _ => serde_json::json!([]),
+            };
+            MockResponse::json(200, serde_json::json!({ "data": reviews }).to_string()).delay(Duration::from_millis(50))
+        })
+        .await;
+        let api_client = test_client(&server.base_url).await;
+
+        let products = products_with_status(&api_client, 3).await.unwrap();
+
+        assert_eq!(products.len(), 20);
+        assert_eq!(products[0]["id"], 1);
+        assert_eq!(products[0]["latest_review_id"], 11);
+        assert_eq!(products[0]["review_status"], "pending");
+        assert_eq!(products[0]["product_status"], "in_review");
+        assert_eq!(products[19]["id"], 20);
+        assert!(products[19]["review_status"].is_null());
+        assert!(products[19].as_object().unwrap().contains_key("latest_review_id"));
+        assert!(server.max_in_flight() > 1);
+        assert!(server.max_in_flight() <= TEAM_PRODUCT_REVIEW_CONCURRENCY);
+    }
+
+    #[tokio::test]
+    async fn team_statistics_aggregate_sub_responses_and_report_failures() {
+        let server = mock_server_with(|request| {
+            let data = match request.path.as_str() {
+                "/teams/2/users" => serde_json::json!({ "members": [{ "id": 1 }, { "id": 2 }, { "id": 3 }] }),
+                "/teams/2/products" => serde_json::json!({ "products": [
+                    { "id": 10, "created_at": "2024-01-01T00:00:00Z", "acceptance_date": "2024-01-05" },
+                    { "id": 11, "created_at": "2024-01-01T00:00:00", "acceptance_date": "2024-01-03T00:00:00Z" },
+                    { "id": 12, "created_at": "2024-01-01" },
+                ] }),
+                "/reviews/product/10" => serde_json::json!([{ "review_status": "Pending" }, { "review_status": "approved" }]),
+                "/reviews/product/11" => serde_json::json!([{ "review_status": "pending" }]),
+                _ => return MockResponse::json(500, r#"{"message":"Unavailable"}"#),
+            };
+            MockResponse::json(200, serde_json::json!({ "data": data }).to_string())
+        })
+        .await;
+        let api_client = test_client(&server.base_url).await;
+
+        let stats = team_statistics(&api_client, 2).await;
+
+        assert_eq!((stats.member_count, stats.product_count, stats.task_count), (3, 3, 0));
+        assert_eq!(stats.pending_reviews, 2);
+        assert_eq!(stats.average_cycle_time_days, Some(3.0));
+        let mut warnings = stats.warnings.clone();
+        warnings.sort();
+        assert_eq!(warnings.len(), 2, "{warnings:?}");
+        assert!(warnings[0].starts_with("Failed to fetch reviews for product 12"), "{warnings:?}");
+        assert!(warnings[1].starts_with("Failed to fetch tasks"), "{warnings:?}");
+    }
+
+    #[tokio::test]
+    async fn task_orders_shared_by_two_teams_are_listed_once() {
+        let server = mock_server_with(|request| {
+            let data = match request.path.as_str() {
+                "/users/me/teams" => serde_json::json!([{ "id": 1 }, { "team_id": 2 }]),
+                "/teams/1/tasks" => serde_json::json!({ "tasks": [{ "id": 10, "name": "North" }, { "id": 11, "name": "Shared" }] }),
+                "/teams/2/tasks" => serde_json::json!([{ "id": 11, "name": "Shared" }, { "id": 12, "name": "South" }]),
+                _ => return MockResponse::json(404, "{}"),
+            };
+            MockResponse::json(200, serde_json::json!({ "data": data }).to_string())
+        })
+        .await;
+        let api_client = test_client(&server.base_url).await;
+
+        let task_orders = my_task_orders(&api_client).await.unwrap();
+
+        let ids: Vec<i64> = task_orders.iter().filter_map(|t| t["id"].as_i64()).collect();
+        assert_eq!(ids, [10, 11, 12]);
+    }
+
+    #[test]
+    fn task_orders_without_an_id_are_kept() {
+        let task_orders = dedup_task_orders([
+            serde_json::json!({ "id": 5 }),
+            serde_json::json!({ "name": "Unsaved" }),
+            serde_json::json!({ "id": 5 }),
+            serde_json::json!({ "name": "Unsaved" }),
+        ]);
+        assert_eq!(task_orders.len(), 3);
+    }
+
+    #[test]
+    fn team_roles_round_trip_through_their_strings() {
+        for role in TeamRole::ALL {
+            assert_eq!(role.as_str().parse::<TeamRole>(), Ok(role));
+            assert_eq!(serde_json::to_value(role).unwrap(), role.as_str());
+        }
+        assert_eq!("manager".parse::<TeamRole>(), Ok(TeamRole::Manager));
+        assert!("owner".parse::<TeamRole>().is_err());
+    }
+}
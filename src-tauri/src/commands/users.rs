@@ -1,8 +1,36 @@
+use crate::auth::login::role_allows;
 use crate::services::api_client::ApiClient;
 use log::{debug, error, info};
+use reqwest::StatusCode;
+use serde::Serialize;
 use serde_json::Value;
+use std::collections::HashSet;
 use tauri::State;
 
+const DEFAULT_USER_PAGE_SIZE: i32 = 25;
+const MAX_USER_PAGE_SIZE: i32 = 100;
+
+#[derive(Debug, Serialize)]
+pub struct UserWorkload {
+    pub user_id: i32,
+    pub active_products: usize,
+    pub pending_reviews: usize,
+    pub active_workflow_instances: usize,
+    pub soonest_due_date: Option<String>,
+    /// Sub-fetches that failed; their counts are reported as zero
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserPage {
+    pub users: Vec<Value>,
+    pub total: i64,
+    pub page: i32,
+    pub page_size: i32,
+    /// False when the filters were applied locally because the backend ignored them
+    pub server_filtered: bool,
+}
+
 #[tauri::command(rename_all = "snake_case")]
 pub async fn delete_user(api_client: State<'_, ApiClient>, user_id: i32) -> Result<String, String> {
     info!("Deleting user {user_id}");
@@ -53,6 +81,58 @@ pub async fn get_me_profile(
     api_client.get("/users/me/profile").await
 }
 
+/// What the current user may do, for showing or hiding UI controls.
+///
+/// Global roles map to flags through `role_allows` (`admin` gets every flag,
+/// `team_lead` may approve reviews). A `team_lead` or `admin` role on a team
+/// also allows approving reviews and managing that team (`lead_team_ids`).
+#[derive(Debug, Serialize)]
+pub struct Permissions {
+    pub role: String,
+    pub is_global_admin: bool,
+    pub can_create_teams: bool,
+    pub can_manage_users: bool,
+    pub can_approve_reviews: bool,
+    pub lead_team_ids: Vec<i64>,
+}
+
+// Teams, from `/users/me/teams`, on which the user is `team_lead` or `admin`
+pub(crate) fn lead_team_ids(teams: &[Value]) -> Vec<i64> {
+    teams
+        .iter()
+        .filter(|t| matches!(t["role"].as_str(), Some("team_lead") | Some("admin")))
+        .filter_map(|t| t["id"].as_i64().or_else(|| t["team_id"].as_i64()))
+        .collect()
+}
+
+fn derive_permissions(role: &str, teams: &[Value]) -> Permissions {
+    let is_global_admin = role == "admin";
+    let lead_team_ids = lead_team_ids(teams);
+    Permissions {
+        role: role.to_string(),
+        is_global_admin,
+        can_create_teams: role_allows(role, "create_team"),
+        can_manage_users: role_allows(role, "manage_users"),
+        can_approve_reviews: role_allows(role, "approve_review") || !lead_team_ids.is_empty(),
+        lead_team_ids,
+    }
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_my_permissions(api_client: State<'_, ApiClient>) -> Result<Permissions, String> {
+    info!("Fetching current user permissions");
+    let (me, teams) = tokio::join!(
+        api_client.get_json::<Value>("/users/me"),
+        api_client.get_json::<Vec<Value>>("/users/me/teams"),
+    );
+    let me = me.map_err(|e| format!("Failed to fetch current user: {}", e))?;
+    let teams = teams.map_err(|e| format!("Failed to fetch user teams: {}", e))?;
+    let role = me["role"]
+        .as_str()
+        .ok_or_else(|| "Current user has no role".to_string())?;
+    Ok(derive_permissions(role, &teams))
+}
+
 #[tauri::command(rename_all = "snake_case")]
 pub async fn change_password(
     api_client: State<'_, ApiClient>,
@@ -66,4 +146,340 @@ pub async fn change_password(
         "new_password": new_password,
     });
     api_client.post(&format!("/auth/change_password/{}", user_id), &password_data).await
-}
\ No newline at end of file
+}
+
+// Users and total from a `/users` response: a bare list or a page object
+fn parse_user_page(data: Value) -> Result<(Vec<Value>, i64), String> {
+    match data {
+        Value::Array(users) => {
+            let total = users.len() as i64;
+            Ok((users, total))
+        }
+        Value::Object(mut page_data) => {
+            let users = match page_data.remove("users").or_else(|| page_data.remove("items")) {
+                Some(Value::Array(users)) => users,
+                _ => return Err("Failed to parse user search results".to_string()),
+            };
+            let total = page_data
+                .get("total")
+                .and_then(Value::as_i64)
+                .unwrap_or(users.len() as i64);
+            Ok((users, total))
+        }
+        _ => Err("Failed to parse user search results".to_string()),
+    }
+}
+
+fn user_matches(
+    user: &Value,
+    query: Option<&str>,
+    role: Option<&str>,
+    team_members: Option<&HashSet<i64>>,
+) -> bool {
+    let query_matches = query.is_none_or(|q| {
+        user["username"]
+            .as_str()
+            .is_some_and(|name| name.to_lowercase().contains(&q.to_lowercase()))
+    });
+    let role_matches = role.is_none_or(|r| user["role"].as_str().is_some_and(|ur| ur.eq_ignore_ascii_case(r)));
+    let team_matches =
+        team_members.is_none_or(|members| user["id"].as_i64().is_some_and(|id| members.contains(&id)));
+    query_matches && role_matches && team_matches
+}
+
+/// Search users by username substring, role and team, one page at a time, so
+/// pickers can lazy-load instead of downloading every user.
+///
+/// Filters are sent to `GET /users`. If the backend rejects them or returns
+/// users that don't match, all users are fetched and filtered locally instead;
+/// `server_filtered` on the result says which path ran. Team membership isn't
+/// on the user record, so with `team_id` the team's members are fetched first
+/// to check the server's results.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn search_users(
+    api_client: State<'_, ApiClient>,
+    query: Option<String>,
+    role: Option<String>,
+    team_id: Option<i32>,
+    page: i32,
+    page_size: Option<i32>,
+) -> Result<UserPage, String> {
+    search(&api_client, query, role, team_id, page, page_size).await
+}
+
+async fn search(
+    api_client: &ApiClient,
+    query: Option<String>,
+    role: Option<String>,
+    team_id: Option<i32>,
+    page: i32,
+    page_size: Option<i32>,
+) -> Result<UserPage, String> {
+    let page = page.max(1);
+    let page_size = page_size
+        .unwrap_or(DEFAULT_USER_PAGE_SIZE)
+        .clamp(1, MAX_USER_PAGE_SIZE);
+    let query = query.map(|q| q.trim().to_string()).filter(|q| !q.is_empty());
+    let role = role.map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
+
+    let mut params = vec![("page", page.to_string()), ("limit", page_size.to_string())];
+    if let Some(q) = &query {
+        params.push(("q", q.clone()));
+    }
+    if let Some(r) = &role {
+        params.push(("role", r.clone()));
+    }
+    if let Some(team) = team_id {
+        params.push(("team_id", team.to_string()));
+    }
+    let query_string = serde_urlencoded::to_string(&params)
+        .map_err(|e| format!("Failed to encode search parameters: {}", e))?;
+    info!("Searching users (page {page}, {page_size} per page)");
+
+    let team_members: Option<HashSet<i64>> = match team_id {
+        Some(team) => {
+            let team_data: Value = api_client.get_json(&format!("/teams/{}/users", team)).await?;
+            Some(
+                list_items(team_data, "members")
+                    .iter()
+                    .filter_map(|m| m["user_id"].as_i64())
+                    .collect(),
+            )
+        }
+        None => None,
+    };
+    let matches = |u: &Value| user_matches(u, query.as_deref(), role.as_deref(), team_members.as_ref());
+
+    match api_client.get_json::<Value>(&format!("/users?{}", query_string)).await {
+        Ok(data) => {
+            let (users, total) = parse_user_page(data)?;
+            // A total larger than the team means the team filter was ignored
+            let total_fits_team = team_members.as_ref().is_none_or(|members| total <= members.len() as i64);
+            if total_fits_team && users.iter().all(matches) {
+                return Ok(UserPage { users, total, page, page_size, server_filtered: true });
+            }
+            info!("Backend ignored user search filters, filtering locally");
+        }
+        Err(e) if matches!(e.status(), Some(StatusCode::BAD_REQUEST | StatusCode::NOT_FOUND)) => {
+            info!("Backend rejected user search parameters, filtering locally");
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    let (all_users, _) = parse_user_page(api_client.get_json::<Value>("/users").await?)?;
+    let matching: Vec<Value> = all_users.into_iter().filter(|u| matches(u)).collect();
+    let total = matching.len() as i64;
+    let users = matching
+        .into_iter()
+        .skip((page as usize - 1).saturating_mul(page_size as usize))
+        .take(page_size as usize)
+        .collect();
+    Ok(UserPage { users, total, page, page_size, server_filtered: false })
+}
+
+// List endpoints return either a bare array or an object wrapping one
+pub(crate) fn list_items(data: Value, key: &str) -> Vec<Value> {
+    match data {
+        Value::Array(items) => items,
+        Value::Object(mut map) => match map.remove(key) {
+            Some(Value::Array(items)) => items,
+            _ => Vec::new(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+/// Lists from a group of concurrent fetches. A failed fetch yields an empty
+/// list and a warning, so the rest can still be reported.
+pub(crate) struct PartialFetch {
+    // Whose data is fetched, for the log, e.g. "user 4"
+    subject: String,
+    pub warnings: Vec<String>,
+}
+
+impl PartialFetch {
+    pub(crate) fn new(subject: String) -> Self {
+        PartialFetch { subject, warnings: Vec::new() }
+    }
+
+    /// The `key` list from a fetch of `name`, or an empty list on failure
+    pub(crate) fn take(&mut self, name: &str, key: &str, result: Result<Value, impl std::fmt::Display>) -> Vec<Value> {
+        match result {
+            Ok(data) => list_items(data, key),
+            Err(e) => {
+                error!("Failed to fetch {} for {}: {}", name, self.subject, e);
+                self.warnings.push(format!("Failed to fetch {}: {}", name, e));
+                Vec::new()
+            }
+        }
+    }
+}
+
+/// Summarize what a user currently has on their plate across teams: active
+/// products, pending reviews and in-progress workflow instances.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_user_workload(
+    api_client: State<'_, ApiClient>,
+    user_id: i32,
+) -> Result<UserWorkload, String> {
+    user_workload(&api_client, user_id).await
+}
+
+async fn user_workload(api_client: &ApiClient, user_id: i32) -> Result<UserWorkload, String> {
+    info!("Fetching workload for user {user_id}");
+    let products_endpoint = format!("/users/{}/products", user_id);
+    let reviews_endpoint = format!("/reviews/user/{}", user_id);
+    let instances_endpoint = format!("/production/instances?assigned_user_id={}&status=in_progress", user_id);
+    let (products, reviews, instances) = tokio::join!(
+        api_client.get_json::<Value>(&products_endpoint),
+        api_client.get_json::<Value>(&reviews_endpoint),
+        api_client.get_json::<Value>(&instances_endpoint),
+    );
+
+    let mut fetched = PartialFetch::new(format!("user {}", user_id));
+    let products: Vec<Value> = fetched.take("products", "products", products)
+        .into_iter()
+        .filter(|p| !matches!(p["status"].as_str(), Some("completed" | "Completed")))
+        .collect();
+    let reviews: Vec<Value> = fetched.take("reviews", "reviews", reviews)
+        .into_iter()
+        .filter(|r| !matches!(r["review_status"].as_str(), Some("Approved" | "Rejected")))
+        .collect();
+    let instances = fetched.take("workflow instances", "instances", instances);
+
+    // ISO 8601 dates compare correctly as strings
+    let soonest_due_date = products
+        .iter()
+        .filter_map(|p| p["due_date"].as_str())
+        .chain(instances.iter().filter_map(|i| i["estimated_completion"].as_str()))
+        .min()
+        .map(String::from);
+
+    Ok(UserWorkload {
+        user_id,
+        active_products: products.len(),
+        pending_reviews: reviews.len(),
+        active_workflow_instances: instances.len(),
+        soonest_due_date,
+        warnings: fetched.warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{mock_server, test_client};
+    use serde_json::json;
+
+    #[test]
+    fn admins_and_plain_members_get_different_permissions() {
+        let admin = derive_permissions("admin", &[]);
+        assert!(admin.is_global_admin && admin.can_create_teams && admin.can_manage_users && admin.can_approve_reviews);
+        assert!(admin.lead_team_ids.is_empty());
+
+        let member = derive_permissions("user", &[json!({ "id": 4, "role": "member" })]);
+        assert!(!member.is_global_admin && !member.can_create_teams && !member.can_manage_users);
+        assert!(!member.can_approve_reviews);
+        assert!(member.lead_team_ids.is_empty());
+    }
+
+    #[test]
+    fn leading_a_team_allows_approving_reviews() {
+        let teams = [json!({ "id": 4, "role": "member" }), json!({ "team_id": 9, "role": "team_lead" })];
+        let lead = derive_permissions("user", &teams);
+        assert!(lead.can_approve_reviews);
+        assert!(!lead.can_manage_users);
+        assert_eq!(lead.lead_team_ids, [9]);
+    }
+
+    #[test]
+    fn user_matches_checks_team_membership() {
+        let user = json!({ "id": 7, "username": "Alice", "role": "editor" });
+        let members: HashSet<i64> = [7, 8].into_iter().collect();
+        let others: HashSet<i64> = [8].into_iter().collect();
+
+        assert!(user_matches(&user, Some("ali"), Some("EDITOR"), Some(&members)));
+        assert!(!user_matches(&user, None, None, Some(&others)));
+        assert!(!user_matches(&user, Some("bob"), None, None));
+    }
+
+    #[tokio::test]
+    async fn workload_aggregates_sub_responses_and_reports_failures() {
+        let (base_url, _) = mock_server(|_, path, _| match path {
+            "/users/5/products" => (
+                200,
+                json!({ "data": [
+                    { "id": 1, "status": "in_progress", "due_date": "2026-05-01" },
+                    { "id": 2, "status": "completed", "due_date": "2026-01-01" },
+                    { "id": 3, "status": "assigned", "due_date": null },
+                ]})
+                .to_string(),
+            ),
+            "/production/instances?assigned_user_id=5&status=in_progress" => (
+                200,
+                json!({ "data": { "instances": [{ "id": 9, "estimated_completion": "2026-04-15" }] } }).to_string(),
+            ),
+            _ => (500, r#"{"message":"reviews unavailable"}"#.to_string()),
+        })
+        .await;
+        let api_client = test_client(&base_url).await;
+
+        let workload = user_workload(&api_client, 5).await.unwrap();
+        assert_eq!(workload.active_products, 2);
+        assert_eq!(workload.pending_reviews, 0);
+        assert_eq!(workload.active_workflow_instances, 1);
+        assert_eq!(workload.soonest_due_date.as_deref(), Some("2026-04-15"));
+        assert_eq!(workload.warnings.len(), 1);
+        assert!(workload.warnings[0].starts_with("Failed to fetch reviews"));
+    }
+
+    fn users() -> Vec<Value> {
+        vec![
+            json!({ "id": 1, "username": "alice", "role": "editor" }),
+            json!({ "id": 2, "username": "alan", "role": "viewer" }),
+            json!({ "id": 3, "username": "albert", "role": "editor" }),
+            json!({ "id": 4, "username": "bob", "role": "editor" }),
+        ]
+    }
+
+    #[tokio::test]
+    async fn search_trusts_server_filtered_results() {
+        let (base_url, requests) = mock_server(|_, _, _| {
+            (200, json!({ "data": { "users": [users()[0].clone()], "total": 1 } }).to_string())
+        })
+        .await;
+        let api_client = test_client(&base_url).await;
+
+        let page = search(&api_client, Some("ali".into()), None, None, 1, None).await.unwrap();
+        assert!(page.server_filtered);
+        assert_eq!(page.total, 1);
+        assert_eq!(page.users[0]["username"], "alice");
+        assert_eq!(*requests.lock().unwrap(), ["GET /users?page=1&limit=25&q=ali"]);
+    }
+
+    #[tokio::test]
+    async fn search_filters_locally_when_the_server_ignores_filters() {
+        // The backend answers every /users request with every user
+        let (base_url, requests) = mock_server(|_, _, _| (200, json!({ "data": users() }).to_string())).await;
+        let api_client = test_client(&base_url).await;
+
+        let page = search(&api_client, Some("al".into()), Some("editor".into()), None, 2, Some(1))
+            .await
+            .unwrap();
+        assert!(!page.server_filtered);
+        assert_eq!(page.total, 2);
+        assert_eq!(page.users.len(), 1);
+        assert_eq!(page.users[0]["username"], "albert");
+        assert_eq!(requests.lock().unwrap().last().map(String::as_str), Some("GET /users"));
+    }
+
+    #[tokio::test]
+    async fn a_page_far_past_the_end_is_empty() {
+        let (base_url, _) = mock_server(|_, _, _| (200, json!({ "data": users() }).to_string())).await;
+        let api_client = test_client(&base_url).await;
+
+        let page = search(&api_client, Some("al".into()), None, None, i32::MAX, Some(100)).await.unwrap();
+        assert!(!page.server_filtered);
+        assert!(page.users.is_empty());
+    }
+}
@@ -1,7 +1,11 @@
+use crate::commands::team::TeamRole;
 use crate::services::api_client::ApiClient;
 use chrono::{Duration, Utc};
-use log::{debug, error, info};
+use log::{debug, info, warn};
+use reqwest::{Method, StatusCode};
+use serde::Serialize;
 use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
 use tauri::State;
 
 #[tauri::command(rename_all = "snake_case")]
@@ -11,6 +15,18 @@ pub async fn request_team_join(
     role: String,
     justification: Option<String>,
 ) -> Result<String, String> {
+    let role: TeamRole = role.parse()?;
+
+    // Don't create a second pending request for the same team
+    if let Some(existing_id) = find_pending_join_request(&api_client, team_id).await {
+        info!("Join request {existing_id} for team {team_id} is already pending");
+        return Err(json!({
+            "message": "You already have a pending request to join this team",
+            "existing_request_id": existing_id,
+        })
+        .to_string());
+    }
+
     info!("Submitting team join request for team ID: {team_id}");
     let mut request_payload = json!({
         "request_type": "TeamJoin",
@@ -27,6 +43,63 @@ pub async fn request_team_join(
     api_client.post("/requests", &request_payload).await
 }
 
+/// ID of the current user's pending join request for `team_id`, if any. Reads
+/// the caller's own pending requests, since a non-member can't list the
+/// team's. If the check itself fails the join request is allowed through.
+async fn find_pending_join_request(api_client: &ApiClient, team_id: i32) -> Option<i64> {
+    let (me, requests) = tokio::join!(
+        api_client.get_json::<Value>("/users/me"),
+        api_client.get_json::<Vec<Value>>("/requests?status=pending"),
+    );
+    let (me, requests) = match (me, requests) {
+        (Ok(me), Ok(requests)) => (me, requests),
+        (Err(e), _) | (_, Err(e)) => {
+            warn!("Could not check for a pending join request to team {}: {}", team_id, e);
+            return None;
+        }
+    };
+    let user_id = me["id"].as_i64()?;
+    requests
+        .iter()
+        .find(|r| {
+            r["request_type"].as_str() == Some("TeamJoin")
+                && r["requested_by"].as_i64() == Some(user_id)
+                && r["target_id"].as_i64() == Some(team_id as i64)
+                && r["status"].as_str().is_some_and(|s| s.eq_ignore_ascii_case("pending"))
+        })
+        .and_then(|r| r["id"].as_i64())
+}
+
+/// Add each request's requester `username`. Every distinct user is looked up
+/// once, concurrently; requests whose user can't be fetched are left as-is.
+pub(crate) async fn enrich_requests_with_usernames(api_client: &ApiClient, requests: Vec<Value>) -> Vec<Value> {
+    let user_ids: HashSet<i64> = requests
+        .iter()
+        .filter(|req| req.get("username").is_none())
+        .filter_map(|req| req["requested_by"].as_i64())
+        .collect();
+    let usernames: HashMap<i64, String> = futures::future::join_all(user_ids.into_iter().map(|user_id| async move {
+        let user: Value = api_client.get_json(&format!("/users/{}", user_id)).await.ok()?;
+        Some((user_id, user["username"].as_str()?.to_string()))
+    }))
+    .await
+    .into_iter()
+    .flatten()
+    .collect();
+
+    requests
+        .into_iter()
+        .map(|mut req| {
+            if let Some(username) = req["requested_by"].as_i64().and_then(|id| usernames.get(&id)) {
+                if req.get("username").is_none() {
+                    req["username"] = json!(username);
+                }
+            }
+            req
+        })
+        .collect()
+}
+
 #[tauri::command(rename_all = "snake_case")]
 pub async fn get_pending_team_requests(
     api_client: State<'_, ApiClient>,
@@ -34,15 +107,27 @@ pub async fn get_pending_team_requests(
 ) -> Result<String, String> {
     let url = format!("/teams/{}/requests", team_id);
     debug!("🔍 Fetching pending requests for team {}", team_id);
-    let result = api_client.get(&url).await;
+    let result = api_client.execute(Method::GET, &url, None::<&()>).await;
     match result {
-        Ok(response_text) => Ok(response_text),
+        Ok(response_text) => {
+            let mut parsed_response: Value = match serde_json::from_str(&response_text) {
+                Ok(parsed) => parsed,
+                Err(_) => return Ok(response_text),
+            };
+            match parsed_response["data"].take() {
+                Value::Array(requests) => {
+                    parsed_response["data"] = json!(enrich_requests_with_usernames(&api_client, requests).await);
+                    Ok(parsed_response.to_string())
+                }
+                _ => Ok(response_text),
+            }
+        }
         Err(e) => {
-            if e.contains("404") {
+            if e.status() == Some(StatusCode::NOT_FOUND) {
                 info!("Dedicated endpoint not found, falling back to filtering approach");
                 fallback_get_pending_team_requests(api_client, team_id).await
             } else {
-                Err(e)
+                Err(e.into())
             }
         }
     }
@@ -66,32 +151,7 @@ async fn fallback_get_pending_team_requests(
             })
             .cloned()
             .collect();
-        let mut enriched_requests = Vec::new();
-        for req in team_requests {
-            if let Some(user_id) = req["requested_by"].as_i64() {
-                let user_url = format!("/users/{}", user_id);
-                match api_client.get(&user_url).await {
-                    Ok(user_data_str) => {
-                        if let Ok(user_data) = serde_json::from_str::<Value>(&user_data_str) {
-                            if let Some(username) = user_data["data"]["username"].as_str() {
-                                let mut enriched_req = req.clone();
-                                enriched_req["username"] = json!(username);
-                                enriched_requests.push(enriched_req);
-                            } else {
-                                enriched_requests.push(req);
-                            }
-                        } else {
-                            enriched_requests.push(req);
-                        }
-                    }
-                    Err(_) => {
-                        enriched_requests.push(req);
-                    }
-                }
-            } else {
-                enriched_requests.push(req);
-            }
-        }
+        let enriched_requests = enrich_requests_with_usernames(&api_client, team_requests).await;
         let filtered_response = json!({
             "success": true,
             "status_code": 200,
@@ -106,6 +166,46 @@ async fn fallback_get_pending_team_requests(
     }
 }
 
+#[derive(Debug, Clone, Copy, Serialize)]
+pub enum Decision {
+    Approved,
+    Rejected,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RequestDecision {
+    pub decision: Decision,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+// The request endpoint takes the bare status string. Only a decision with a
+// reason is sent as an object, and if the server rejects that shape the
+// decision is resent without the reason.
+async fn send_request_decision(
+    api_client: &ApiClient,
+    request_id: i32,
+    decision: RequestDecision,
+) -> Result<String, String> {
+    let endpoint = format!("/requests/{}", request_id);
+    if decision.reason.is_none() {
+        return api_client.put(&endpoint, &decision.decision).await;
+    }
+    match api_client.execute(Method::PUT, &endpoint, Some(&decision)).await {
+        Ok(response_text) => Ok(response_text),
+        Err(e)
+            if matches!(
+                e.status(),
+                Some(StatusCode::BAD_REQUEST | StatusCode::UNSUPPORTED_MEDIA_TYPE | StatusCode::UNPROCESSABLE_ENTITY)
+            ) =>
+        {
+            warn!("Dropping rejection reason for request {}; the server does not accept it", request_id);
+            api_client.put(&endpoint, &decision.decision).await
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
 #[tauri::command(rename_all = "snake_case")]
 pub async fn approve_team_request(
     api_client: State<'_, ApiClient>,
@@ -113,8 +213,8 @@ pub async fn approve_team_request(
     team_id: i32,
 ) -> Result<String, String> {
     info!("👍 Approving request {} for team {}", request_id, team_id);
-    let json_payload = "Approved";
-    api_client.put(&format!("/requests/{}", request_id), &json_payload).await
+    let decision = RequestDecision { decision: Decision::Approved, reason: None };
+    send_request_decision(&api_client, request_id, decision).await
 }
 
 #[tauri::command(rename_all = "snake_case")]
@@ -122,22 +222,37 @@ pub async fn reject_team_request(
     api_client: State<'_, ApiClient>,
     request_id: i32,
     team_id: i32,
+    reason: Option<String>,
 ) -> Result<String, String> {
     info!("👎 Rejecting request {} for team {}", request_id, team_id);
-    let json_payload = "Rejected";
-    api_client.put(&format!("/requests/{}", request_id), &json_payload).await
+    let reason = reason.map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
+    let decision = RequestDecision { decision: Decision::Rejected, reason };
+    send_request_decision(&api_client, request_id, decision).await
 }
 
-#[tauri::command(rename_all = "snake_case")]
-pub async fn send_team_notification(
-    api_client: State<'_, ApiClient>,
-    team_id: i32,
+/// Longest a team or user notification may stay active.
+const MAX_NOTIFICATION_EXPIRY_DAYS: i64 = 365;
+
+// Validated notification body shared by team and user notifications.
+// `expiry_days`, if given, must be between 1 and `MAX_NOTIFICATION_EXPIRY_DAYS`.
+fn notification_payload(
     title: String,
     body: Option<String>,
     r#type: Option<String>,
     expiry_days: Option<i64>,
-) -> Result<String, String> {
-    info!("Sending notification to team {}", team_id);
+) -> Result<Value, String> {
+    let title = title.trim().to_string();
+    if title.is_empty() {
+        return Err("Notification title cannot be empty".to_string());
+    }
+    if let Some(expiry) = expiry_days {
+        if !(1..=MAX_NOTIFICATION_EXPIRY_DAYS).contains(&expiry) {
+            return Err(format!(
+                "Notification expiry must be between 1 and {} days, got {}",
+                MAX_NOTIFICATION_EXPIRY_DAYS, expiry
+            ));
+        }
+    }
     let mut payload = json!({ "title": title });
     if let Some(body_val) = body { payload["body"] = json!(body_val); }
     if let Some(type_val) = r#type { payload["type"] = json!(type_val); }
@@ -145,5 +260,140 @@ pub async fn send_team_notification(
         let expiry_date = Utc::now() + Duration::days(expiry);
         payload["expiry"] = json!(expiry_date.to_rfc3339());
     }
+    Ok(payload)
+}
+
+/// Send a notification to every member of a team. `expiry_days`, if given,
+/// must be between 1 and `MAX_NOTIFICATION_EXPIRY_DAYS`.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn send_team_notification(
+    api_client: State<'_, ApiClient>,
+    team_id: i32,
+    title: String,
+    body: Option<String>,
+    r#type: Option<String>,
+    expiry_days: Option<i64>,
+) -> Result<String, String> {
+    info!("Sending notification to team {}", team_id);
+    let payload = notification_payload(title, body, r#type, expiry_days)?;
     api_client.post(&format!("/teams/{}/notifications", team_id), &payload).await
 }
+
+// `user`-scoped targets for each distinct recipient, in ascending id order
+fn user_notification_targets(user_ids: &[i32]) -> Vec<Value> {
+    let mut user_ids = user_ids.to_vec();
+    user_ids.sort_unstable();
+    user_ids.dedup();
+    user_ids
+        .into_iter()
+        .map(|user_id| json!({ "scope": "user", "target_id": user_id }))
+        .collect()
+}
+
+/// Send one notification to specific users rather than a whole team, as a
+/// single payload targeting each of them. Same validation as
+/// `send_team_notification`.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn send_notification_to_users(
+    api_client: State<'_, ApiClient>,
+    user_ids: Vec<i32>,
+    title: String,
+    body: Option<String>,
+    r#type: Option<String>,
+    expiry_days: Option<i64>,
+) -> Result<String, String> {
+    notify_users(&api_client, &user_ids, title, body, r#type, expiry_days).await
+}
+
+async fn notify_users(
+    api_client: &ApiClient,
+    user_ids: &[i32],
+    title: String,
+    body: Option<String>,
+    r#type: Option<String>,
+    expiry_days: Option<i64>,
+) -> Result<String, String> {
+    let targets = user_notification_targets(user_ids);
+    if targets.is_empty() {
+        return Err("Select at least one user to notify".to_string());
+    }
+    info!("Sending notification to {} user(s)", targets.len());
+    let mut payload = notification_payload(title, body, r#type, expiry_days)?;
+    payload["global"] = json!(false);
+    payload["targets"] = json!(targets);
+    api_client.post("/notifications", &payload).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{mock_server, mock_server_with, test_client, MockResponse};
+
+    #[tokio::test]
+    async fn each_requester_is_looked_up_once() {
+        let (base_url, requests) = mock_server(|_, path, _| {
+            let id = path.trim_start_matches("/users/");
+            (200, json!({ "data": { "username": format!("user{}", id) } }).to_string())
+        })
+        .await;
+        let api_client = test_client(&base_url).await;
+
+        let pending = vec![
+            json!({ "id": 1, "requested_by": 7 }),
+            json!({ "id": 2, "requested_by": 7 }),
+            json!({ "id": 3, "requested_by": 8 }),
+            json!({ "id": 4, "requested_by": 7 }),
+        ];
+        let enriched = enrich_requests_with_usernames(&api_client, pending).await;
+
+        let usernames: Vec<&str> = enriched.iter().filter_map(|r| r["username"].as_str()).collect();
+        assert_eq!(usernames, ["user7", "user7", "user8", "user7"]);
+        let mut requests = requests.lock().unwrap().clone();
+        requests.sort();
+        assert_eq!(requests, ["GET /users/7", "GET /users/8"]);
+    }
+
+    #[test]
+    fn each_user_is_targeted_once_in_id_order() {
+        assert_eq!(
+            user_notification_targets(&[9, 3, 9, 5]),
+            [
+                json!({ "scope": "user", "target_id": 3 }),
+                json!({ "scope": "user", "target_id": 5 }),
+                json!({ "scope": "user", "target_id": 9 }),
+            ]
+        );
+        assert!(user_notification_targets(&[]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn user_notification_is_one_non_global_payload() {
+        let server = mock_server_with(|_| MockResponse::json(201, r#"{"data":1}"#)).await;
+        let api_client = test_client(&server.base_url).await;
+
+        notify_users(&api_client, &[4, 2, 4], " Review due ".to_string(), None, Some("reminder".to_string()), None)
+            .await
+            .unwrap();
+
+        let received = server.received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!((received[0].method.as_str(), received[0].path.as_str()), ("POST", "/notifications"));
+        let payload: Value = serde_json::from_str(&received[0].body).unwrap();
+        assert_eq!(payload["title"], "Review due");
+        assert_eq!(payload["type"], "reminder");
+        assert_eq!(payload["global"], false);
+        let target_ids: Vec<i64> = payload["targets"].as_array().unwrap().iter().filter_map(|t| t["target_id"].as_i64()).collect();
+        assert_eq!(target_ids, [2, 4]);
+    }
+
+    #[tokio::test]
+    async fn user_notification_without_recipients_is_not_sent() {
+        let server = mock_server_with(|_| MockResponse::json(201, r#"{"data":1}"#)).await;
+        let api_client = test_client(&server.base_url).await;
+
+        let result = notify_users(&api_client, &[], "Hi".to_string(), None, None, None).await;
+
+        assert_eq!(result.unwrap_err(), "Select at least one user to notify");
+        assert!(server.requests.lock().unwrap().is_empty());
+    }
+}
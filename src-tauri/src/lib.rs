@@ -1,71 +1,98 @@
 // src-tauri/src/lib.rs
 mod auth;
 mod commands;
+mod models;
 mod state;
+#[cfg(test)]
+mod test_support;
 mod utils;
 mod services;  // Add this line
 
-use auth::login::{login, register, AuthState};
+use auth::login::{login, logout, register, token_status, AuthState};
 use commands::admin::*;
+use commands::draft_watch::*;
 use commands::notifications::*;
+use commands::operations::*;
+use commands::production_workflow::*;
 use commands::products::*;
 use commands::reviews::*;
 use commands::team::*;
 use commands::users::*;
 use commands::userteams::*;
 use commands::contracts::*;
+use commands::health::*;
+use commands::profiles::*;
+use commands::session::*;
+use commands::shutdown::*;
 use commands::taskorders::*;
+use auth::token_refresh::*;
 use commands::settings::*;
 
 // Add these imports for the new ApiClient
 use services::{api_client::ApiClient, config::AppConfig};
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tauri::{Emitter, Manager};
 
 #[tokio::main]
 pub async fn run() {
     // Create configuration
     let config = Arc::new(AppConfig::new());
     
-    // Create shared auth state
-    let auth_state = Arc::new(Mutex::new(AuthState::default()));
+    // Create shared auth state; clones share the same token
+    let auth_state = AuthState::default();
     
     // Create shared API client
-    let api_client = ApiClient::new((*config).clone(), auth_state.clone());
+    // Misconfigured proxy/CA settings are fatal rather than silently ignored
+    let api_client = ApiClient::new((*config).clone(), auth_state.clone())
+        .unwrap_or_else(|e| panic!("Failed to create API client: {}", e));
     
     tauri::Builder::default()
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_log::Builder::new().build())
         .plugin(tauri_plugin_notification::init())
-        .manage(AuthState::default())  // Keep old AuthState for backward compatibility
-        .manage(auth_state.clone())    // Add new shared AuthState
+        .manage(auth_state.clone())    // Shared AuthState (also used by legacy commands)
         .manage(config.clone())        // Add shared config for polling
         .manage(api_client)            // Add new shared ApiClient
         .manage(Arc::new(commands::notifications::PollingState::default()))
+        .manage(Arc::new(SessionMonitor::default()))
+        .manage(Arc::new(commands::operations::OperationRegistry::default()))
+        .manage(Arc::new(commands::draft_watch::DraftWatchers::default()))
+        .manage(Arc::new(TokenRefreshState::default()))
         .invoke_handler(tauri::generate_handler![
             // Auth commands (keep as-is)
             login,
+            logout,
             register,
+            token_status,
+            start_token_refresh,
+            stop_token_refresh,
             get_me,
+            get_my_permissions,
             
             // Team commands (keep existing until migrated)
             create_team,
             get_all_teams,
             get_team,
             update_team,
+            update_team_details,
             delete_team,
             get_team_users,
             get_team_products,
+            get_team_products_with_status,
+            get_team_statistics,
             get_team_product_types,
             update_user_role,
+            transfer_team_lead,
             remove_user_from_team,
             get_user_role,
             add_user_to_team,
+            add_users_to_team,
             assign_product_to_team,
             remove_product_from_team,
             assign_product_type_to_team,
             assign_task_order_to_team,
             get_team_tasks,
+            get_my_task_orders,
             remove_task_order_from_team,
             remove_product_type_from_team,
             get_team_notifications,
@@ -73,6 +100,7 @@ pub async fn run() {
             approve_team_request,
             reject_team_request,
             send_team_notification,
+            send_notification_to_users,
             
             // User commands (keep existing until migrated)
             get_all_users,
@@ -84,9 +112,15 @@ pub async fn run() {
             request_team_join,
             change_password,
             get_me_profile,
+            search_users,
+            get_user_workload,
             
             // Product commands (keep existing until migrated)
             get_all_products,
+            stream_products,
+            find_duplicate_products,
+            find_products_near,
+            stream_all_products,
             get_all_product_types,
             get_user_products,
             create_product,
@@ -97,28 +131,49 @@ pub async fn run() {
             get_product_reviews,
             delete_product_assignment,
             get_product_assignments,
+            get_product_overview,
+            transfer_user_assignments,
+            reassign_user_products,
             update_product,
             update_product_status,
+            set_product_due_date,
+            bulk_update_product_status,
+            get_product_status_history,
+            get_assignment_history,
+            import_products_csv,
+            export_products_geojson,
             
             // Review commands (keep existing until migrated)
             save_review_draft,
             load_review_draft,
+            render_review_preview,
+            watch_review_draft,
+            stop_watch_review_draft,
             convert_image_to_base64,
+            verify_local_image,
             create_review,
             get_review,
             update_review,
             get_product_reviews,
             get_user_reviews,
             upload_review_image,
+            upload_review_image_with_progress,
             get_review_images,
+            download_review_image,
+            download_review_images,
+            download_all_review_images,
             delete_review_image,
             approve_review,
             reject_review,
             submit_review_from_file,
+            submit_review_from_path,
             update_review_from_file,
             sync_review_from_file,
             get_pending_reviews_for_team_lead,
+            get_team_reviews,
             delete_review,
+            get_review_version_diff,
+            compare_draft_to_server,
             
             // Contract commands (keep existing until migrated)
             get_contracts,
@@ -129,17 +184,47 @@ pub async fn run() {
             // Task order commands (now unified)
             get_task_order,
             get_taskorder_products,
+            export_task_order_bundle,
             create_task_order,
             get_all_taskorders,
+            stream_taskorders,
             update_task_order,
             check_task_order_edit_permission,
+
+            // Production workflow commands
+            get_production_workflows,
+            create_production_workflow,
+            get_production_workflow_by_id,
+            get_workflow_steps,
+            create_workflow_step,
+            reorder_workflow_steps,
+            get_product_workflow_instances,
+            create_product_workflow_instance,
+            update_product_workflow_instance,
+            assign_workflow_instance,
+            advance_workflow_step,
+            approve_workflow_step,
+            reject_workflow_step,
+            get_production_dashboard,
+            get_products_due_soon,
+            get_sla_at_risk,
+            compute_bottlenecks,
+            get_production_issues,
+            create_production_issue,
+            update_production_issue,
+            get_overdue_issues,
             
             // Notification commands (keep existing until migrated)
             get_notification_count,
             get_notifications,
+            get_notifications_typed,
             dismiss_notification,
             dismiss_all_notifications,
+            dismiss_notifications_by_type,
+            snooze_notification,
+            resolve_notification_action,
             show_system_notification,
+            reset_shown_notifications,
             start_notification_polling,
             stop_notification_polling,
             manual_refresh_notifications,
@@ -155,11 +240,51 @@ pub async fn run() {
             apply_display_density,
             update_notification_polling,
             clear_application_cache,
+            get_storage_usage,
+            set_default_header,
+
+            // Health commands
+            check_server_health,
+            ping_server,
+            get_api_metrics,
+
+            // Profile commands
+            list_profiles,
+            save_current_as_profile,
+            switch_profile,
+
+            // Session commands
+            record_activity,
+
+            // Shutdown commands
+            prepare_shutdown,
+            cancel_operation,
+            cancel_request,
             
             // Add new commands here as you migrate them
             // Example: get_contracts_v2,  // New version using ApiClient
         ])
-        .setup(|_app| {
+        .on_window_event(|window, event| {
+            // Stop polling before the window goes away so it doesn't emit
+            // into a closed window; drafts are flushed by `prepare_shutdown`.
+            if let tauri::WindowEvent::CloseRequested { .. } = event {
+                let polling_state = window.state::<Arc<commands::notifications::PollingState>>();
+                if abort_polling(&polling_state) {
+                    log::info!("Notification polling stopped on close");
+                }
+            }
+        })
+        .setup(|app| {
+            if let Some(settings) = load_stored_settings(app.handle()) {
+                app.state::<Arc<SessionMonitor>>().update_settings(settings.security);
+                apply_data_settings(&settings.data);
+            }
+            spawn_session_monitor(app.handle().clone());
+            let handle = app.handle().clone();
+            app.state::<AuthState>().on_session_expired(move || {
+                let _ = handle.emit("auth_session_expired", ());
+            });
+            app.state::<Arc<TokenRefreshState>>().start(app.handle().clone());
             log::info!("Tauri app initialized successfully!");
             Ok(())
         })
@@ -0,0 +1,115 @@
+// src-tauri/src/models/production.rs
+//
+// Production dashboard data returned by `/production/dashboard`.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductionDashboardData {
+    pub total_active_products: i64,
+    pub products_by_status: Vec<StatusCount>,
+    pub products_by_priority: Vec<PriorityCount>,
+    pub throughput_metrics: ThroughputMetrics,
+    pub capacity_utilization: CapacityUtilization,
+    pub sla_performance: SlaPerformance,
+    pub quality_metrics: QualityMetrics,
+    pub bottlenecks: Vec<BottleneckItem>,
+    pub upcoming_deadlines: Vec<DeadlineItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatusCount {
+    pub status: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PriorityCount {
+    pub priority: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ThroughputMetrics {
+    pub products_completed_today: i64,
+    pub products_completed_week: i64,
+    pub products_completed_month: i64,
+    pub average_cycle_time_hours: f64,
+    pub throughput_trend: Vec<ThroughputDataPoint>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ThroughputDataPoint {
+    pub date: String,
+    pub completed_count: i64,
+    pub average_cycle_time: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CapacityUtilization {
+    pub total_capacity: f64,
+    pub utilized_capacity: f64,
+    pub utilization_percentage: f64,
+    pub by_team: Vec<TeamCapacityData>,
+    pub by_user: Vec<UserCapacityData>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TeamCapacityData {
+    pub team_id: i32,
+    pub team_name: String,
+    pub capacity: f64,
+    pub utilization: f64,
+    pub utilization_percentage: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserCapacityData {
+    pub user_id: i32,
+    pub username: String,
+    pub capacity: f64,
+    pub utilization: f64,
+    pub utilization_percentage: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SlaPerformance {
+    pub on_time_percentage: f64,
+    pub average_delay_hours: f64,
+    pub sla_breaches_today: i64,
+    pub sla_breaches_week: i64,
+    pub at_risk_count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QualityMetrics {
+    pub average_quality_score: f64,
+    pub quality_trend: Vec<QualityDataPoint>,
+    pub defect_rate: f64,
+    pub rework_rate: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QualityDataPoint {
+    pub date: String,
+    pub average_score: f64,
+    pub total_inspections: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BottleneckItem {
+    pub workflow_step_name: String,
+    pub products_waiting: i64,
+    pub average_wait_time_hours: f64,
+    pub severity: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeadlineItem {
+    pub product_id: i32,
+    pub product_name: String,
+    pub due_date: String,
+    pub hours_until_due: f64,
+    pub current_status: String,
+    pub priority: String,
+}
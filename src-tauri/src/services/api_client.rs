@@ -1,110 +1,1075 @@
-use crate::auth::login::AuthState;
+use crate::auth::login::{token_status_at, AuthState};
 use crate::services::config::AppConfig;
-use crate::utils::get_auth_header_internal;
-use log::{debug, error};
-use reqwest::{Client, Method};
-use serde::Serialize;
+use crate::services::log_redaction::redact_for_log;
+use crate::services::rate_limiter::RateLimiter;
+use crate::services::request_metrics::{endpoint_key, EndpointMetrics, RequestMetrics};
+use crate::services::response_cache::ResponseCache;
+use crate::utils::bearer_header;
+use futures::StreamExt;
+use log::{debug, error, info, warn};
+use reqwest::{Client, Method, RequestBuilder};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::{oneshot, Semaphore};
+use tokio_util::sync::CancellationToken;
+
+type InFlightGets = std::sync::Mutex<HashMap<String, Vec<oneshot::Sender<Result<String, RequestFailure>>>>>;
+
+/// Header carrying the per-request correlation ID, so backend logs can be
+/// matched to ours
+const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+/// Header letting the backend recognise retries of the same create request
+const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// Headers the client sets itself, which default headers may not override
+const RESERVED_HEADERS: [&str; 4] = ["authorization", "content-type", "x-request-id", "idempotency-key"];
+
+// Check a default header is allowed and is a valid HTTP header
+fn validate_default_header(name: &str, value: &str) -> Result<(), String> {
+    if RESERVED_HEADERS.contains(&name.to_ascii_lowercase().as_str()) {
+        return Err(format!("Header '{}' is managed by the client and can't be overridden", name));
+    }
+    reqwest::header::HeaderName::from_bytes(name.as_bytes())
+        .map_err(|e| format!("Invalid header name '{}': {}", name, e))?;
+    reqwest::header::HeaderValue::from_str(value)
+        .map_err(|e| format!("Invalid value for header '{}': {}", name, e))?;
+    Ok(())
+}
+
+/// Join a base URL and an endpoint with exactly one `/` between them,
+/// whatever slashes either side already has.
+pub(crate) fn join_url(base: &str, endpoint: &str) -> String {
+    let base = base.trim_end_matches('/');
+    let endpoint = endpoint.trim_start_matches('/');
+    if endpoint.is_empty() {
+        base.to_string()
+    } else {
+        format!("{}/{}", base, endpoint)
+    }
+}
+
+fn new_request_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// A failed request along with the correlation ID it was sent with
+#[derive(Debug, Clone)]
+struct RequestFailure {
+    request_id: String,
+    message: String,
+    // Set when the server answered 429 Too Many Requests
+    retry_after: Option<Duration>,
+    // No response, or a gateway error; the same request may well succeed
+    transient: bool,
+    // Status of the error response, if the server answered
+    status: Option<reqwest::StatusCode>,
+}
+
+impl RequestFailure {
+    // A failure before any response arrived, e.g. no token or a closed limiter
+    fn local(request_id: &str, message: String) -> Self {
+        RequestFailure {
+            request_id: request_id.to_string(),
+            message,
+            retry_after: None,
+            transient: false,
+            status: None,
+        }
+    }
+}
+
+impl From<RequestFailure> for String {
+    fn from(failure: RequestFailure) -> Self {
+        ApiError::from(failure).to_string()
+    }
+}
+
+/// Error returned by the typed JSON helpers and cancellable requests
+#[derive(Debug)]
+pub enum ApiError {
+    /// The request failed or the server returned a non-success status.
+    /// `request_id` is the `X-Request-Id` sent, for quoting to support.
+    /// `status` is set when the server answered.
+    Request { message: String, request_id: String, status: Option<reqwest::StatusCode> },
+    /// The response was not the expected `{ "data": ... }` JSON
+    Parse(String),
+    /// The request was cancelled through `ApiClient::cancel_request`
+    Cancelled { request_id: String },
+    /// The server answered 429 and asked to wait `retry_after_secs`
+    RateLimited { retry_after_secs: u64, request_id: String },
+}
+
+impl From<RequestFailure> for ApiError {
+    fn from(failure: RequestFailure) -> Self {
+        match failure.retry_after {
+            Some(wait) => ApiError::RateLimited { retry_after_secs: wait.as_secs().max(1), request_id: failure.request_id },
+            None => ApiError::Request {
+                message: failure.message,
+                request_id: failure.request_id,
+                status: failure.status,
+            },
+        }
+    }
+}
+
+impl ApiError {
+    /// HTTP status of the error response, if the server answered
+    pub fn status(&self) -> Option<reqwest::StatusCode> {
+        match self {
+            ApiError::Request { status, .. } => *status,
+            ApiError::RateLimited { .. } => Some(reqwest::StatusCode::TOO_MANY_REQUESTS),
+            ApiError::Parse(_) | ApiError::Cancelled { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::Request { message, request_id, .. } => {
+                write!(f, "{} (request ID: {})", message, request_id)
+            }
+            ApiError::Parse(message) => write!(f, "Failed to parse response: {}", message),
+            ApiError::Cancelled { request_id } => write!(f, "Request {} was cancelled", request_id),
+            ApiError::RateLimited { retry_after_secs, request_id } => write!(
+                f,
+                "Rate limited by the server, retry in {} seconds (request ID: {})",
+                retry_after_secs, request_id
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<ApiError> for String {
+    fn from(error: ApiError) -> Self {
+        error.to_string()
+    }
+}
+
+// Standard response envelope used by the backend
+#[derive(Deserialize)]
+struct Envelope<T> {
+    data: T,
+}
+
+fn unwrap_envelope<T: DeserializeOwned>(body: &str) -> Result<T, ApiError> {
+    serde_json::from_str::<Envelope<T>>(body)
+        .map(|envelope| envelope.data)
+        .map_err(|e| ApiError::Parse(e.to_string()))
+}
+
+/// Result of probing the backend for reachability
+#[derive(Debug, Serialize)]
+pub struct HealthStatus {
+    pub reachable: bool,
+    pub latency_ms: u64,
+    pub version: Option<String>,
+}
+
+/// Uploads may legitimately outlast `api_timeout_seconds` on slow links
+pub const UPLOAD_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// Health probes should answer quickly regardless of `api_timeout_seconds`
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Authenticated request with one-off headers, built by `ApiClient::with_header`.
+/// Header validation errors are reported when the request is sent.
+pub struct HeaderScopedRequest<'a> {
+    client: &'a ApiClient,
+    headers: Vec<(String, String)>,
+    invalid: Option<String>,
+}
+
+#[allow(dead_code)]
+impl HeaderScopedRequest<'_> {
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        let name = name.to_ascii_lowercase();
+        match validate_default_header(&name, value) {
+            Ok(()) => {
+                self.headers.retain(|(existing, _)| *existing != name);
+                self.headers.push((name, value.to_string()));
+            }
+            Err(e) => {
+                self.invalid.get_or_insert(e);
+            }
+        }
+        self
+    }
+
+    async fn send<T: Serialize>(&self, method: Method, endpoint: &str, body: Option<&T>) -> Result<String, String> {
+        if let Some(e) = &self.invalid {
+            return Err(e.clone());
+        }
+        Ok(self.client.send(method, endpoint, body, None, &self.headers).await?)
+    }
+
+    pub async fn get(&self, endpoint: &str) -> Result<String, String> {
+        self.send(Method::GET, endpoint, None::<&()>).await
+    }
+
+    pub async fn post<T: Serialize>(&self, endpoint: &str, body: &T) -> Result<String, String> {
+        self.send(Method::POST, endpoint, Some(body)).await
+    }
+
+    pub async fn put<T: Serialize>(&self, endpoint: &str, body: &T) -> Result<String, String> {
+        self.send(Method::PUT, endpoint, Some(body)).await
+    }
+
+    pub async fn delete(&self, endpoint: &str) -> Result<String, String> {
+        self.send(Method::DELETE, endpoint, None::<&()>).await
+    }
+}
+
+// Items of a buffered list response: a bare array, an array under `data`,
+// or the single array inside a `data` object (e.g. `{"data": {"products": []}}`)
+fn json_list_items(body: &str) -> Result<Vec<serde_json::Value>, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(body).map_err(|e| format!("Failed to parse list response: {}", e))?;
+    let list = match value {
+        serde_json::Value::Array(items) => Some(items),
+        serde_json::Value::Object(mut object) => match object.remove("data") {
+            Some(serde_json::Value::Array(items)) => Some(items),
+            Some(serde_json::Value::Object(data)) => {
+                let mut arrays = data.into_iter().filter_map(|(_, v)| match v {
+                    serde_json::Value::Array(items) => Some(items),
+                    _ => None,
+                });
+                match (arrays.next(), arrays.next()) {
+                    (Some(items), None) => Some(items),
+                    _ => None,
+                }
+            }
+            _ => None,
+        },
+        _ => None,
+    };
+    list.ok_or_else(|| "List response did not contain an array".to_string())
+}
+
+/// Body and ETag of a binary download
+pub struct BinaryResponse {
+    pub bytes: Vec<u8>,
+    pub etag: Option<String>,
+}
+
+// A successful list response opened by `get_streamed`
+enum StreamedResponse<'a> {
+    // Plain JSON, already read in full
+    Buffered(String),
+    // NDJSON still to be read; the request slot stays taken until it is
+    Ndjson {
+        url: String,
+        started: Instant,
+        response: reqwest::Response,
+        permit: tokio::sync::SemaphorePermit<'a>,
+    },
+}
 
 pub struct ApiClient {
     client: Client,
     config: AppConfig,
-    auth_state: Arc<Mutex<AuthState>>,
+    // Starts as `config.api_base_url`; replaced when switching profiles
+    base_url: std::sync::RwLock<String>,
+    auth_state: AuthState,
+    // Caps concurrent requests at `AppConfig::max_concurrent_requests`
+    request_limit: Semaphore,
+    // Spaces requests out to `AppConfig::requests_per_second`, if set.
+    // Shared with background clients so every request counts against it.
+    rate_limiter: Option<Arc<RateLimiter>>,
+    // Callers waiting on a GET that is already in flight, keyed by endpoint
+    in_flight_gets: InFlightGets,
+    // Opt-in cache used by `cached_get`
+    response_cache: std::sync::Mutex<ResponseCache>,
+    // Sent with every request; see `set_default_header`. Shared with
+    // background clients so a header set at runtime reaches them too.
+    default_headers: Arc<std::sync::RwLock<HashMap<String, String>>>,
+    // Tokens of in-flight cancellable requests, keyed by request id
+    cancellable_requests: std::sync::Mutex<HashMap<String, CancellationToken>>,
+    // Recent request durations, reported by `api_metrics`
+    request_metrics: std::sync::Mutex<RequestMetrics>,
+    // Set once a response echoes `Idempotency-Key`; see `post_idempotent`
+    idempotency_supported: AtomicBool,
+}
+
+/// Endpoint exchanging the current token for a fresh one
+const TOKEN_REFRESH_ENDPOINT: &str = "/auth/refresh";
+
+/// Upper bound on cached GET responses before LRU eviction kicks in
+const RESPONSE_CACHE_MAX_ENTRIES: usize = 128;
+
+/// Attempts made by `post_multipart_file` before giving up
+const MULTIPART_MAX_ATTEMPTS: u32 = 3;
+
+/// Delay before the first upload retry; later retries wait proportionally longer
+const MULTIPART_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Attempts made by `post_idempotent` before giving up
+const IDEMPOTENT_POST_MAX_ATTEMPTS: u32 = 3;
+
+/// Delay before the first `post_idempotent` retry; later ones wait longer
+const IDEMPOTENT_POST_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Retries of an idempotent request answered with 429
+const RATE_LIMIT_MAX_RETRIES: u32 = 1;
+
+/// Longest Retry-After honoured automatically; longer waits go to the caller
+const RATE_LIMIT_MAX_WAIT: Duration = Duration::from_secs(30);
+
+/// Wait assumed when a 429 has no usable Retry-After header
+const RATE_LIMIT_DEFAULT_WAIT: Duration = Duration::from_secs(1);
+
+// Retry-After is either a number of seconds or an HTTP date
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let wait = at.with_timezone(&chrono::Utc) - chrono::Utc::now();
+    Some(wait.to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Durations kept per endpoint for latency metrics
+const REQUEST_METRICS_MAX_SAMPLES: usize = 200;
+
+/// Clears an in-flight GET entry even if the leading request is cancelled,
+/// so waiters are released instead of hanging.
+struct InFlightGuard<'a> {
+    in_flight: &'a InFlightGets,
+    endpoint: &'a str,
+    armed: bool,
+}
+
+impl InFlightGuard<'_> {
+    // Remove the entry and hand back everyone waiting on it
+    fn take_waiters(mut self) -> Vec<oneshot::Sender<Result<String, RequestFailure>>> {
+        self.armed = false;
+        self.in_flight
+            .lock()
+            .ok()
+            .and_then(|mut in_flight| in_flight.remove(self.endpoint))
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        if self.armed {
+            if let Ok(mut in_flight) = self.in_flight.lock() {
+                in_flight.remove(self.endpoint);
+            }
+        }
+    }
+}
+
+// HTTP client honoring the configured timeout, proxy and extra root CA
+fn build_http_client(config: &AppConfig) -> Result<Client, String> {
+    let mut builder = Client::builder().timeout(Duration::from_secs(config.api_timeout_seconds));
+
+    if let Some(proxy_url) = &config.https_proxy {
+        let proxy = reqwest::Proxy::https(proxy_url)
+            .map_err(|e| format!("Invalid proxy URL '{}': {}", proxy_url, e))?
+            .no_proxy(config.no_proxy.as_deref().and_then(reqwest::NoProxy::from_string));
+        info!("Routing API requests through proxy {}", proxy_url);
+        builder = builder.proxy(proxy);
+    }
+
+    if let Some(ca_path) = &config.ca_cert_path {
+        let bytes = std::fs::read(ca_path)
+            .map_err(|e| format!("Failed to read CA certificate '{}': {}", ca_path, e))?;
+        let certificate = reqwest::Certificate::from_pem(&bytes)
+            .or_else(|_| reqwest::Certificate::from_der(&bytes))
+            .map_err(|e| format!("Invalid CA certificate '{}': {}", ca_path, e))?;
+        info!("Trusting additional CA certificate from {}", ca_path);
+        builder = builder.add_root_certificate(certificate);
+    }
+
+    builder.build().map_err(|e| format!("Failed to create HTTP client: {}", e))
 }
 
 impl ApiClient {
-    pub fn new(config: AppConfig, auth_state: Arc<Mutex<AuthState>>) -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(config.api_timeout_seconds))
-            .build()
-            .expect("Failed to create HTTP client");
+    /// Fails if the proxy or CA certificate settings are unusable.
+    pub fn new(config: AppConfig, auth_state: AuthState) -> Result<Self, String> {
+        let client = build_http_client(&config)?;
 
-        Self {
+        let default_headers = config
+            .default_headers
+            .iter()
+            .filter(|(name, value)| match validate_default_header(name, value) {
+                Ok(()) => true,
+                Err(e) => {
+                    warn!("Ignoring configured default header: {}", e);
+                    false
+                }
+            })
+            .map(|(name, value)| (name.to_ascii_lowercase(), value.clone()))
+            .collect();
+
+        Ok(Self {
+            default_headers: Arc::new(std::sync::RwLock::new(default_headers)),
+            cancellable_requests: std::sync::Mutex::new(HashMap::new()),
             client,
+            request_limit: Semaphore::new(config.max_concurrent_requests),
+            rate_limiter: config.requests_per_second.map(|rate| Arc::new(RateLimiter::new(rate))),
+            in_flight_gets: std::sync::Mutex::new(HashMap::new()),
+            response_cache: std::sync::Mutex::new(ResponseCache::new(RESPONSE_CACHE_MAX_ENTRIES)),
+            request_metrics: std::sync::Mutex::new(RequestMetrics::new(REQUEST_METRICS_MAX_SAMPLES)),
+            idempotency_supported: AtomicBool::new(false),
+            base_url: std::sync::RwLock::new(config.api_base_url.clone()),
             config,
             auth_state,
-        }
+        })
+    }
+
+    /// A client for background work such as polling. It has its own
+    /// connections and concurrency slots, so it doesn't queue behind user
+    /// actions, but shares this client's session, server, default headers and
+    /// rate limit.
+    pub fn background_client(&self) -> Result<ApiClient, String> {
+        let mut config = self.config.clone();
+        config.api_base_url = self.base_url();
+        let mut client = ApiClient::new(config, self.auth_state.clone())?;
+        client.default_headers = self.default_headers.clone();
+        client.rate_limiter = self.rate_limiter.clone();
+        Ok(client)
     }
 
     // GET request - returns raw string
+    // Identical concurrent GETs share a single network request.
     pub async fn get(&self, endpoint: &str) -> Result<String, String> {
-        self.request(Method::GET, endpoint, None::<&()>).await
+        Ok(self.get_shared(endpoint).await?)
+    }
+
+    async fn get_shared(&self, endpoint: &str) -> Result<String, RequestFailure> {
+        let waiter = {
+            let mut in_flight = self.in_flight_gets.lock().map_err(|e| RequestFailure {
+                retry_after: None,
+                transient: false,
+                status: None,
+                request_id: new_request_id(),
+                message: e.to_string(),
+            })?;
+            match in_flight.get_mut(endpoint) {
+                Some(waiters) => {
+                    let (tx, rx) = oneshot::channel();
+                    waiters.push(tx);
+                    Some(rx)
+                }
+                None => {
+                    in_flight.insert(endpoint.to_string(), Vec::new());
+                    None
+                }
+            }
+        };
+
+        if let Some(rx) = waiter {
+            debug!("Joining in-flight GET to: {}", endpoint);
+            return match rx.await {
+                Ok(result) => result,
+                // The leading request was cancelled; issue our own
+                Err(_) => self.request(Method::GET, endpoint, None::<&()>).await,
+            };
+        }
+
+        let guard = InFlightGuard { in_flight: &self.in_flight_gets, endpoint, armed: true };
+        let result = self.request(Method::GET, endpoint, None::<&()>).await;
+        for waiter in guard.take_waiters() {
+            let _ = waiter.send(result.clone());
+        }
+        result
+    }
+
+    // GET request - deserializes the `data` field of the response envelope
+    pub async fn get_json<T: DeserializeOwned>(&self, endpoint: &str) -> Result<T, ApiError> {
+        let body = self.get_shared(endpoint).await?;
+        unwrap_envelope(&body)
+    }
+
+    // POST request - deserializes the `data` field of the response envelope
+    pub async fn post_json<B: Serialize, T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        body: &B,
+    ) -> Result<T, ApiError> {
+        let body = self.request(Method::POST, endpoint, Some(body)).await?;
+        unwrap_envelope(&body)
+    }
+
+    // PUT request - deserializes the `data` field of the response envelope
+    pub async fn put_json<B: Serialize, T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        body: &B,
+    ) -> Result<T, ApiError> {
+        let body = self.request(Method::PUT, endpoint, Some(body)).await?;
+        unwrap_envelope(&body)
+    }
+
+    // GET request served from the in-memory cache while younger than `ttl`.
+    // Only successful responses are cached.
+    pub async fn cached_get(&self, endpoint: &str, ttl: Duration) -> Result<String, String> {
+        if let Some(body) = self.response_cache.lock().ok().and_then(|mut cache| cache.get(endpoint)) {
+            debug!("Cache hit for: {}", endpoint);
+            return Ok(body);
+        }
+
+        let body = self.get(endpoint).await?;
+        if let Ok(mut cache) = self.response_cache.lock() {
+            cache.insert(endpoint, body.clone(), ttl);
+        }
+        Ok(body)
+    }
+
+    // Authenticated request returning the typed error, for callers that
+    // branch on the HTTP status rather than the error text
+    pub async fn execute<T: Serialize>(
+        &self,
+        method: Method,
+        endpoint: &str,
+        body: Option<&T>,
+    ) -> Result<String, ApiError> {
+        if method == Method::GET && body.is_none() {
+            return Ok(self.get_shared(endpoint).await?);
+        }
+        Ok(self.request(method, endpoint, body).await?)
+    }
+
+    // Drop cached GET responses for endpoints starting with `prefix`
+    pub fn invalidate_cache(&self, prefix: &str) {
+        if let Ok(mut cache) = self.response_cache.lock() {
+            cache.invalidate(prefix);
+        }
     }
 
     // POST request - returns raw string
     pub async fn post<T: Serialize>(&self, endpoint: &str, body: &T) -> Result<String, String> {
-        self.request(Method::POST, endpoint, Some(body)).await
+        Ok(self.request(Method::POST, endpoint, Some(body)).await?)
     }
 
     // PUT request - returns raw string
     pub async fn put<T: Serialize>(&self, endpoint: &str, body: &T) -> Result<String, String> {
-        self.request(Method::PUT, endpoint, Some(body)).await
+        Ok(self.request(Method::PUT, endpoint, Some(body)).await?)
+    }
+
+    // POST for create operations. Every attempt carries the same
+    // `Idempotency-Key` so the backend can drop duplicates, which makes
+    // retrying after a network error or 502-504 safe. This needs backend
+    // support: until a response has echoed the header, the backend is assumed
+    // to ignore it and failures are returned without retrying, as with `post`.
+    pub async fn post_idempotent<T: Serialize>(&self, endpoint: &str, body: &T) -> Result<String, String> {
+        let headers = [(IDEMPOTENCY_KEY_HEADER.to_string(), uuid::Uuid::new_v4().to_string())];
+        let mut attempt = 1;
+        loop {
+            match self.send(Method::POST, endpoint, Some(body), None, &headers).await {
+                Ok(body) => return Ok(body),
+                Err(failure)
+                    if failure.transient
+                        && attempt < IDEMPOTENT_POST_MAX_ATTEMPTS
+                        && self.idempotency_supported.load(Ordering::Relaxed) =>
+                {
+                    warn!(
+                        "[{}] POST attempt {} of {} failed, retrying: {}",
+                        failure.request_id, attempt, IDEMPOTENT_POST_MAX_ATTEMPTS, failure.message
+                    );
+                    tokio::time::sleep(IDEMPOTENT_POST_RETRY_DELAY * attempt).await;
+                    attempt += 1;
+                }
+                Err(failure) => return Err(failure.into()),
+            }
+        }
     }
 
     // PATCH request - returns raw string
     pub async fn patch<T: Serialize>(&self, endpoint: &str, body: &T) -> Result<String, String> {
-        self.request(Method::PATCH, endpoint, Some(body)).await
+        Ok(self.request(Method::PATCH, endpoint, Some(body)).await?)
     }
 
     // DELETE request - returns raw string
     pub async fn delete(&self, endpoint: &str) -> Result<String, String> {
-        self.request(Method::DELETE, endpoint, None::<&()>).await
+        Ok(self.request(Method::DELETE, endpoint, None::<&()>).await?)
+    }
+
+    // GET request that can be stopped with `cancel_request(request_id)`.
+    // `request_id` doubles as the `X-Request-Id` correlation ID.
+    pub async fn get_cancellable(&self, endpoint: &str, request_id: &str) -> Result<String, ApiError> {
+        self.cancellable(Method::GET, endpoint, None::<&()>, request_id).await
+    }
+
+    // POST request that can be stopped with `cancel_request(request_id)`
+    #[allow(dead_code)] // No command posts cancellably yet
+    pub async fn post_cancellable<T: Serialize>(
+        &self,
+        endpoint: &str,
+        body: &T,
+        request_id: &str,
+    ) -> Result<String, ApiError> {
+        self.cancellable(Method::POST, endpoint, Some(body), request_id).await
+    }
+
+    /// Cancel an in-flight cancellable request. Returns false if no such
+    /// request is running.
+    pub fn cancel_request(&self, request_id: &str) -> bool {
+        let requests = self.cancellable_requests.lock().unwrap_or_else(|e| e.into_inner());
+        match requests.get(request_id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    async fn cancellable<T: Serialize>(
+        &self,
+        method: Method,
+        endpoint: &str,
+        body: Option<&T>,
+        request_id: &str,
+    ) -> Result<String, ApiError> {
+        let token = CancellationToken::new();
+        self.cancellable_requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(request_id.to_string(), token.clone());
+
+        // Dropping the request future aborts the connection
+        let result = tokio::select! {
+            biased;
+            _ = token.cancelled() => {
+                info!("[{}] Request cancelled", request_id);
+                Err(ApiError::Cancelled { request_id: request_id.to_string() })
+            }
+            result = self.send_as(request_id.to_string(), method, endpoint, body, None, &[]) => {
+                result.map_err(ApiError::from)
+            }
+        };
+
+        self.cancellable_requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(request_id);
+        result
+    }
+
+    // GET request with a per-request timeout overriding the configured one
+    pub async fn get_with_timeout(&self, endpoint: &str, timeout: Duration) -> Result<String, String> {
+        self.request_with_timeout(Method::GET, endpoint, None::<&()>, Some(timeout)).await
     }
 
-    // Multipart form upload
+    // POST request with a per-request timeout overriding the configured one
+    pub async fn post_with_timeout<T: Serialize>(
+        &self,
+        endpoint: &str,
+        body: &T,
+        timeout: Duration,
+    ) -> Result<String, String> {
+        self.request_with_timeout(Method::POST, endpoint, Some(body), Some(timeout)).await
+    }
+
+    /// Authenticated request with an optional timeout for this call only.
+    /// `None` falls back to `AppConfig::api_timeout_seconds`.
+    pub async fn request_with_timeout<T: Serialize>(
+        &self,
+        method: Method,
+        endpoint: &str,
+        body: Option<&T>,
+        timeout: Option<Duration>,
+    ) -> Result<String, String> {
+        Ok(self.send(method, endpoint, body, timeout, &[]).await?)
+    }
+
+    // Multipart form upload. The form can't be replayed, so if the token
+    // has to be refreshed mid-request the upload fails and must be retried.
     pub async fn post_multipart(
         &self,
         endpoint: &str,
         form: reqwest::multipart::Form,
     ) -> Result<String, String> {
-        let auth_header = {
-            let auth_state = self.auth_state.lock().await;
-            get_auth_header_internal(&*auth_state).await?
+        let mut form = Some(form);
+        let make_form = move || {
+            let form = form.take();
+            async move { form.ok_or_else(|| "Upload interrupted by a token refresh, please retry".to_string()) }
         };
-        let url = format!("{}{}", self.config.api_base_url, endpoint);
-        
-        debug!("POST (multipart) request to: {}", url);
-        
-        let response = self.client
-            .post(&url)
-            .header("Authorization", auth_header)
-            .multipart(form)
-            .send()
-            .await
-            .map_err(|e| {
-                error!("Request failed: {}", e);
-                format!("Request failed: {}", e)
-            })?;
+        self.multipart_attempt(endpoint, make_form).await.map_err(|(message, _)| message)
+    }
+
+    // Multipart upload of a single file plus any text fields, retried on
+    // network errors and 5xx responses. The form is rebuilt from disk for each
+    // attempt, since a multipart body can't be replayed once sent.
+    pub async fn post_multipart_file(
+        &self,
+        endpoint: &str,
+        field_name: &str,
+        file_path: &Path,
+        text_fields: &[(&str, &str)],
+    ) -> Result<String, String> {
+        let make_form = || async move {
+            text_fields
+                .iter()
+                .fold(reqwest::multipart::Form::new(), |form, (name, value)| {
+                    form.text(name.to_string(), value.to_string())
+                })
+                .file(field_name.to_string(), file_path)
+                .await
+                .map_err(|e| format!("Failed to read {}: {}", file_path.display(), e))
+        };
+        let mut attempt = 1;
+        loop {
+            match self.multipart_attempt(endpoint, make_form).await {
+                Ok(body) => return Ok(body),
+                Err((message, true)) if attempt < MULTIPART_MAX_ATTEMPTS => {
+                    warn!("Upload attempt {} of {} failed, retrying: {}", attempt, MULTIPART_MAX_ATTEMPTS, message);
+                    tokio::time::sleep(MULTIPART_RETRY_DELAY * attempt).await;
+                    attempt += 1;
+                }
+                Err((message, _)) => {
+                    return Err(format!("Upload failed after {} attempt(s): {}", attempt, message));
+                }
+            }
+        }
+    }
+
+    // Raw binary POST, e.g. one chunk of a resumable upload. Errors carry the
+    // response status, or `None` when the request never got a response.
+    pub async fn post_bytes(
+        &self,
+        endpoint: &str,
+        bytes: Vec<u8>,
+    ) -> Result<String, (String, Option<reqwest::StatusCode>)> {
+        let request_id = &new_request_id();
+        self.with_session_retries(request_id, &Method::POST, || {
+            let bytes = bytes.clone();
+            async move {
+                let (url, started, response, _permit) = self
+                    .send_built(request_id, Method::POST, endpoint, |request| {
+                        request
+                            .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+                            .timeout(UPLOAD_TIMEOUT)
+                            .body(bytes)
+                    })
+                    .await?;
+                self.handle_response(request_id, &Method::POST, &url, started, response).await
+            }
+        })
+        .await
+        .map_err(|failure| (failure.message, failure.status))
+    }
+
+    // One multipart POST, building the form with `make_form` for each send;
+    // the flag on errors says whether retrying may help
+    async fn multipart_attempt<F, Fut>(&self, endpoint: &str, mut make_form: F) -> Result<String, (String, bool)>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<reqwest::multipart::Form, String>>,
+    {
+        let request_id = &new_request_id();
+        self.with_session_retries(request_id, &Method::POST, || {
+            let form = make_form();
+            async move {
+                let form = form.await.map_err(|message| RequestFailure::local(request_id, message))?;
+                let (url, started, response, _permit) = self
+                    .send_built(request_id, Method::POST, endpoint, |request| {
+                        request.timeout(UPLOAD_TIMEOUT).multipart(form)
+                    })
+                    .await?;
+                self.handle_response(request_id, &Method::POST, &url, started, response).await
+            }
+        })
+        .await
+        .map_err(|failure| {
+            let retryable = failure.transient || failure.status.is_some_and(|status| status.is_server_error());
+            (failure.message, retryable)
+        })
+    }
+
+    // Binary GET that skips the body when a cached copy is still current.
+    // Returns `Ok(None)` on 304, or when neither side has an ETag and the
+    // Content-Length matches `cached_len`.
+    pub async fn get_bytes_if_changed(
+        &self,
+        endpoint: &str,
+        etag: Option<&str>,
+        cached_len: Option<u64>,
+    ) -> Result<Option<BinaryResponse>, String> {
+        let request_id = &new_request_id();
+        let download = self.with_session_retries(request_id, &Method::GET, || async move {
+            let (url, started, response, _permit) = self
+                .send_built(request_id, Method::GET, endpoint, |request| match etag {
+                    Some(etag) => request.header("If-None-Match", etag),
+                    None => request,
+                })
+                .await?;
 
-        self.handle_response(response).await
+            let status = response.status();
+            if status == reqwest::StatusCode::NOT_MODIFIED {
+                debug!("[{}] Cached copy still current", request_id);
+                return Ok(None);
+            }
+            if !status.is_success() {
+                // Always a failure here, carrying the status and any Retry-After
+                return self.handle_response(request_id, &Method::GET, &url, started, response).await.map(|_| None);
+            }
+
+            let new_etag = response
+                .headers()
+                .get(reqwest::header::ETAG)
+                .and_then(|v| v.to_str().ok())
+                .map(String::from);
+            if etag.is_none() && new_etag.is_none() && cached_len.is_some() && response.content_length() == cached_len {
+                debug!("[{}] Cached copy matches remote size", request_id);
+                return Ok(None);
+            }
+
+            let bytes = response.bytes().await.map_err(|e| {
+                error!("[{}] Failed to read response: {}", request_id, e);
+                RequestFailure::local(request_id, format!("Failed to read response: {}", e))
+            })?;
+            Ok(Some(BinaryResponse { bytes: bytes.to_vec(), etag: new_etag }))
+        });
+        Ok(download.await?)
     }
 
-    // GET request without auth
-    pub async fn get_no_auth(&self, endpoint: &str) -> Result<String, String> {
-        self.request_no_auth(Method::GET, endpoint, None::<&()>).await
+    // GET of a list endpoint, handing each item to `on_item` as it arrives.
+    // NDJSON responses are parsed line by line; a normal JSON array (bare or
+    // under `data`) is buffered and then handed over item by item. Returns
+    // the number of items.
+    pub async fn get_streamed<F>(&self, endpoint: &str, mut on_item: F) -> Result<usize, String>
+    where
+        F: FnMut(serde_json::Value),
+    {
+        let request_id = &new_request_id();
+        // Errors are settled, and retried, before any item is handed over
+        let opened = self.with_session_retries(request_id, &Method::GET, || async move {
+            let (url, started, response, permit) = self
+                .send_built(request_id, Method::GET, endpoint, |request| {
+                    request.header(reqwest::header::ACCEPT, "application/x-ndjson, application/json;q=0.9")
+                })
+                .await?;
+            let is_ndjson = response
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|content_type| content_type.contains("ndjson"));
+            if !is_ndjson || !response.status().is_success() {
+                let body = self.handle_response(request_id, &Method::GET, &url, started, response).await?;
+                return Ok(StreamedResponse::Buffered(body));
+            }
+            Ok(StreamedResponse::Ndjson { url, started, response, permit })
+        });
+        let (url, started, response, _permit) = match opened.await? {
+            StreamedResponse::Buffered(body) => {
+                let items = json_list_items(&body)?;
+                let count = items.len();
+                items.into_iter().for_each(&mut on_item);
+                return Ok(count);
+            }
+            StreamedResponse::Ndjson { url, started, response, permit } => (url, started, response, permit),
+        };
+
+        let mut chunks = response.bytes_stream();
+        let mut pending: Vec<u8> = Vec::new();
+        let mut count = 0;
+        let mut line_number = 0;
+        loop {
+            let chunk = chunks.next().await.transpose().map_err(|e| {
+                error!("[{}] Failed to read response: {}", request_id, e);
+                format!("Failed to read response: {}", e)
+            })?;
+            let finished = chunk.is_none();
+            match chunk {
+                Some(chunk) => pending.extend_from_slice(&chunk),
+                // A final line may lack its newline
+                None if !pending.is_empty() => pending.push(b'\n'),
+                None => {}
+            }
+            while let Some(end) = pending.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = pending.drain(..=end).collect();
+                line_number += 1;
+                if line.trim_ascii().is_empty() {
+                    continue;
+                }
+                let item = serde_json::from_slice(&line).map_err(|e| {
+                    error!("[{}] Invalid NDJSON on line {}: {}", request_id, line_number, e);
+                    format!("Invalid NDJSON on line {}: {}", line_number, e)
+                })?;
+                on_item(item);
+                count += 1;
+            }
+            if finished {
+                break;
+            }
+        }
+
+        if let Ok(mut metrics) = self.request_metrics.lock() {
+            metrics.record(endpoint_key(Method::GET.as_str(), &url), started.elapsed());
+        }
+        debug!("[{}] Streamed {} items in {} ms", request_id, count, started.elapsed().as_millis());
+        Ok(count)
     }
 
     // POST request without auth
     pub async fn post_no_auth<T: Serialize>(&self, endpoint: &str, body: &T) -> Result<String, String> {
-        self.request_no_auth(Method::POST, endpoint, Some(body)).await
+        Ok(self.request_no_auth(Method::POST, endpoint, Some(body), None).await?)
     }
 
-    // PUT request without auth
-    pub async fn put_no_auth<T: Serialize>(&self, endpoint: &str, body: &T) -> Result<String, String> {
-        self.request_no_auth(Method::PUT, endpoint, Some(body)).await
-    }
+    // Probe `/health` without auth; if the backend has no such route, any
+    // response to `HEAD /` still proves it is reachable.
+    pub async fn check_api_health(&self) -> HealthStatus {
+        let started = Instant::now();
+        let elapsed_ms = |started: Instant| started.elapsed().as_millis() as u64;
 
-    // DELETE request without auth
-    pub async fn delete_no_auth(&self, endpoint: &str) -> Result<String, String> {
-        self.request_no_auth(Method::DELETE, endpoint, None::<&()>).await
+        let health = self
+            .request_no_auth(Method::GET, "/health", None::<&()>, Some(HEALTH_CHECK_TIMEOUT))
+            .await;
+        match health {
+            Ok(body) => {
+                let version = serde_json::from_str::<serde_json::Value>(&body).ok().and_then(|json| {
+                    json["version"]
+                        .as_str()
+                        .or_else(|| json["data"]["version"].as_str())
+                        .map(String::from)
+                });
+                HealthStatus { reachable: true, latency_ms: elapsed_ms(started), version }
+            }
+            Err(RequestFailure {
+                status: Some(reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::METHOD_NOT_ALLOWED),
+                ..
+            }) => {
+                debug!("No health endpoint, falling back to HEAD /");
+                let started = Instant::now();
+                // Any answer at all proves the server is up
+                let reachable = match self
+                    .request_no_auth(Method::HEAD, "/", None::<&()>, Some(HEALTH_CHECK_TIMEOUT))
+                    .await
+                {
+                    Ok(_) => true,
+                    Err(failure) => failure.status.is_some(),
+                };
+                HealthStatus { reachable, latency_ms: elapsed_ms(started), version: None }
+            }
+            Err(failure) => {
+                error!("[{}] Health check failed: {}", failure.request_id, failure.message);
+                HealthStatus { reachable: false, latency_ms: elapsed_ms(started), version: None }
+            }
+        }
     }
 
     pub async fn set_token(&self, token: String) {
-        let mut auth_state = self.auth_state.lock().await;
-        let mut token_guard = auth_state.token.lock().await;
-        *token_guard = Some(token);
+        *self.auth_state.token.write().await = Some(token);
+    }
+
+    /// Exchange the current token for a new one via `/auth/refresh` and store it
+    pub async fn refresh_token(&self) -> Result<String, String> {
+        let response = self
+            .send_once(&new_request_id(), Method::POST, TOKEN_REFRESH_ENDPOINT, Some(&serde_json::json!({})), None, &[])
+            .await?;
+        let body: serde_json::Value = serde_json::from_str(&response)
+            .map_err(|e| format!("Failed to parse refresh response: {}", e))?;
+        let token = body["data"]["token"].as_str().or_else(|| body["token"].as_str());
+        let token = token.ok_or_else(|| "Refresh response did not include a token".to_string())?;
+        self.set_token(token.to_string()).await;
+        Ok(token.to_string())
+    }
+
+    // Whether a 401 was caused by the token expiring: the token's own expiry
+    // has passed, or the server says so (opaque tokens carry no expiry)
+    async fn token_expired(&self, failure: &RequestFailure) -> bool {
+        let token = self.auth_state.token.read().await.clone();
+        let status = token_status_at(token.as_deref(), chrono::Utc::now().timestamp());
+        let expired_by_claims = !status.valid && status.seconds_remaining.is_some();
+        expired_by_claims || failure.message.to_ascii_lowercase().contains("expired")
+    }
+
+    pub async fn clear_token(&self) {
+        *self.auth_state.token.write().await = None;
+        *self.auth_state.role.write().await = None;
+        *self.auth_state.profile.write().await = None;
+    }
+
+    /// Latency summary per endpoint over recent requests
+    pub fn api_metrics(&self) -> Vec<EndpointMetrics> {
+        self.request_metrics
+            .lock()
+            .map(|metrics| metrics.summary())
+            .unwrap_or_default()
+    }
+
+    /// Add, replace or (with `None`) remove a header sent on every request
+    pub fn set_default_header(&self, name: &str, value: Option<String>) -> Result<(), String> {
+        // Header names are case-insensitive
+        let name = name.to_ascii_lowercase();
+        let mut headers = self.default_headers.write().unwrap_or_else(|e| e.into_inner());
+        match value {
+            Some(value) => {
+                validate_default_header(&name, &value)?;
+                headers.insert(name, value);
+            }
+            None => {
+                headers.remove(&name);
+            }
+        }
+        Ok(())
+    }
+
+    /// Start a request carrying a one-off header on top of the defaults, e.g.
+    /// `api_client.with_header("X-Api-Key", key).get("/reports")`.
+    #[allow(dead_code)] // For gateway-specific calls; no command needs one yet
+    pub fn with_header(&self, name: &str, value: &str) -> HeaderScopedRequest<'_> {
+        HeaderScopedRequest { client: self, headers: Vec::new(), invalid: None }.with_header(name, value)
+    }
+
+    // Default headers plus any one-off `extra` headers, which take precedence
+    fn with_default_headers(&self, mut request: RequestBuilder, extra: &[(String, String)]) -> RequestBuilder {
+        let headers = self.default_headers.read().unwrap_or_else(|e| e.into_inner());
+        for (name, value) in headers.iter() {
+            if !extra.iter().any(|(extra_name, _)| extra_name == name) {
+                request = request.header(name.as_str(), value.as_str());
+            }
+        }
+        for (name, value) in extra {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        request
+    }
+
+    pub fn base_url(&self) -> String {
+        self.base_url.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    // Point the client at another server and token in one step. The token
+    // write lock is held while the URL changes so no request pairs the old
+    // server with the new token or vice versa.
+    pub async fn switch_session(&self, base_url: String, token: Option<String>) {
+        let mut token_guard = self.auth_state.token.write().await;
+        *self.base_url.write().unwrap_or_else(|e| e.into_inner()) = base_url;
+        *token_guard = token;
+        // The role and profile belonged to the previous session
+        *self.auth_state.role.write().await = None;
+        *self.auth_state.profile.write().await = None;
+        drop(token_guard);
+        // Cached responses belong to the previous server
+        self.invalidate_cache("");
+    }
+
+    fn url(&self, endpoint: &str) -> String {
+        join_url(&self.base_url(), endpoint)
+    }
+
+    // Auth header and full URL read together under the token lock
+    async fn authorized_url(&self, endpoint: &str) -> Result<(String, String), String> {
+        let token_guard = self.auth_state.token.read().await;
+        let auth_header = bearer_header(token_guard.as_deref())?;
+        Ok((auth_header, self.url(endpoint)))
     }
 
     // Internal method to handle all HTTP requests
@@ -113,30 +1078,157 @@ impl ApiClient {
         method: Method,
         endpoint: &str,
         body: Option<&T>,
-    ) -> Result<String, String> {
-        let auth_header = {
-            let auth_state = self.auth_state.lock().await;
-            get_auth_header_internal(&*auth_state).await?
+    ) -> Result<String, RequestFailure> {
+        self.send(method, endpoint, body, None, &[]).await
+    }
+
+    // Authenticated request tagged with a fresh correlation ID
+    async fn send<T: Serialize>(
+        &self,
+        method: Method,
+        endpoint: &str,
+        body: Option<&T>,
+        timeout: Option<Duration>,
+        extra_headers: &[(String, String)],
+    ) -> Result<String, RequestFailure> {
+        self.send_as(new_request_id(), method, endpoint, body, timeout, extra_headers).await
+    }
+
+    // Authenticated request tagged with the given correlation ID
+    async fn send_as<T: Serialize>(
+        &self,
+        request_id: String,
+        method: Method,
+        endpoint: &str,
+        body: Option<&T>,
+        timeout: Option<Duration>,
+        extra_headers: &[(String, String)],
+    ) -> Result<String, RequestFailure> {
+        self.with_session_retries(&request_id, &method, || {
+            self.send_once(&request_id, method.clone(), endpoint, body, timeout, extra_headers)
+        })
+        .await
+    }
+
+    // Run `attempt` with the handling every authenticated request gets.
+    // Idempotent requests answered with 429 are retried after the server's
+    // Retry-After, if it is short enough; others return the 429 to the caller.
+    // A 401 caused by an expired token triggers one token refresh and retry;
+    // if the refresh fails too, the session is expired. Other 401s are
+    // returned to the caller.
+    async fn with_session_retries<R, F, Fut>(
+        &self,
+        request_id: &str,
+        method: &Method,
+        mut attempt: F,
+    ) -> Result<R, RequestFailure>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<R, RequestFailure>>,
+    {
+        let idempotent = matches!(*method, Method::GET | Method::HEAD | Method::PUT | Method::DELETE);
+        let mut retries = 0;
+        let mut refreshed = false;
+        loop {
+            match attempt().await {
+                Err(failure)
+                    if failure.status == Some(reqwest::StatusCode::UNAUTHORIZED)
+                        && !refreshed
+                        && self.token_expired(&failure).await =>
+                {
+                    refreshed = true;
+                    match self.refresh_token().await {
+                        Ok(_) => info!("[{}] Token refreshed after 401, retrying", request_id),
+                        Err(e) => {
+                            warn!("[{}] Token rejected and refresh failed, ending session: {}", request_id, e);
+                            self.auth_state.expire_session().await;
+                            return Err(failure);
+                        }
+                    }
+                }
+                Err(RequestFailure { retry_after: Some(wait), .. })
+                    if idempotent && retries < RATE_LIMIT_MAX_RETRIES && wait <= RATE_LIMIT_MAX_WAIT =>
+                {
+                    retries += 1;
+                    warn!("[{}] Rate limited, retrying in {:?}", request_id, wait);
+                    tokio::time::sleep(wait).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    // Send an authenticated request tagged with `request_id`, after `build`
+    // has added the body and any headers of its own. The permit is returned
+    // so the request slot stays taken until the response has been read.
+    async fn send_built(
+        &self,
+        request_id: &str,
+        method: Method,
+        endpoint: &str,
+        build: impl FnOnce(RequestBuilder) -> RequestBuilder,
+    ) -> Result<(String, Instant, reqwest::Response, tokio::sync::SemaphorePermit<'_>), RequestFailure> {
+        let (auth_header, url) =
+            self.authorized_url(endpoint).await.map_err(|e| RequestFailure::local(request_id, e))?;
+        let permit = self.acquire_permit().await.map_err(|e| RequestFailure::local(request_id, e))?;
+
+        debug!("[{}] {} {}", request_id, method, url);
+        let started = Instant::now();
+
+        let request = self
+            .with_default_headers(self.client.request(method, &url), &[])
+            .header("Authorization", auth_header)
+            .header(REQUEST_ID_HEADER, request_id);
+        let response = build(request).send().await.map_err(|e| {
+            error!("[{}] Request failed: {}", request_id, e);
+            RequestFailure { transient: true, ..RequestFailure::local(request_id, format!("Request failed: {}", e)) }
+        })?;
+        Ok((url, started, response, permit))
+    }
+
+    async fn send_once<T: Serialize>(
+        &self,
+        request_id: &str,
+        method: Method,
+        endpoint: &str,
+        body: Option<&T>,
+        timeout: Option<Duration>,
+        extra_headers: &[(String, String)],
+    ) -> Result<String, RequestFailure> {
+        let fail = |message: String| RequestFailure {
+            request_id: request_id.to_string(),
+            message,
+            retry_after: None,
+            transient: false,
+            status: None,
         };
-        let url = format!("{}{}", self.config.api_base_url, endpoint);
-        
-        debug!("{} request to: {}", method, url);
-        
-        let mut request = self.client
-            .request(method, &url)
+        let (auth_header, url) = self.authorized_url(endpoint).await.map_err(fail)?;
+        let _permit = self.acquire_permit().await.map_err(fail)?;
+
+        debug!("[{}] {} {}", request_id, method, url);
+        let started = Instant::now();
+
+        let mut request = self
+            .with_default_headers(self.client.request(method.clone(), &url), extra_headers)
             .header("Authorization", auth_header)
-            .header("Content-Type", "application/json");
+            .header("Content-Type", "application/json")
+            .header(REQUEST_ID_HEADER, request_id);
 
         if let Some(body) = body {
+            self.log_request_body(request_id, &method, &url, body);
             request = request.json(body);
         }
 
+        if let Some(timeout) = timeout {
+            request = request.timeout(timeout);
+        }
+
         let response = request.send().await.map_err(|e| {
-            error!("Request failed: {}", e);
-            format!("Request failed: {}", e)
+            error!("[{}] Request failed: {}", request_id, e);
+            RequestFailure { transient: true, ..fail(format!("Request failed: {}", e)) }
         })?;
 
-        self.handle_response(response).await
+        self.handle_response(request_id, &method, &url, started, response).await
     }
 
     async fn request_no_auth<T: Serialize>(
@@ -144,40 +1236,566 @@ impl ApiClient {
         method: Method,
         endpoint: &str,
         body: Option<&T>,
-    ) -> Result<String, String> {
-        let url = format!("{}{}", self.config.api_base_url, endpoint);
-        debug!("{} request (no auth) to: {}", method, url);
+        timeout: Option<Duration>,
+    ) -> Result<String, RequestFailure> {
+        let request_id = new_request_id();
+        let fail = |message: String| RequestFailure {
+            request_id: request_id.clone(),
+            message,
+            retry_after: None,
+            transient: false,
+            status: None,
+        };
+        let url = self.url(endpoint);
+        let _permit = self.acquire_permit().await.map_err(fail)?;
+        debug!("[{}] {} (no auth) {}", request_id, method, url);
+        let started = Instant::now();
 
-        let mut request = self.client
-            .request(method, &url)
-            .header("Content-Type", "application/json");
+        let mut request = self
+            .with_default_headers(self.client.request(method.clone(), &url), &[])
+            .header("Content-Type", "application/json")
+            .header(REQUEST_ID_HEADER, &request_id);
 
         if let Some(body) = body {
+            self.log_request_body(&request_id, &method, &url, body);
             request = request.json(body);
         }
 
+        if let Some(timeout) = timeout {
+            request = request.timeout(timeout);
+        }
+
         let response = request.send().await.map_err(|e| {
-            error!("Request failed: {}", e);
-            format!("Request failed: {}", e)
+            error!("[{}] Request failed: {}", request_id, e);
+            fail(format!("Request failed: {}", e))
         })?;
 
-        self.handle_response(response).await
+        self.handle_response(&request_id, &method, &url, started, response).await
+    }
+
+    /// How many requests a bulk operation should keep in flight: `preferred`,
+    /// capped by the concurrency limit and by one second's worth of the rate
+    /// limit, so batches don't just queue behind the limiter.
+    pub fn batch_concurrency(&self, preferred: usize) -> usize {
+        let by_rate = self
+            .rate_limiter
+            .as_ref()
+            .map_or(usize::MAX, |limiter| limiter.requests_per_second().ceil() as usize);
+        preferred.min(self.config.max_concurrent_requests).min(by_rate).max(1)
+    }
+
+    // Wait for the rate limiter and a free request slot; the permit is held
+    // until the response is read
+    async fn acquire_permit(&self) -> Result<tokio::sync::SemaphorePermit<'_>, String> {
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire().await;
+        }
+        self.request_limit
+            .acquire()
+            .await
+            .map_err(|e| format!("Request limiter closed: {}", e))
+    }
+
+    // With verbose logging, record outgoing JSON bodies (redacted)
+    fn log_request_body<T: Serialize>(&self, request_id: &str, method: &Method, url: &str, body: &T) {
+        if self.config.verbose_logging {
+            if let Ok(body) = serde_json::to_string(body) {
+                info!("[{}] {} {} body: {}", request_id, method, url, redact_for_log(&body));
+            }
+        }
     }
 
     // Internal method to handle all responses consistently
-    async fn handle_response(&self, response: reqwest::Response) -> Result<String, String> {
+    async fn handle_response(
+        &self,
+        request_id: &str,
+        method: &Method,
+        url: &str,
+        started: Instant,
+        response: reqwest::Response,
+    ) -> Result<String, RequestFailure> {
+        let fail = |message: String| RequestFailure {
+            request_id: request_id.to_string(),
+            message,
+            retry_after: None,
+            transient: false,
+            status: None,
+        };
         let status = response.status();
+        if response.headers().contains_key(IDEMPOTENCY_KEY_HEADER) {
+            self.idempotency_supported.store(true, Ordering::Relaxed);
+        }
+        let retry_after = (status == reqwest::StatusCode::TOO_MANY_REQUESTS).then(|| {
+            response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(parse_retry_after)
+                .unwrap_or(RATE_LIMIT_DEFAULT_WAIT)
+        });
         let response_text = response.text().await.map_err(|e| {
-            error!("Failed to read response: {}", e);
-            format!("Failed to read response: {}", e)
+            error!("[{}] Failed to read response: {}", request_id, e);
+            fail(format!("Failed to read response: {}", e))
         })?;
 
+        if let Ok(mut metrics) = self.request_metrics.lock() {
+            metrics.record(endpoint_key(method.as_str(), url), started.elapsed());
+        }
+
+        if self.config.verbose_logging {
+            // Headers are never logged, so the bearer token stays out of the logs
+            info!(
+                "[{}] {} {} -> {} in {} ms, response: {}",
+                request_id,
+                method,
+                url,
+                status.as_u16(),
+                started.elapsed().as_millis(),
+                redact_for_log(&response_text)
+            );
+        }
+
         if status.is_success() {
-            debug!("Request successful");
+            debug!("[{}] Request successful", request_id);
             Ok(response_text)
         } else {
-            error!("Request failed. Status: {:?}, Response: {}", status, response_text);
-            Err(response_text)
+            error!(
+                "[{}] Request failed. Status: {:?}, Response: {}",
+                request_id,
+                status,
+                redact_for_log(&response_text)
+            );
+            let transient = matches!(status.as_u16(), 502..=504);
+            Err(RequestFailure { retry_after, transient, status: Some(status), ..fail(response_text) })
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{mock_server_with, temp_dir, test_client, MockResponse};
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::Arc;
+
+    fn failure(status: Option<reqwest::StatusCode>, retry_after: Option<Duration>) -> RequestFailure {
+        RequestFailure {
+            request_id: "req-1".to_string(),
+            message: "Not found".to_string(),
+            retry_after,
+            transient: false,
+            status,
+        }
+    }
+
+    #[test]
+    fn join_url_leaves_exactly_one_slash() {
+        for base in ["http://host/api", "http://host/api/"] {
+            for endpoint in ["users", "/users"] {
+                assert_eq!(join_url(base, endpoint), "http://host/api/users", "{base} + {endpoint}");
+            }
+        }
+        assert_eq!(join_url("http://host/api/", "/"), "http://host/api");
+    }
+
+    #[test]
+    fn error_string_includes_request_id() {
+        let message: String = failure(Some(reqwest::StatusCode::NOT_FOUND), None).into();
+        assert_eq!(message, "Not found (request ID: req-1)");
+
+        let message: String = failure(None, Some(Duration::from_secs(3))).into();
+        assert_eq!(message, "Rate limited by the server, retry in 3 seconds (request ID: req-1)");
+    }
+
+    // Self-signed CA generated for these tests only
+    const TEST_CA_PEM: &str = "-----BEGIN CERTIFICATE-----\n\
+MIIBnzCCAUWgAwIBAgIUOOP6gVZS6Iy2Iwv/0qN1Deuvnh8wCgYIKoZIzj0EAwIw\n\
+JDEiMCAGA1UEAwwZRWxldmF0aW9uIE1hbmFnZXIgVGVzdCBDQTAgFw0yNjEwMTYx\n\
+MTIzMjBaGA8yMTI2MDkyMjExMjMyMFowJDEiMCAGA1UEAwwZRWxldmF0aW9uIE1h\n\
+bmFnZXIgVGVzdCBDQTBZMBMGByqGSM49AgEGCCqGSM49AwEHA0IABIcMpG2e6MHE\n\
+guIHlpFJI00fvqdX3xCw6UoIcTz4HEBw15UzU3po+gUB461u0A54z0luqiOscwW5\n\
+AtykLoSfjVejUzBRMB0GA1UdDgQWBBSJzeIjKKQnU7t6qXZT+V3eqkF3nTAfBgNV\n\
+HSMEGDAWgBSJzeIjKKQnU7t6qXZT+V3eqkF3nTAPBgNVHRMBAf8EBTADAQH/MAoG\n\
+CCqGSM49BAMCA0gAMEUCIQDxBGF5JaR2dk8mtHwUH2o+edj9hdVkch4gnUsJsxfK\n\
+hwIgOlvSAVPPQ4tLKISobCtdzB1WpvzJaNe9cejRTJl0K68=\n\
+-----END CERTIFICATE-----\n";
+
+    fn config_with_ca(contents: &str) -> (AppConfig, std::path::PathBuf) {
+        let path = std::env::temp_dir().join(format!("elevation_manager_ca_{}.pem", uuid::Uuid::new_v4()));
+        std::fs::write(&path, contents).unwrap();
+        let config = AppConfig::builder()
+            .ca_cert_path(Some(path.to_string_lossy().into_owned()))
+            .build()
+            .unwrap();
+        (config, path)
+    }
+
+    #[test]
+    fn client_trusts_a_self_signed_ca() {
+        let (config, path) = config_with_ca(TEST_CA_PEM);
+        let result = build_http_client(&config);
+        std::fs::remove_file(path).unwrap();
+        assert!(result.is_ok(), "{:?}", result.err());
+    }
+
+    #[test]
+    fn unreadable_or_invalid_ca_fails_construction() {
+        let (config, path) = config_with_ca("not a certificate");
+        let result = build_http_client(&config);
+        std::fs::remove_file(&path).unwrap();
+        assert!(result.is_err_and(|e| e.starts_with("Invalid CA certificate")));
+
+        assert!(build_http_client(&config).is_err_and(|e| e.starts_with("Failed to read CA certificate")));
+    }
+
+    #[tokio::test]
+    async fn per_request_timeout_fires_before_the_configured_one() {
+        // Accepts connections but never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut connections = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                connections.push(stream);
+            }
+        });
+
+        let config = AppConfig::builder()
+            .api_base_url(format!("http://{}", address))
+            .api_timeout_seconds(30)
+            .build()
+            .unwrap();
+        let client = ApiClient::new(config, AuthState::default()).unwrap();
+        client.set_token("token".to_string()).await;
+
+        let started = Instant::now();
+        let result = client.get_with_timeout("/slow", Duration::from_millis(200)).await;
+        assert!(result.is_err());
+        let result = client
+            .post_with_timeout("/slow", &serde_json::json!({}), Duration::from_millis(200))
+            .await;
+        assert!(result.is_err());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn api_error_keeps_the_response_status() {
+        let error = ApiError::from(failure(Some(reqwest::StatusCode::NOT_FOUND), None));
+        assert_eq!(error.status(), Some(reqwest::StatusCode::NOT_FOUND));
+        assert_eq!(ApiError::from(failure(None, None)).status(), None);
+    }
+
+    #[tokio::test]
+    async fn idempotent_post_is_not_retried_until_the_key_is_echoed() {
+        let server = mock_server_with(|_| MockResponse::json(502, "{}")).await;
+        let client = test_client(&server.base_url).await;
+
+        assert!(client.post_idempotent("/products", &serde_json::json!({})).await.is_err());
+        assert_eq!(*server.requests.lock().unwrap(), ["POST /products"]);
+    }
+
+    #[tokio::test]
+    async fn idempotent_post_retries_with_the_same_key() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let counter = attempts.clone();
+        let server = mock_server_with(move |request| {
+            let key = request.header("Idempotency-Key").unwrap_or_default();
+            let status = if counter.fetch_add(1, Ordering::SeqCst) == 0 { 502 } else { 201 };
+            MockResponse::json(status, r#"{"data":1}"#).header("Idempotency-Key", key)
+        })
+        .await;
+        let client = test_client(&server.base_url).await;
+
+        let body = client.post_idempotent("/products", &serde_json::json!({})).await.unwrap();
+        assert_eq!(body, r#"{"data":1}"#);
+        let received = server.received.lock().unwrap();
+        let keys: Vec<_> = received.iter().map(|request| request.header("Idempotency-Key")).collect();
+        assert_eq!(keys.len(), 2);
+        assert!(keys[0].is_some());
+        assert_eq!(keys[0], keys[1]);
+    }
+
+    #[tokio::test]
+    async fn default_headers_reach_authed_no_auth_and_background_requests() {
+        let server = mock_server_with(|_| MockResponse::json(200, "{}")).await;
+        let client = test_client(&server.base_url).await;
+        let background = client.background_client().unwrap();
+        // Set after the background client exists; it must still see it
+        client.set_default_header("X-Tenant-Id", Some("north".to_string())).unwrap();
+
+        client.get("/teams").await.unwrap();
+        client.post_no_auth("/auth/login", &serde_json::json!({})).await.unwrap();
+        background.get("/notifications/count").await.unwrap();
+
+        let received = server.received.lock().unwrap();
+        assert_eq!(received.len(), 3);
+        for request in received.iter() {
+            assert_eq!(request.header("X-Tenant-Id"), Some("north"), "{}", request.path);
         }
+        assert!(received[0].header("Authorization").is_some());
+        assert!(received[1].header("Authorization").is_none());
+    }
+
+    #[tokio::test]
+    async fn one_off_header_is_sent_alongside_the_default_headers() {
+        let server = mock_server_with(|_| MockResponse::json(200, "{}")).await;
+        let client = test_client(&server.base_url).await;
+        client.set_default_header("X-Tenant-Id", Some("north".to_string())).unwrap();
+
+        client.with_header("X-Api-Key", "k-123").get("/reports").await.unwrap();
+        client.get("/reports/1").await.unwrap();
+
+        let received = server.received.lock().unwrap();
+        assert_eq!(received[0].header("X-Api-Key"), Some("k-123"));
+        assert_eq!(received[0].header("X-Tenant-Id"), Some("north"));
+        assert!(received[0].header("Authorization").is_some());
+        // One-off headers don't stick to later requests
+        assert_eq!(received[1].header("X-Api-Key"), None);
+        assert!(client.with_header("Authorization", "Bearer x").get("/reports").await.is_err());
+    }
+
+    #[test]
+    fn background_client_shares_the_rate_limit() {
+        let config = AppConfig::builder().requests_per_second(Some(5.0)).build().unwrap();
+        let client = ApiClient::new(config, AuthState::default()).unwrap();
+        let background = client.background_client().unwrap();
+        assert!(Arc::ptr_eq(
+            client.rate_limiter.as_ref().unwrap(),
+            background.rate_limiter.as_ref().unwrap()
+        ));
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn health_check_measures_latency_of_the_health_endpoint() {
+        let server = mock_server_with(|_| {
+            MockResponse::json(200, r#"{"version":"1.4.0"}"#).delay(Duration::from_millis(20))
+        })
+        .await;
+        let health = test_client(&server.base_url).await.check_api_health().await;
+
+        assert!(health.reachable);
+        assert!(health.latency_ms >= 20, "latency {}ms", health.latency_ms);
+        assert_eq!(health.version.as_deref(), Some("1.4.0"));
+        assert_eq!(*server.requests.lock().unwrap(), ["GET /health"]);
+    }
+
+    #[tokio::test]
+    async fn health_check_falls_back_to_head_without_a_health_endpoint() {
+        let server = mock_server_with(|request| match request.method.as_str() {
+            "HEAD" => MockResponse::json(200, ""),
+            _ => MockResponse::json(404, "{}"),
+        })
+        .await;
+        let health = test_client(&server.base_url).await.check_api_health().await;
+
+        assert!(health.reachable);
+        assert_eq!(health.version, None);
+        assert_eq!(*server.requests.lock().unwrap(), ["GET /health", "HEAD /"]);
+    }
+
+    #[tokio::test]
+    async fn error_quotes_the_request_id_that_was_sent() {
+        let server = mock_server_with(|_| MockResponse::json(404, r#"{"message":"Not found"}"#)).await;
+        let client = test_client(&server.base_url).await;
+
+        let error = client.get("/products/7").await.unwrap_err();
+
+        let received = server.received.lock().unwrap();
+        let request_id = received[0].header(REQUEST_ID_HEADER).expect("request ID header");
+        assert!(!request_id.is_empty());
+        assert!(error.contains(&format!("(request ID: {})", request_id)), "{error}");
+    }
+
+    #[tokio::test]
+    async fn requests_are_recorded_in_the_metrics() {
+        let server = mock_server_with(|_| MockResponse::json(200, r#"{"data":[]}"#)).await;
+        let client = test_client(&server.base_url).await;
+
+        for endpoint in ["/products/1", "/products/2", "/products/3", "/users"] {
+            client.get(endpoint).await.unwrap();
+        }
+
+        let metrics = client.api_metrics();
+        let count = |endpoint: &str| metrics.iter().find(|m| m.endpoint == endpoint).map(|m| m.count);
+        assert_eq!(count("GET /products/:id"), Some(3));
+        assert_eq!(count("GET /users"), Some(1));
+        assert_eq!(metrics.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn cancelled_request_returns_promptly() {
+        let server = mock_server_with(|_| MockResponse::json(200, "{}").delay(Duration::from_secs(10))).await;
+        let client = test_client(&server.base_url).await;
+
+        let started = Instant::now();
+        let (result, cancelled) = tokio::join!(client.get_cancellable("/exports", "export-1"), async {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            client.cancel_request("export-1")
+        });
+
+        assert!(cancelled);
+        assert!(matches!(result, Err(ApiError::Cancelled { request_id }) if request_id == "export-1"));
+        assert!(started.elapsed() < Duration::from_secs(2));
+        // Finished requests can no longer be cancelled
+        assert!(!client.cancel_request("export-1"));
+
+        let started = Instant::now();
+        let body = serde_json::json!({ "format": "geotiff" });
+        let (result, cancelled) = tokio::join!(client.post_cancellable("/exports", &body, "export-2"), async {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            client.cancel_request("export-2")
+        });
+
+        assert!(cancelled);
+        assert!(matches!(result, Err(ApiError::Cancelled { request_id }) if request_id == "export-2"));
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(*server.requests.lock().unwrap(), ["GET /exports", "POST /exports"]);
+    }
+
+    #[tokio::test]
+    async fn multipart_upload_is_resent_after_a_server_error() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let counter = attempts.clone();
+        let server = mock_server_with(move |_| match counter.fetch_add(1, Ordering::SeqCst) {
+            0 => MockResponse::json(503, r#"{"message":"Unavailable"}"#),
+            _ => MockResponse::json(201, r#"{"data":{"id":5}}"#),
+        })
+        .await;
+        let client = test_client(&server.base_url).await;
+        let dir = temp_dir();
+        let file = dir.join("tile.tif");
+        std::fs::write(&file, "tile bytes").unwrap();
+
+        let result = client.post_multipart_file("/reviews/5/images", "file", &file, &[("caption", "North")]).await;
+
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(result.unwrap(), r#"{"data":{"id":5}}"#);
+        let received = server.received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        for request in received.iter() {
+            assert!(request.body.contains("tile bytes"));
+            assert!(request.body.contains("North"));
+        }
+    }
+
+    #[test]
+    fn retry_after_accepts_seconds_and_http_dates() {
+        assert_eq!(parse_retry_after(" 5 "), Some(Duration::from_secs(5)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), Some(Duration::ZERO));
+        let soon = (chrono::Utc::now() + chrono::Duration::seconds(60)).to_rfc2822();
+        assert!(parse_retry_after(&soon).is_some_and(|wait| wait > Duration::from_secs(50)));
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    #[tokio::test]
+    async fn rate_limited_get_is_retried_after_the_requested_wait() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let counter = attempts.clone();
+        let server = mock_server_with(move |_| match counter.fetch_add(1, Ordering::SeqCst) {
+            0 => MockResponse::json(429, r#"{"message":"Slow down"}"#).header("Retry-After", "0"),
+            _ => MockResponse::json(200, r#"{"data":[]}"#),
+        })
+        .await;
+        let client = test_client(&server.base_url).await;
+
+        assert_eq!(client.get("/products").await.unwrap(), r#"{"data":[]}"#);
+        assert_eq!(*server.requests.lock().unwrap(), ["GET /products", "GET /products"]);
+    }
+
+    #[tokio::test]
+    async fn rate_limited_post_is_reported_not_retried() {
+        let server = mock_server_with(|_| {
+            MockResponse::json(429, r#"{"message":"Slow down"}"#).header("Retry-After", "2")
+        })
+        .await;
+        let client = test_client(&server.base_url).await;
+
+        let error = client.post_json::<_, serde_json::Value>("/reviews", &serde_json::json!({})).await.unwrap_err();
+
+        assert!(matches!(error, ApiError::RateLimited { retry_after_secs: 2, .. }), "{error}");
+        assert_eq!(error.status(), Some(reqwest::StatusCode::TOO_MANY_REQUESTS));
+        assert_eq!(server.requests.lock().unwrap().len(), 1);
+    }
+
+    // Rejects the initial "token" as expired and hands out "fresh" on refresh
+    async fn expiring_token_server() -> crate::test_support::MockServer {
+        mock_server_with(|request| match (request.path.as_str(), request.header("Authorization")) {
+            ("/auth/refresh", _) => MockResponse::json(200, r#"{"data":{"token":"fresh"}}"#),
+            (_, Some("Bearer fresh")) => MockResponse::json(201, r#"{"data":["tile.tif"]}"#),
+            _ => MockResponse::json(401, r#"{"message":"Token expired"}"#),
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn uploads_refresh_an_expired_token_and_resend() {
+        let server = expiring_token_server().await;
+        let client = test_client(&server.base_url).await;
+        client.post_bytes("/reviews/5/images/chunk", b"chunk".to_vec()).await.unwrap();
+
+        let client = test_client(&server.base_url).await;
+        let dir = temp_dir();
+        let file = dir.join("tile.tif");
+        std::fs::write(&file, "tile bytes").unwrap();
+        let result = client.post_multipart_file("/reviews/5/images", "file", &file, &[]).await;
+        let _ = std::fs::remove_dir_all(&dir);
+        result.unwrap();
+
+        let received = server.received.lock().unwrap();
+        let paths: Vec<_> = received.iter().map(|request| request.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "/reviews/5/images/chunk",
+                "/auth/refresh",
+                "/reviews/5/images/chunk",
+                "/reviews/5/images",
+                "/auth/refresh",
+                "/reviews/5/images"
+            ]
+        );
+        // The resent bodies are complete
+        assert_eq!(received[2].body, "chunk");
+        assert!(received[5].body.contains("tile bytes"));
+    }
+
+    #[tokio::test]
+    async fn downloads_refresh_an_expired_token_and_honour_retry_after() {
+        let server = expiring_token_server().await;
+        let client = test_client(&server.base_url).await;
+        let download = client.get_bytes_if_changed("/reviews/5/image/tile.tif", None, None).await.unwrap();
+        assert_eq!(download.unwrap().bytes, br#"{"data":["tile.tif"]}"#);
+        assert_eq!(
+            *server.requests.lock().unwrap(),
+            ["GET /reviews/5/image/tile.tif", "POST /auth/refresh", "GET /reviews/5/image/tile.tif"]
+        );
+
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let counter = attempts.clone();
+        let server = mock_server_with(move |_| match counter.fetch_add(1, Ordering::SeqCst) {
+            0 => MockResponse::json(429, r#"{"message":"Slow down"}"#).header("Retry-After", "0"),
+            _ => MockResponse::json(200, "{\"id\":1}\n{\"id\":2}\n").header("Content-Type", "application/x-ndjson"),
+        })
+        .await;
+        let client = test_client(&server.base_url).await;
+        let mut items = Vec::new();
+        let count = client.get_streamed("/taskorders", |item| items.push(item)).await.unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(items, [serde_json::json!({"id": 1}), serde_json::json!({"id": 2})]);
+        assert_eq!(*server.requests.lock().unwrap(), ["GET /taskorders", "GET /taskorders"]);
+    }
+
+    #[tokio::test]
+    async fn multipart_upload_is_not_resent_after_a_client_error() {
+        let server = mock_server_with(|_| MockResponse::json(400, r#"{"message":"Bad file"}"#)).await;
+        let client = test_client(&server.base_url).await;
+        let dir = temp_dir();
+        let file = dir.join("tile.tif");
+        std::fs::write(&file, "tile bytes").unwrap();
+
+        let result = client.post_multipart_file("/reviews/5/images", "file", &file, &[]).await;
+
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(result.unwrap_err().starts_with("Upload failed after 1 attempt(s)"));
+        assert_eq!(server.requests.lock().unwrap().len(), 1);
+    }
+}
@@ -1,22 +1,316 @@
+use log::warn;
+use serde::Deserialize;
+use std::collections::HashMap;
 use std::env;
+use std::fmt;
+use std::str::FromStr;
 
-use crate::services::api_client;
 
+/// Settings are read from environment variables first, then from
+/// `~/.elevation-manager/config.toml` (keys named like the fields below),
+/// then built-in defaults.
 #[derive(Debug, Clone)]
 pub struct AppConfig {
     pub api_base_url: String,
     pub api_timeout_seconds: u64,
+    /// Maximum number of HTTP requests `ApiClient` keeps in flight at once,
+    /// from `MAX_CONCURRENT_REQUESTS`. Defaults to 8.
+    pub max_concurrent_requests: usize,
+    /// Average cap on outgoing requests per second, from `REQUESTS_PER_SECOND`.
+    /// `None` means unlimited.
+    pub requests_per_second: Option<f64>,
+    /// Log every request's method, URL, status and timing (bodies redacted),
+    /// from `VERBOSE_LOGGING`.
+    pub verbose_logging: bool,
+    /// Extra tags allowed in review HTML, from comma-separated `REVIEW_HTML_EXTRA_TAGS`.
+    pub review_html_extra_tags: Vec<String>,
+    /// Headers added to every API request, from `DEFAULT_HEADERS` as
+    /// semicolon-separated `Name=value` pairs.
+    pub default_headers: HashMap<String, String>,
+    /// Proxy for API traffic, from `HTTPS_PROXY` (or `https_proxy`).
+    pub https_proxy: Option<String>,
+    /// Comma-separated hosts that bypass the proxy, from `NO_PROXY` (or `no_proxy`).
+    pub no_proxy: Option<String>,
+    /// Extra root certificate (PEM or DER) to trust, from `CA_CERT_PATH`.
+    pub ca_cert_path: Option<String>,
+}
+
+const CONFIG_FILE: &str = "config.toml";
+
+/// Optional overrides from `config.toml`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct FileConfig {
+    api_base_url: Option<String>,
+    api_timeout_seconds: Option<u64>,
+    max_concurrent_requests: Option<usize>,
+    requests_per_second: Option<f64>,
+    verbose_logging: Option<bool>,
+    review_html_extra_tags: Option<Vec<String>>,
+    default_headers: Option<HashMap<String, String>>,
+    https_proxy: Option<String>,
+    no_proxy: Option<String>,
+    ca_cert_path: Option<String>,
+}
+
+// Read `config.toml`; a missing file is normal, a malformed one is ignored
+// with a warning
+fn load_file_config() -> FileConfig {
+    let Some(path) = dirs::home_dir().map(|home| home.join(".elevation-manager").join(CONFIG_FILE)) else {
+        return FileConfig::default();
+    };
+    let Ok(text) = std::fs::read_to_string(&path) else {
+        return FileConfig::default();
+    };
+    toml::from_str(&text).unwrap_or_else(|e| {
+        warn!("Ignoring malformed config file {}: {}", path.display(), e);
+        FileConfig::default()
+    })
+}
+
+// First non-empty value among the given environment variables
+fn env_any(names: &[&str]) -> Option<String> {
+    names
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .map(|value| value.trim().to_string())
+        .find(|value| !value.is_empty())
+}
+
+const DEFAULT_API_BASE_URL: &str = "http://localhost:3000";
+const DEFAULT_API_TIMEOUT_SECONDS: u64 = 30;
+const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 8;
+
+/// Check that `url` has an http(s) scheme and a host, and strip trailing
+/// slashes so joining it with an endpoint never produces `//`.
+pub fn normalize_base_url(url: &str) -> Result<String, String> {
+    let trimmed = url.trim().trim_end_matches('/');
+    let parsed = reqwest::Url::parse(trimmed).map_err(|e| format!("Invalid API base URL '{}': {}", url, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        return Err(format!("API base URL '{}' must be an http(s) URL with a host", url));
+    }
+    Ok(trimmed.to_string())
+}
+
+fn validate_timeout(seconds: u64) -> Result<u64, String> {
+    if seconds == 0 {
+        return Err("API timeout must be at least 1 second".to_string());
+    }
+    Ok(seconds)
+}
+
+fn validate_max_concurrent_requests(max: usize) -> Result<usize, String> {
+    if max == 0 {
+        return Err("Maximum concurrent requests must be at least 1".to_string());
+    }
+    Ok(max)
+}
+
+fn validate_requests_per_second(rate: f64) -> Result<f64, String> {
+    if !rate.is_finite() || rate <= 0.0 {
+        return Err("Requests per second must be a positive number".to_string());
+    }
+    Ok(rate)
+}
+
+// Parse an environment variable, warning about (rather than silently
+// dropping) a value that doesn't parse
+fn env_parsed<T: FromStr>(name: &str) -> Option<T>
+where
+    T::Err: fmt::Display,
+{
+    let raw = env_any(&[name])?;
+    raw.parse()
+        .map_err(|e| warn!("Ignoring invalid {}={:?}: {}", name, raw, e))
+        .ok()
+}
+
+// The environment value if valid, else the config file value if valid.
+// Invalid values are logged and skipped.
+fn first_valid<T>(
+    name: &str,
+    env_value: Option<T>,
+    file_value: Option<T>,
+    validate: fn(T) -> Result<T, String>,
+) -> Option<T> {
+    [(env_value, "environment"), (file_value, CONFIG_FILE)]
+        .into_iter()
+        .filter_map(|(value, source)| value.map(|v| (v, source)))
+        .find_map(|(value, source)| {
+            validate(value)
+                .map_err(|e| warn!("Ignoring {} from {}: {}", name, source, e))
+                .ok()
+        })
+}
+
+/// Builds an `AppConfig`, validating values in `build()`. Unset fields take
+/// the built-in defaults.
+#[derive(Debug, Default, Clone)]
+pub struct AppConfigBuilder {
+    api_base_url: Option<String>,
+    api_timeout_seconds: Option<u64>,
+    max_concurrent_requests: Option<usize>,
+    requests_per_second: Option<f64>,
+    verbose_logging: bool,
+    review_html_extra_tags: Vec<String>,
+    default_headers: HashMap<String, String>,
+    https_proxy: Option<String>,
+    no_proxy: Option<String>,
+    ca_cert_path: Option<String>,
+}
+
+impl AppConfigBuilder {
+    pub fn api_base_url(mut self, url: impl Into<String>) -> Self {
+        self.api_base_url = Some(url.into());
+        self
+    }
+
+    pub fn api_timeout_seconds(mut self, seconds: u64) -> Self {
+        self.api_timeout_seconds = Some(seconds);
+        self
+    }
+
+    pub fn max_concurrent_requests(mut self, max: usize) -> Self {
+        self.max_concurrent_requests = Some(max);
+        self
+    }
+
+    pub fn requests_per_second(mut self, rate: Option<f64>) -> Self {
+        self.requests_per_second = rate;
+        self
+    }
+
+    pub fn verbose_logging(mut self, enabled: bool) -> Self {
+        self.verbose_logging = enabled;
+        self
+    }
+
+    pub fn review_html_extra_tags(mut self, tags: Vec<String>) -> Self {
+        self.review_html_extra_tags = tags;
+        self
+    }
+
+    pub fn default_headers(mut self, headers: HashMap<String, String>) -> Self {
+        self.default_headers = headers;
+        self
+    }
+
+    pub fn https_proxy(mut self, proxy: Option<String>) -> Self {
+        self.https_proxy = proxy;
+        self
+    }
+
+    pub fn no_proxy(mut self, hosts: Option<String>) -> Self {
+        self.no_proxy = hosts;
+        self
+    }
+
+    pub fn ca_cert_path(mut self, path: Option<String>) -> Self {
+        self.ca_cert_path = path;
+        self
+    }
+
+    pub fn build(self) -> Result<AppConfig, String> {
+        let api_base_url = normalize_base_url(self.api_base_url.as_deref().unwrap_or(DEFAULT_API_BASE_URL))?;
+        let api_timeout_seconds = validate_timeout(self.api_timeout_seconds.unwrap_or(DEFAULT_API_TIMEOUT_SECONDS))?;
+        let max_concurrent_requests = validate_max_concurrent_requests(
+            self.max_concurrent_requests.unwrap_or(DEFAULT_MAX_CONCURRENT_REQUESTS),
+        )?;
+        let requests_per_second = self.requests_per_second.map(validate_requests_per_second).transpose()?;
+        Ok(AppConfig {
+            api_base_url,
+            api_timeout_seconds,
+            max_concurrent_requests,
+            requests_per_second,
+            verbose_logging: self.verbose_logging,
+            review_html_extra_tags: self
+                .review_html_extra_tags
+                .into_iter()
+                .map(|t| t.trim().to_ascii_lowercase())
+                .filter(|t| !t.is_empty())
+                .collect(),
+            default_headers: self.default_headers,
+            https_proxy: self.https_proxy,
+            no_proxy: self.no_proxy,
+            ca_cert_path: self.ca_cert_path,
+        })
+    }
 }
 
 impl AppConfig {
+    pub fn builder() -> AppConfigBuilder {
+        AppConfigBuilder::default()
+    }
+
+    /// Configuration from the environment and `config.toml`. Invalid values
+    /// are logged and replaced by the next source or the built-in default.
     pub fn new() -> Self {
-        Self {
-            api_base_url: env::var("API_BASE_URL")
-                .unwrap_or_else(|_| "http://localhost:3000".to_string()),
-            api_timeout_seconds: env::var("API_TIMEOUT_SECONDS")
-                .unwrap_or_else(|_| "30".to_string())
-                .parse()
-                .unwrap_or(30),
+        let file = load_file_config();
+        let mut builder = Self::builder()
+            .verbose_logging(
+                env::var("VERBOSE_LOGGING")
+                    .ok()
+                    .map(|v| matches!(v.to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+                    .or(file.verbose_logging)
+                    .unwrap_or(false),
+            )
+            .review_html_extra_tags(
+                env::var("REVIEW_HTML_EXTRA_TAGS")
+                    .ok()
+                    .map(|tags| tags.split(',').map(String::from).collect())
+                    .or(file.review_html_extra_tags)
+                    .unwrap_or_default(),
+            )
+            .default_headers(
+                env::var("DEFAULT_HEADERS")
+                    .ok()
+                    .map(|headers| {
+                        headers
+                            .split(';')
+                            .filter_map(|pair| pair.split_once('='))
+                            .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+                            .filter(|(name, _)| !name.is_empty())
+                            .collect()
+                    })
+                    .or(file.default_headers)
+                    .unwrap_or_default(),
+            )
+            .https_proxy(env_any(&["HTTPS_PROXY", "https_proxy"]).or(file.https_proxy))
+            .no_proxy(env_any(&["NO_PROXY", "no_proxy"]).or(file.no_proxy))
+            .ca_cert_path(env_any(&["CA_CERT_PATH"]).or(file.ca_cert_path));
+
+        if let Some(url) = first_valid(
+            "API_BASE_URL",
+            env_any(&["API_BASE_URL"]),
+            file.api_base_url,
+            |url| normalize_base_url(&url),
+        ) {
+            builder = builder.api_base_url(url);
         }
+        if let Some(seconds) = first_valid(
+            "API_TIMEOUT_SECONDS",
+            env_parsed("API_TIMEOUT_SECONDS"),
+            file.api_timeout_seconds,
+            validate_timeout,
+        ) {
+            builder = builder.api_timeout_seconds(seconds);
+        }
+        if let Some(max) = first_valid(
+            "MAX_CONCURRENT_REQUESTS",
+            env_parsed("MAX_CONCURRENT_REQUESTS"),
+            file.max_concurrent_requests,
+            validate_max_concurrent_requests,
+        ) {
+            builder = builder.max_concurrent_requests(max);
+        }
+        builder = builder.requests_per_second(first_valid(
+            "REQUESTS_PER_SECOND",
+            env_parsed("REQUESTS_PER_SECOND"),
+            file.requests_per_second,
+            validate_requests_per_second,
+        ));
+
+        // Every value that can fail validation was checked above
+        builder.build().expect("validated configuration")
     }
 }
@@ -0,0 +1,105 @@
+use ammonia::Builder;
+use std::borrow::Cow;
+
+/// Tags allowed in review HTML on top of ammonia's default formatting, table,
+/// list, link and image tags.
+const REVIEW_TAGS: &[&str] = &["mark", "figure", "figcaption"];
+
+/// Strip scripts, iframes and inline event handlers from review HTML while
+/// keeping formatting, tables, links and embedded (base64) images.
+///
+/// `extra_tags` extends the allowlist, see `AppConfig::review_html_extra_tags`.
+pub fn sanitize_review_html(html: &str, extra_tags: &[String]) -> String {
+    sanitize_review_html_inlining_images(html, extra_tags, |_| None)
+}
+
+/// Like `sanitize_review_html`, but each `<img src>` is first passed to
+/// `resolve_image`; a returned `data:image/...` URI replaces the source. Local
+/// `file:` sources that aren't resolved are dropped.
+pub fn sanitize_review_html_inlining_images<F>(html: &str, extra_tags: &[String], resolve_image: F) -> String
+where
+    F: Fn(&str) -> Option<String> + Send + Sync + 'static,
+{
+    let mut builder = Builder::default();
+    builder
+        .add_tags(REVIEW_TAGS)
+        // ammonia panics if a tag is both allowed and content-stripped
+        .add_tags(
+            extra_tags
+                .iter()
+                .map(String::as_str)
+                .filter(|tag| !matches!(*tag, "script" | "style")),
+        )
+        .add_tag_attributes("img", &["style", "data-align"])
+        .add_tag_attributes("p", &["style"])
+        .add_tag_attributes("span", &["style"])
+        .add_tag_attributes("td", &["style", "colspan", "rowspan"])
+        .add_tag_attributes("th", &["style", "colspan", "rowspan"])
+        // file: only survives long enough to be resolved, see below
+        .add_url_schemes(&["data", "file"])
+        .attribute_filter(move |element, attribute, value| {
+            let is_image_src = element == "img" && attribute == "src";
+            if is_image_src {
+                if let Some(data_uri) = resolve_image(value) {
+                    return Some(Cow::Owned(data_uri));
+                }
+            }
+            let lower = value.trim_start().to_ascii_lowercase();
+            if lower.starts_with("file:") {
+                return None;
+            }
+            // data: URLs are only acceptable as inline image sources
+            if lower.starts_with("data:") && !(is_image_src && lower.starts_with("data:image/")) {
+                return None;
+            }
+            Some(Cow::Borrowed(value))
+        });
+    builder.clean(html).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripts_and_event_handlers_are_removed() {
+        let html = r#"<p onclick="steal()">Hi</p><script>alert(1)</script><iframe src="https://x"></iframe>"#;
+        let clean = sanitize_review_html(html, &[]);
+        assert_eq!(clean, "<p>Hi</p>");
+    }
+
+    #[test]
+    fn images_and_tables_survive() {
+        let html = concat!(
+            r#"<img src="data:image/png;base64,iVBORw0KGgo=" alt="tile">"#,
+            "<table><tbody><tr><td>1</td></tr></tbody></table>",
+        );
+        let clean = sanitize_review_html(html, &[]);
+        assert!(clean.contains(r#"<img src="data:image/png;base64,iVBORw0KGgo=" alt="tile">"#), "{clean}");
+        assert!(clean.contains("<table><tbody><tr><td>1</td></tr></tbody></table>"), "{clean}");
+    }
+
+    #[test]
+    fn extra_tags_extend_the_allowlist() {
+        let html = "<dialog>More</dialog>";
+        assert_eq!(sanitize_review_html(html, &[]), "More");
+        assert_eq!(sanitize_review_html(html, &["dialog".to_string()]), html);
+    }
+
+    #[test]
+    fn resolved_images_are_inlined_and_unresolved_local_ones_dropped() {
+        let html = concat!(
+            r#"<img src="file:///reviews/north.png">"#,
+            r#"<img src="file:///reviews/missing.png">"#,
+            r#"<img src="https://example.com/south.png">"#,
+            r#"<a href="data:text/html,hi">link</a>"#,
+        );
+        let clean = sanitize_review_html_inlining_images(html, &[], |src| {
+            src.ends_with("north.png").then(|| "data:image/png;base64,AAAA".to_string())
+        });
+        assert!(clean.contains(r#"<img src="data:image/png;base64,AAAA">"#), "{clean}");
+        assert!(clean.contains("<img>"), "{clean}");
+        assert!(clean.contains(r#"<img src="https://example.com/south.png">"#), "{clean}");
+        assert!(!clean.contains("file:") && !clean.contains("data:text"), "{clean}");
+    }
+}
@@ -0,0 +1,114 @@
+use serde_json::Value;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Lowercase form of the scheme prefix, matched case-insensitively
+const BEARER_PREFIX: &str = "bearer ";
+
+/// Logged bodies are cut off after this many characters.
+const MAX_LOGGED_BODY_CHARS: usize = 2048;
+
+/// Prepare a request or response body for logging: bearer tokens and any
+/// `password`/`token` JSON fields are masked and the result is size-capped.
+pub fn redact_for_log(body: &str) -> String {
+    let redacted = match serde_json::from_str::<Value>(body) {
+        Ok(mut json) => {
+            redact_value(&mut json);
+            json.to_string()
+        }
+        Err(_) => redact_bearer(body),
+    };
+    truncate(redacted)
+}
+
+fn is_sensitive_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    key.contains("password") || key.contains("token") || key == "authorization"
+}
+
+fn redact_value(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                if is_sensitive_key(key) {
+                    *field = Value::String(REDACTED.to_string());
+                } else {
+                    redact_value(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_value),
+        Value::String(text) if text.to_ascii_lowercase().contains(BEARER_PREFIX) => *text = redact_bearer(text),
+        _ => {}
+    }
+}
+
+/// Mask whatever follows each `Bearer ` (in any case) up to the next
+/// whitespace or quote.
+fn redact_bearer(text: &str) -> String {
+    // ASCII lowercasing keeps byte offsets, so matches index into `text` too
+    let lowercase = text.to_ascii_lowercase();
+    let mut output = String::with_capacity(text.len());
+    let mut position = 0;
+    while let Some(index) = lowercase[position..].find(BEARER_PREFIX) {
+        let token_start = position + index + BEARER_PREFIX.len();
+        output.push_str(&text[position..token_start]);
+        output.push_str(REDACTED);
+        let after = &text[token_start..];
+        let token_end = after
+            .find(|c: char| c.is_whitespace() || c == '"' || c == '\'')
+            .unwrap_or(after.len());
+        position = token_start + token_end;
+    }
+    output.push_str(&text[position..]);
+    output
+}
+
+fn truncate(text: String) -> String {
+    match text.char_indices().nth(MAX_LOGGED_BODY_CHARS) {
+        Some((cut, _)) => format!("{}... [{} more bytes]", &text[..cut], text.len() - cut),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_password_and_token_fields_are_masked() {
+        let body = r#"{"user":{"name":"ana","password":"hunter2","sessions":[{"refresh_token":"abc","id":1}]}}"#;
+        let logged: Value = serde_json::from_str(&redact_for_log(body)).unwrap();
+        assert_eq!(logged["user"]["name"], "ana");
+        assert_eq!(logged["user"]["password"], REDACTED);
+        assert_eq!(logged["user"]["sessions"][0]["refresh_token"], REDACTED);
+        assert_eq!(logged["user"]["sessions"][0]["id"], 1);
+    }
+
+    #[test]
+    fn bearer_tokens_inside_json_strings_are_masked() {
+        let body = r#"{"note":"sent bearer abc.def with it","items":["BEARER xyz"]}"#;
+        let logged: Value = serde_json::from_str(&redact_for_log(body)).unwrap();
+        assert_eq!(logged["note"], "sent bearer [REDACTED] with it");
+        assert_eq!(logged["items"][0], "BEARER [REDACTED]");
+    }
+
+    #[test]
+    fn bearer_tokens_in_non_json_bodies_are_masked_in_any_case() {
+        let body = "Authorization: Bearer abc\nretry with bearer def, or BEARER ghi";
+        assert_eq!(
+            redact_for_log(body),
+            "Authorization: Bearer [REDACTED]\nretry with bearer [REDACTED] or BEARER [REDACTED]"
+        );
+        assert_eq!(redact_for_log("plain text, no secrets"), "plain text, no secrets");
+    }
+
+    #[test]
+    fn long_bodies_are_cut_on_a_character_boundary() {
+        let exact = "é".repeat(MAX_LOGGED_BODY_CHARS);
+        assert_eq!(redact_for_log(&exact), exact);
+
+        let logged = redact_for_log(&"é".repeat(MAX_LOGGED_BODY_CHARS + 3));
+        assert_eq!(logged, format!("{}... [6 more bytes]", exact));
+    }
+}
@@ -1,2 +1,11 @@
 pub mod api_client;
-pub mod config;
\ No newline at end of file
+pub mod config;
+pub mod html_sanitizer;
+pub mod log_redaction;
+pub mod notification_snooze;
+pub mod profiles;
+pub mod rate_limiter;
+pub mod request_metrics;
+pub mod response_cache;
+pub mod s2_cell;
+pub mod shown_notifications;
@@ -0,0 +1,99 @@
+// src-tauri/src/services/notification_snooze.rs
+//
+// Local notification snoozes. Snoozed notifications are hidden from the
+// `notifications` event until their snooze time passes; nothing is changed
+// on the server.
+
+use crate::utils::write_atomic;
+use chrono::{DateTime, Utc};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+const SNOOZE_FILE: &str = "snoozed.json";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SnoozeEntry {
+    notification_id: i32,
+    until: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SnoozeFile {
+    snoozed: Vec<SnoozeEntry>,
+}
+
+fn snooze_path() -> Result<PathBuf, String> {
+    let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
+    Ok(home_dir.join(".elevation-manager").join(SNOOZE_FILE))
+}
+
+fn read_snoozes() -> Result<SnoozeFile, String> {
+    let path = snooze_path()?;
+    if !path.exists() {
+        return Ok(SnoozeFile::default());
+    }
+    let text = fs::read_to_string(&path).map_err(|e| format!("Failed to read snoozes: {}", e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Failed to parse snoozes: {}", e))
+}
+
+fn write_snoozes(file: &SnoozeFile) -> Result<(), String> {
+    let path = snooze_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create snooze directory: {}", e))?;
+    }
+    let text = serde_json::to_string_pretty(file).map_err(|e| format!("Failed to serialize snoozes: {}", e))?;
+    write_atomic(&path, text).map_err(|e| format!("Failed to write snoozes: {}", e))
+}
+
+/// Hide a notification until `until`, replacing any earlier snooze for it
+pub fn snooze(notification_id: i32, until: DateTime<Utc>) -> Result<(), String> {
+    let mut file = read_snoozes()?;
+    file.snoozed.retain(|entry| entry.notification_id != notification_id);
+    file.snoozed.push(SnoozeEntry { notification_id, until });
+    write_snoozes(&file)
+}
+
+/// Ids of notifications snoozed at `now`. Expired snoozes are removed from
+/// disk so their notifications reappear.
+pub fn active_snoozes(now: DateTime<Utc>) -> HashSet<i32> {
+    let mut file = match read_snoozes() {
+        Ok(file) => file,
+        Err(e) => {
+            warn!("Ignoring notification snoozes: {}", e);
+            return HashSet::new();
+        }
+    };
+    let before = file.snoozed.len();
+    file.snoozed.retain(|entry| entry.until > now);
+    if file.snoozed.len() != before {
+        if let Err(e) = write_snoozes(&file) {
+            warn!("Failed to clean up expired snoozes: {}", e);
+        }
+    }
+    file.snoozed.iter().map(|entry| entry.notification_id).collect()
+}
+
+/// Drop snoozed notifications from a `/notifications` response body. Bodies
+/// that can't be parsed are passed through untouched.
+pub fn filter_snoozed(body: String) -> String {
+    let snoozed = active_snoozes(Utc::now());
+    if snoozed.is_empty() {
+        return body;
+    }
+    let Ok(mut response) = serde_json::from_str::<Value>(&body) else {
+        return body;
+    };
+    let Some(items) = response["data"].as_array_mut() else {
+        return body;
+    };
+    items.retain(|item| {
+        // Items are either notifications or `{ notification, targets }`
+        let id = item["id"].as_i64().or_else(|| item["notification"]["id"].as_i64());
+        !id.is_some_and(|id| snoozed.contains(&(id as i32)))
+    });
+    response.to_string()
+}
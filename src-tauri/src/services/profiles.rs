@@ -0,0 +1,155 @@
+// src-tauri/src/services/profiles.rs
+//
+// Saved server/account profiles. Only the server URL and session token are
+// stored, never passwords, and tokens are encrypted with a per-machine key
+// kept next to the profiles file.
+
+use crate::utils::write_atomic;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+const PROFILES_FILE: &str = "profiles.json";
+const PROFILES_KEY_FILE: &str = "profiles.key";
+const NONCE_LEN: usize = 12;
+
+/// A decrypted profile
+#[derive(Debug, Clone)]
+pub struct Profile {
+    pub name: String,
+    pub api_base_url: String,
+    pub token: String,
+}
+
+/// Profile as written to disk, with the token encrypted
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredProfile {
+    name: String,
+    api_base_url: String,
+    // base64 of nonce followed by ciphertext
+    encrypted_token: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ProfilesFile {
+    profiles: Vec<StoredProfile>,
+}
+
+fn profiles_dir() -> Result<PathBuf, String> {
+    let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
+    Ok(home_dir.join(".elevation-manager"))
+}
+
+// Load the encryption key, creating it on first use
+fn load_or_create_key() -> Result<Key<Aes256Gcm>, String> {
+    let dir = profiles_dir()?;
+    let key_path = dir.join(PROFILES_KEY_FILE);
+    if let Ok(bytes) = fs::read(&key_path) {
+        if bytes.len() == 32 {
+            return Ok(*Key::<Aes256Gcm>::from_slice(&bytes));
+        }
+        return Err("Profile key file is corrupt".to_string());
+    }
+
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create profile directory: {}", e))?;
+    let key = Aes256Gcm::generate_key(OsRng);
+    // Owner-only from the moment it exists, not restricted after the fact
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(&key_path)
+        .and_then(|mut file| file.write_all(key.as_slice()))
+        .map_err(|e| format!("Failed to write profile key: {}", e))?;
+    Ok(key)
+}
+
+fn encrypt_token(key: &Key<Aes256Gcm>, token: &str) -> Result<String, String> {
+    let cipher = Aes256Gcm::new(key);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, token.as_bytes())
+        .map_err(|e| format!("Failed to encrypt token: {}", e))?;
+    let mut payload = nonce.to_vec();
+    payload.extend_from_slice(&ciphertext);
+    Ok(STANDARD.encode(payload))
+}
+
+fn decrypt_token(key: &Key<Aes256Gcm>, encrypted: &str) -> Result<String, String> {
+    let payload = STANDARD
+        .decode(encrypted)
+        .map_err(|e| format!("Failed to decode token: {}", e))?;
+    if payload.len() <= NONCE_LEN {
+        return Err("Stored token is truncated".to_string());
+    }
+    let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+    let plaintext = Aes256Gcm::new(key)
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Failed to decrypt token".to_string())?;
+    String::from_utf8(plaintext).map_err(|e| format!("Stored token is not valid UTF-8: {}", e))
+}
+
+fn read_profiles_file() -> Result<ProfilesFile, String> {
+    let path = profiles_dir()?.join(PROFILES_FILE);
+    if !path.exists() {
+        return Ok(ProfilesFile::default());
+    }
+    let text = fs::read_to_string(&path).map_err(|e| format!("Failed to read profiles: {}", e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Failed to parse profiles: {}", e))
+}
+
+fn write_profiles_file(file: &ProfilesFile) -> Result<(), String> {
+    let dir = profiles_dir()?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create profile directory: {}", e))?;
+    let text = serde_json::to_string_pretty(file)
+        .map_err(|e| format!("Failed to serialize profiles: {}", e))?;
+    write_atomic(&dir.join(PROFILES_FILE), text).map_err(|e| format!("Failed to write profiles: {}", e))
+}
+
+/// Names and server URLs of all saved profiles, without decrypting tokens
+pub fn list_profiles() -> Result<Vec<(String, String)>, String> {
+    Ok(read_profiles_file()?
+        .profiles
+        .into_iter()
+        .map(|p| (p.name, p.api_base_url))
+        .collect())
+}
+
+/// Look up and decrypt a saved profile by name
+pub fn load_profile(name: &str) -> Result<Profile, String> {
+    let stored = read_profiles_file()?
+        .profiles
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| format!("Profile '{}' not found", name))?;
+    let key = load_or_create_key()?;
+    Ok(Profile {
+        token: decrypt_token(&key, &stored.encrypted_token)?,
+        name: stored.name,
+        api_base_url: stored.api_base_url,
+    })
+}
+
+/// Save a profile, replacing any existing profile with the same name
+pub fn save_profile(profile: &Profile) -> Result<(), String> {
+    let key = load_or_create_key()?;
+    let mut file = read_profiles_file()?;
+    let stored = StoredProfile {
+        name: profile.name.clone(),
+        api_base_url: profile.api_base_url.clone(),
+        encrypted_token: encrypt_token(&key, &profile.token)?,
+    };
+    match file.profiles.iter_mut().find(|p| p.name == profile.name) {
+        Some(existing) => *existing = stored,
+        None => file.profiles.push(stored),
+    }
+    write_profiles_file(&file)
+}
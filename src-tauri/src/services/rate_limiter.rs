@@ -0,0 +1,77 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Token bucket shared by every outgoing request. Callers over budget are
+/// delayed rather than rejected; each reserves the next free slot, so waiting
+/// callers are served roughly in arrival order.
+pub struct RateLimiter {
+    requests_per_second: f64,
+    burst: f64,
+    bucket: Mutex<Bucket>,
+}
+
+struct Bucket {
+    // Negative when callers have reserved slots that haven't refilled yet
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    /// Allow `requests_per_second` on average, with bursts of up to one
+    /// second's worth of requests.
+    pub fn new(requests_per_second: f64) -> Self {
+        let burst = requests_per_second.ceil().max(1.0);
+        Self {
+            requests_per_second,
+            burst,
+            bucket: Mutex::new(Bucket { tokens: burst, refilled_at: Instant::now() }),
+        }
+    }
+
+    pub fn requests_per_second(&self) -> f64 {
+        self.requests_per_second
+    }
+
+    /// Wait until a request may be sent
+    pub async fn acquire(&self) {
+        let wait = {
+            let mut bucket = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * self.requests_per_second).min(self.burst);
+            bucket.refilled_at = now;
+            bucket.tokens -= 1.0;
+            if bucket.tokens >= 0.0 {
+                Duration::ZERO
+            } else {
+                Duration::from_secs_f64(-bucket.tokens / self.requests_per_second)
+            }
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn requests_over_the_burst_are_spaced_at_the_configured_rate() {
+        let limiter = RateLimiter::new(20.0);
+        let started = Instant::now();
+        for _ in 0..20 {
+            limiter.acquire().await;
+        }
+        assert!(started.elapsed() < Duration::from_millis(100), "burst was delayed");
+
+        // The next ten each wait for a token, 50ms apart
+        for _ in 0..10 {
+            limiter.acquire().await;
+        }
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_millis(450), "too fast: {:?}", elapsed);
+        assert!(elapsed < Duration::from_millis(1000), "too slow: {:?}", elapsed);
+    }
+}
@@ -0,0 +1,117 @@
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+/// Latency summary for one endpoint, in milliseconds
+#[derive(Debug, Serialize)]
+pub struct EndpointMetrics {
+    pub endpoint: String,
+    pub count: usize,
+    pub min_ms: f64,
+    pub avg_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Rolling window of request durations per endpoint. Only the most recent
+/// `max_samples` durations are kept for each endpoint.
+pub struct RequestMetrics {
+    samples: HashMap<String, VecDeque<Duration>>,
+    max_samples: usize,
+}
+
+/// Group requests by method and path, with numeric IDs collapsed so that
+/// `/products/1` and `/products/2` share one entry.
+pub fn endpoint_key(method: &str, url: &str) -> String {
+    let path = reqwest::Url::parse(url)
+        .map(|u| u.path().to_string())
+        .unwrap_or_else(|_| url.split('?').next().unwrap_or_default().to_string());
+    let path = path
+        .split('/')
+        .map(|segment| {
+            if !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit()) {
+                ":id"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    format!("{} {}", method, path)
+}
+
+impl RequestMetrics {
+    pub fn new(max_samples: usize) -> Self {
+        Self {
+            samples: HashMap::new(),
+            max_samples: max_samples.max(1),
+        }
+    }
+
+    pub fn record(&mut self, endpoint: String, duration: Duration) {
+        let samples = self.samples.entry(endpoint).or_default();
+        if samples.len() == self.max_samples {
+            samples.pop_front();
+        }
+        samples.push_back(duration);
+    }
+
+    /// Summaries for every endpoint seen, slowest average first
+    pub fn summary(&self) -> Vec<EndpointMetrics> {
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        let mut metrics: Vec<EndpointMetrics> = self
+            .samples
+            .iter()
+            .filter(|(_, samples)| !samples.is_empty())
+            .map(|(endpoint, samples)| {
+                let mut sorted: Vec<Duration> = samples.iter().copied().collect();
+                sorted.sort();
+                let count = sorted.len();
+                // Nearest-rank percentile
+                let p95_index = ((count as f64 * 0.95).ceil() as usize).clamp(1, count) - 1;
+                EndpointMetrics {
+                    endpoint: endpoint.clone(),
+                    count,
+                    min_ms: ms(sorted[0]),
+                    avg_ms: ms(sorted.iter().sum::<Duration>()) / count as f64,
+                    p95_ms: ms(sorted[p95_index]),
+                    max_ms: ms(sorted[count - 1]),
+                }
+            })
+            .collect();
+        metrics.sort_by(|a, b| b.avg_ms.total_cmp(&a.avg_ms));
+        metrics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endpoint_key_collapses_ids_and_drops_the_query() {
+        assert_eq!(endpoint_key("GET", "http://host/api/products/12?page=2"), "GET /api/products/:id");
+        assert_eq!(endpoint_key("PUT", "/reviews/3/images/4"), "PUT /reviews/:id/images/:id");
+    }
+
+    #[test]
+    fn summary_counts_and_averages_per_endpoint_slowest_first() {
+        let mut metrics = RequestMetrics::new(3);
+        for (url, ms) in [("/products/1", 10), ("/products/2", 30), ("/users", 100), ("/products/3", 50), ("/products/4", 70)] {
+            metrics.record(endpoint_key("GET", url), Duration::from_millis(ms));
+        }
+
+        let summary = metrics.summary();
+
+        let endpoints: Vec<&str> = summary.iter().map(|m| m.endpoint.as_str()).collect();
+        assert_eq!(endpoints, ["GET /users", "GET /products/:id"]);
+        // Only the latest three product samples are kept
+        let products = &summary[1];
+        assert_eq!(products.count, 3);
+        assert_eq!(products.min_ms, 30.0);
+        assert_eq!(products.avg_ms, 50.0);
+        assert_eq!(products.p95_ms, 70.0);
+        assert_eq!(products.max_ms, 70.0);
+        assert_eq!(summary[0].count, 1);
+    }
+}
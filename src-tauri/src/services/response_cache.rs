@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+struct CacheEntry {
+    body: String,
+    expires_at: Instant,
+    last_used: u64,
+}
+
+/// In-memory cache of GET response bodies keyed by endpoint, with a per-entry
+/// TTL and least-recently-used eviction once `max_entries` is reached.
+pub struct ResponseCache {
+    entries: HashMap<String, CacheEntry>,
+    max_entries: usize,
+    // Monotonic counter used to order entries by last access
+    tick: u64,
+}
+
+impl ResponseCache {
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            max_entries: max_entries.max(1),
+            tick: 0,
+        }
+    }
+
+    /// Return a fresh cached body, dropping the entry if it has expired.
+    pub fn get(&mut self, endpoint: &str) -> Option<String> {
+        self.tick += 1;
+        let tick = self.tick;
+        match self.entries.get_mut(endpoint) {
+            Some(entry) if entry.expires_at > Instant::now() => {
+                entry.last_used = tick;
+                Some(entry.body.clone())
+            }
+            Some(_) => {
+                self.entries.remove(endpoint);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn insert(&mut self, endpoint: &str, body: String, ttl: Duration) {
+        self.tick += 1;
+        if !self.entries.contains_key(endpoint) && self.entries.len() >= self.max_entries {
+            self.evict();
+        }
+        self.entries.insert(
+            endpoint.to_string(),
+            CacheEntry {
+                body,
+                expires_at: Instant::now() + ttl,
+                last_used: self.tick,
+            },
+        );
+    }
+
+    /// Drop every entry whose endpoint starts with `prefix`.
+    pub fn invalidate(&mut self, prefix: &str) {
+        self.entries.retain(|endpoint, _| !endpoint.starts_with(prefix));
+    }
+
+    // Remove expired entries, or the least recently used one if none expired
+    fn evict(&mut self) {
+        let now = Instant::now();
+        let before = self.entries.len();
+        self.entries.retain(|_, entry| entry.expires_at > now);
+        if self.entries.len() < before {
+            return;
+        }
+        if let Some(oldest) = self
+            .entries
+            .iter()
+            .min_by_key(|(_, entry)| entry.last_used)
+            .map(|(endpoint, _)| endpoint.clone())
+        {
+            self.entries.remove(&oldest);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn fresh_entry_is_a_hit() {
+        let mut cache = ResponseCache::new(10);
+        cache.insert("/teams", "teams".to_string(), TTL);
+        assert_eq!(cache.get("/teams").as_deref(), Some("teams"));
+        assert_eq!(cache.get("/product_types"), None);
+    }
+
+    #[test]
+    fn expired_entry_is_a_miss() {
+        let mut cache = ResponseCache::new(10);
+        cache.insert("/teams", "teams".to_string(), Duration::ZERO);
+        assert_eq!(cache.get("/teams"), None);
+        assert!(cache.entries.is_empty());
+    }
+
+    #[test]
+    fn invalidation_drops_entries_by_prefix() {
+        let mut cache = ResponseCache::new(10);
+        cache.insert("/teams", "teams".to_string(), TTL);
+        cache.insert("/teams/1/users", "users".to_string(), TTL);
+        cache.insert("/product_types", "types".to_string(), TTL);
+
+        cache.invalidate("/teams");
+
+        assert_eq!(cache.get("/teams"), None);
+        assert_eq!(cache.get("/teams/1/users"), None);
+        assert_eq!(cache.get("/product_types").as_deref(), Some("types"));
+    }
+
+    #[test]
+    fn least_recently_used_entry_is_evicted_when_full() {
+        let mut cache = ResponseCache::new(2);
+        cache.insert("/a", "a".to_string(), TTL);
+        cache.insert("/b", "b".to_string(), TTL);
+        cache.get("/a");
+        cache.insert("/c", "c".to_string(), TTL);
+
+        assert_eq!(cache.get("/a").as_deref(), Some("a"));
+        assert_eq!(cache.get("/b"), None);
+        assert_eq!(cache.get("/c").as_deref(), Some("c"));
+    }
+}
@@ -0,0 +1,110 @@
+/// Deepest S2 cell level
+pub const MAX_LEVEL: u8 = 30;
+
+/// A validated S2 cell id. Tokens are the cell id in hex with trailing zeros
+/// removed, e.g. `89c25` for a level-8 cell over New York.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellId(u64);
+
+impl CellId {
+    pub fn from_token(token: &str) -> Result<Self, String> {
+        let token = token.trim();
+        if token.is_empty() || token.len() > 16 || !token.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Invalid S2 token '{}': expected 1 to 16 hex digits", token));
+        }
+        let id = u64::from_str_radix(&format!("{:0<16}", token), 16)
+            .map_err(|e| format!("Invalid S2 token '{}': {}", token, e))?;
+        // Six cube faces in the top three bits, and the lowest set bit marks
+        // the level so it must sit at an even position
+        if id == 0 || id >> 61 > 5 || id.trailing_zeros() % 2 != 0 {
+            return Err(format!("'{}' is not a valid S2 cell token", token));
+        }
+        Ok(CellId(id))
+    }
+
+    pub fn level(&self) -> u8 {
+        MAX_LEVEL - (self.0.trailing_zeros() / 2) as u8
+    }
+
+    fn lsb(&self) -> u64 {
+        self.0 & self.0.wrapping_neg()
+    }
+
+    /// The cell containing this one at a coarser (or equal) `level`
+    pub fn parent(&self, level: u8) -> Result<Self, String> {
+        if level > self.level() {
+            return Err(format!(
+                "Level {} is finer than the token's level {}",
+                level,
+                self.level()
+            ));
+        }
+        let lsb = 1u64 << (2 * (MAX_LEVEL - level));
+        Ok(CellId((self.0 & lsb.wrapping_neg()) | lsb))
+    }
+
+    pub fn to_token(self) -> String {
+        let hex = format!("{:016x}", self.0);
+        hex.trim_end_matches('0').to_string()
+    }
+
+    /// Whether `other` is this cell or one of its descendants
+    pub fn contains(&self, other: CellId) -> bool {
+        let span = self.lsb() - 1;
+        other.0 >= self.0 - span && other.0 <= self.0 + span
+    }
+
+    /// Leading hex digits shared by the tokens of every descendant. Coarser
+    /// than the cell itself, since levels don't fall on hex-digit boundaries.
+    pub fn token_prefix(&self) -> String {
+        let fixed_bits = 3 + 2 * self.level() as usize;
+        format!("{:016x}", self.0)[..fixed_bits / 4].to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(token: &str) -> CellId {
+        CellId::from_token(token).unwrap()
+    }
+
+    #[test]
+    fn tokens_are_validated_bit_by_bit() {
+        assert_eq!(cell("89c25").level(), 8);
+        assert_eq!(cell(" 89C25 ").to_token(), "89c25");
+        assert_eq!(cell("1").level(), 0);
+        // Lowest set bit at an odd position
+        assert!(CellId::from_token("89c26").is_err());
+        // Face 6 doesn't exist
+        assert!(CellId::from_token("c").is_err());
+        for token in ["", "0", "89g25", &"1".repeat(17)] {
+            assert!(CellId::from_token(token).is_err(), "{token:?}");
+        }
+    }
+
+    #[test]
+    fn parent_at_equal_and_coarser_levels() {
+        assert_eq!(cell("89c25").parent(8), Ok(cell("89c25")));
+        assert_eq!(cell("89c25").parent(7).unwrap().to_token(), "89c24");
+        assert_eq!(cell("89c25").parent(0).unwrap().to_token(), "9");
+        assert!(cell("89c25").parent(9).is_err());
+    }
+
+    #[test]
+    fn cells_contain_descendants_but_not_siblings() {
+        let parent = cell("89c24");
+        assert!(parent.contains(cell("89c25")));
+        assert!(parent.contains(parent));
+        assert!(cell("89c25").contains(cell("89c254")));
+        assert!(!cell("89c25").contains(cell("89c23")));
+        assert!(!cell("89c25").contains(parent));
+    }
+
+    #[test]
+    fn token_prefix_covers_only_whole_hex_digits() {
+        assert_eq!(cell("89c25").token_prefix(), "89c2");
+        assert_eq!(cell("1").token_prefix(), "");
+    }
+}
@@ -0,0 +1,75 @@
+// src-tauri/src/services/shown_notifications.rs
+//
+// Ids of notifications already shown as OS toasts, persisted so that a
+// notification that stays unread doesn't toast again on every poll or after
+// a restart.
+
+use crate::utils::write_atomic;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::path::PathBuf;
+
+const SHOWN_FILE: &str = "shown_notifications.json";
+
+/// Oldest ids are forgotten past this many, keeping the file small
+const MAX_SHOWN_IDS: usize = 1000;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ShownFile {
+    // Oldest first
+    shown: VecDeque<i32>,
+}
+
+fn shown_path() -> Result<PathBuf, String> {
+    let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
+    Ok(home_dir.join(".elevation-manager").join(SHOWN_FILE))
+}
+
+fn read_shown() -> Result<ShownFile, String> {
+    let path = shown_path()?;
+    if !path.exists() {
+        return Ok(ShownFile::default());
+    }
+    let text = fs::read_to_string(&path).map_err(|e| format!("Failed to read shown notifications: {}", e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Failed to parse shown notifications: {}", e))
+}
+
+fn write_shown(file: &ShownFile) -> Result<(), String> {
+    let path = shown_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create notification directory: {}", e))?;
+    }
+    let text = serde_json::to_string(file).map_err(|e| format!("Failed to serialize shown notifications: {}", e))?;
+    write_atomic(&path, text).map_err(|e| format!("Failed to write shown notifications: {}", e))
+}
+
+/// Record that a notification is being toasted. Returns false if it already
+/// was, in which case it shouldn't be shown again.
+pub fn mark_shown(notification_id: i32) -> bool {
+    let mut file = match read_shown() {
+        Ok(file) => file,
+        Err(e) => {
+            // Better a repeat toast than a missed one
+            warn!("Starting shown-notification list afresh: {}", e);
+            ShownFile::default()
+        }
+    };
+    if file.shown.contains(&notification_id) {
+        return false;
+    }
+    file.shown.push_back(notification_id);
+    while file.shown.len() > MAX_SHOWN_IDS {
+        file.shown.pop_front();
+    }
+    if let Err(e) = write_shown(&file) {
+        warn!("Failed to record shown notification {}: {}", notification_id, e);
+    }
+    true
+}
+
+/// Forget every shown notification so each can toast once more
+pub fn reset() -> Result<(), String> {
+    write_shown(&ShownFile::default())
+}
@@ -0,0 +1,286 @@
+// Helpers shared by unit tests that need a backend to talk to
+
+use crate::auth::login::AuthState;
+use crate::commands::products::set_product_log_root;
+use crate::commands::settings::set_reviews_dir;
+use crate::services::api_client::ApiClient;
+use crate::services::config::AppConfig;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// Requests seen by a mock server, as `"METHOD /path"`
+pub type RecordedRequests = Arc<Mutex<Vec<String>>>;
+
+/// A request received by a mock server
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl MockRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// What a mock server answers with
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    delay: Duration,
+}
+
+impl MockResponse {
+    pub fn json(status: u16, body: impl Into<String>) -> Self {
+        MockResponse {
+            status,
+            headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+            body: body.into().into_bytes(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    pub fn bytes(status: u16, body: &[u8]) -> Self {
+        MockResponse {
+            status,
+            headers: vec![("Content-Type".to_string(), "application/octet-stream".to_string())],
+            body: body.to_vec(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// Set a header, replacing any earlier value such as the default Content-Type
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.retain(|(existing, _)| !existing.eq_ignore_ascii_case(name));
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Wait this long before answering
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+/// A running mock server
+pub struct MockServer {
+    pub base_url: String,
+    /// Requests received so far, as `"METHOD /path"`
+    pub requests: RecordedRequests,
+    /// Requests received so far, with headers and body
+    pub received: Arc<Mutex<Vec<MockRequest>>>,
+    max_in_flight: Arc<AtomicUsize>,
+}
+
+impl MockServer {
+    /// Most requests that were being answered at the same time
+    pub fn max_in_flight(&self) -> usize {
+        self.max_in_flight.load(Ordering::SeqCst)
+    }
+}
+
+/// Start an HTTP server on a free local port that answers every request with
+/// `respond(method, path, body)` as a JSON `(status, body)`. Returns the base
+/// URL and the requests received so far.
+pub async fn mock_server<F>(respond: F) -> (String, RecordedRequests)
+where
+    F: Fn(&str, &str, &str) -> (u16, String) + Send + Sync + 'static,
+{
+    let server = mock_server_with(move |request| {
+        let (status, body) = respond(&request.method, &request.path, &request.body);
+        MockResponse::json(status, body)
+    })
+    .await;
+    (server.base_url, server.requests)
+}
+
+/// Start an HTTP server on a free local port that answers every request with
+/// `respond(request)`
+pub async fn mock_server_with<F>(respond: F) -> MockServer
+where
+    F: Fn(&MockRequest) -> MockResponse + Send + Sync + 'static,
+{
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let server = MockServer {
+        base_url: format!("http://{}", listener.local_addr().unwrap()),
+        requests: RecordedRequests::default(),
+        received: Arc::default(),
+        max_in_flight: Arc::default(),
+    };
+    let requests = server.requests.clone();
+    let received = server.received.clone();
+    let max_in_flight = server.max_in_flight.clone();
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let respond = Arc::new(respond);
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let requests = requests.clone();
+            let received = received.clone();
+            let max_in_flight = max_in_flight.clone();
+            let in_flight = in_flight.clone();
+            let respond = respond.clone();
+            tokio::spawn(async move {
+                let request = parse_request(&read_request(&mut stream).await);
+                let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(current, Ordering::SeqCst);
+                requests.lock().unwrap().push(format!("{} {}", request.method, request.path));
+                received.lock().unwrap().push(request.clone());
+
+                let response = respond(&request);
+                tokio::time::sleep(response.delay).await;
+                let mut head = format!("HTTP/1.1 {} Mock\r\n", response.status);
+                for (name, value) in &response.headers {
+                    head.push_str(&format!("{}: {}\r\n", name, value));
+                }
+                head.push_str(&format!("Content-Length: {}\r\nConnection: close\r\n\r\n", response.body.len()));
+                let _ = stream.write_all(head.as_bytes()).await;
+                let _ = stream.write_all(&response.body).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+            });
+        }
+    });
+    server
+}
+
+fn parse_request(request: &str) -> MockRequest {
+    let (head, body) = request.split_once("\r\n\r\n").unwrap_or((request, ""));
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or_default().to_string();
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+    MockRequest { method, path, headers, body: body.to_string() }
+}
+
+// Read the request head and as much body as Content-Length announces
+async fn read_request(stream: &mut tokio::net::TcpStream) -> String {
+    let mut buffer = Vec::new();
+    let mut chunk = [0; 4096];
+    loop {
+        let read = stream.read(&mut chunk).await.unwrap_or(0);
+        if read == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        let text = String::from_utf8_lossy(&buffer);
+        if let Some((head, body)) = text.split_once("\r\n\r\n") {
+            let content_length = head
+                .lines()
+                .find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    name.eq_ignore_ascii_case("content-length").then(|| value.trim().parse().ok())?
+                })
+                .unwrap_or(0);
+            if body.len() >= content_length {
+                break;
+            }
+        }
+    }
+    String::from_utf8_lossy(&buffer).into_owned()
+}
+
+/// A logged-in client pointed at `base_url`
+pub async fn test_client(base_url: &str) -> ApiClient {
+    let config = AppConfig::builder().api_base_url(base_url).build().unwrap();
+    let client = ApiClient::new(config, AuthState::default()).unwrap();
+    client.set_token("token".to_string()).await;
+    client
+}
+
+/// An unsigned JWT expiring at `exp` (seconds since the epoch)
+pub fn mock_token(exp: i64) -> String {
+    let header = URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#);
+    let payload = URL_SAFE_NO_PAD.encode(format!(r#"{{"sub":"1","exp":{}}}"#, exp));
+    format!("{}.{}.signature", header, payload)
+}
+
+/// A fresh, empty directory under the system temp dir
+pub fn temp_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("elevation_manager_test_{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// The reviews directory is process-wide, so tests that move it take turns
+static REVIEWS_DIR_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Points the reviews directory at `path` until dropped, then restores the
+/// default and removes `path`
+pub struct ReviewsDirOverride {
+    path: PathBuf,
+    _lock: tokio::sync::MutexGuard<'static, ()>,
+}
+
+impl ReviewsDirOverride {
+    /// Use a fresh temp directory for reviews
+    pub async fn temp() -> Self {
+        Self::at(REVIEWS_DIR_LOCK.lock().await, temp_dir())
+    }
+
+    /// Use `path` for reviews, from a test not running on a runtime
+    pub fn blocking_at(path: PathBuf) -> Self {
+        Self::at(REVIEWS_DIR_LOCK.blocking_lock(), path)
+    }
+
+    fn at(lock: tokio::sync::MutexGuard<'static, ()>, path: PathBuf) -> Self {
+        set_reviews_dir(Some(path.clone()));
+        ReviewsDirOverride { path, _lock: lock }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for ReviewsDirOverride {
+    fn drop(&mut self) {
+        set_reviews_dir(None);
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+// Likewise for the product log root
+static PRODUCT_LOG_ROOT_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Points the product log root at a fresh temp directory until dropped, then
+/// restores the default and removes the directory
+pub struct ProductLogRootOverride {
+    path: PathBuf,
+    _lock: tokio::sync::MutexGuard<'static, ()>,
+}
+
+impl ProductLogRootOverride {
+    pub async fn temp() -> Self {
+        let lock = PRODUCT_LOG_ROOT_LOCK.lock().await;
+        let path = temp_dir();
+        set_product_log_root(Some(path.clone()));
+        ProductLogRootOverride { path, _lock: lock }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for ProductLogRootOverride {
+    fn drop(&mut self) {
+        set_product_log_root(None);
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
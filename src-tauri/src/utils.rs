@@ -1,9 +1,12 @@
 use crate::auth::login::AuthState;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use log::{error, info};
+use std::io::Write;
+use std::path::Path;
 use tauri::State;
 
 pub async fn get_auth_header(state: &State<'_, AuthState>) -> Result<String, String> {
-    let token_guard = state.token.lock().await;
+    let token_guard = state.token.read().await;
     if let Some(token) = &*token_guard {
         info!("Successfully retrieved authentication token.");
         Ok(format!("Bearer {}", token))
@@ -13,11 +16,108 @@ pub async fn get_auth_header(state: &State<'_, AuthState>) -> Result<String, Str
     }
 }
 
-pub async fn get_auth_header_internal(auth_state: &AuthState) -> Result<String, String> {
-    let token_guard = auth_state.token.lock().await;
-    if let Some(token) = &*token_guard {
-        Ok(format!("Bearer {token}"))
-    } else {
-        Err("No valid authentication token found. Please log in".to_string())
+/// Write a file so readers see either the old or the new contents, never a
+/// partial write: the data goes to a temp file in the same directory, which
+/// is then renamed over `path`.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let result = (|| {
+        let mut file = std::fs::File::create(&tmp_path)?;
+        file.write_all(contents.as_ref())?;
+        file.sync_all()?;
+        std::fs::rename(&tmp_path, path)
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+/// Parse a timestamp from the server. RFC 3339 is preferred; date-times
+/// without an offset (`T` or space separated) are taken as UTC.
+pub fn parse_server_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc))
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f").map(|t| t.and_utc()))
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f").map(|t| t.and_utc()))
+        .ok()
+}
+
+/// Like `parse_server_timestamp`, but also accepts a plain date as midnight UTC
+pub fn parse_server_date(value: &str) -> Option<DateTime<Utc>> {
+    parse_server_timestamp(value)
+        .or_else(|| Some(NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0)?.and_utc()))
+}
+
+/// `name` if it is a bare file name, so joining it onto a directory stays
+/// inside that directory: no separators, `..` or absolute paths.
+pub fn safe_file_name(name: &str) -> Result<&str, String> {
+    match Path::new(name).file_name().and_then(|n| n.to_str()) {
+        Some(file_name) if file_name == name => Ok(name),
+        _ => Err(format!("Invalid file name: {}", name)),
+    }
+}
+
+pub fn bearer_header(token: Option<&str>) -> Result<String, String> {
+    match token {
+        Some(token) => Ok(format!("Bearer {token}")),
+        None => Err("No valid authentication token found. Please log in".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+
+    #[test]
+    fn only_bare_file_names_are_safe() {
+        assert_eq!(safe_file_name("tile.png"), Ok("tile.png"));
+        for name in ["", "..", "../tile.png", "images/tile.png", "/tmp/tile.png"] {
+            assert!(safe_file_name(name).is_err(), "{name:?}");
+        }
+    }
+
+    #[test]
+    fn failed_write_keeps_the_previous_file() {
+        let dir = temp_dir();
+        let path = dir.join("settings.json");
+        write_atomic(&path, r#"{"theme":"dark"}"#).unwrap();
+
+        // A directory in the temp file's place makes the write fail part way
+        std::fs::create_dir(dir.join("settings.json.tmp")).unwrap();
+        assert!(write_atomic(&path, r#"{"theme":"#).is_err());
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), r#"{"theme":"dark"}"#);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn leftover_partial_temp_file_is_replaced() {
+        let dir = temp_dir();
+        let path = dir.join("settings.json");
+        std::fs::write(dir.join("settings.json.tmp"), r#"{"the"#).unwrap();
+
+        write_atomic(&path, r#"{"theme":"light"}"#).unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), r#"{"theme":"light"}"#);
+        assert!(!dir.join("settings.json.tmp").exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn server_timestamps_with_and_without_offsets_are_utc() {
+        let expected = "2024-03-01T12:30:00+00:00";
+        for value in ["2024-03-01T13:30:00+01:00", "2024-03-01T12:30:00", "2024-03-01 12:30:00.000"] {
+            assert_eq!(parse_server_timestamp(value).map(|t| t.to_rfc3339()).as_deref(), Some(expected), "{value}");
+        }
+        assert_eq!(parse_server_timestamp("2024-03-01"), None);
+        assert_eq!(
+            parse_server_date("2024-03-01").map(|t| t.to_rfc3339()).as_deref(),
+            Some("2024-03-01T00:00:00+00:00")
+        );
     }
 }
@@ -19,6 +19,7 @@ import {
   CircularProgress,
   Paper,
   Stack,
+  TextField,
 } from '@mui/material';
 import { format, parseISO } from 'date-fns';
 
@@ -47,6 +48,7 @@ const PendingRequestsPanel: React.FC<PendingRequestsPanelProps> = ({ teamId }) =
   const [message, setMessage] = useState<{ text: string; severity: 'success' | 'error' } | null>(null);
   const [selectedRequest, setSelectedRequest] = useState<TeamRequest | null>(null);
   const [isDetailDialogOpen, setIsDetailDialogOpen] = useState(false);
+  const [rejectionReason, setRejectionReason] = useState('');
 
   useEffect(() => {
     fetchRequests();
@@ -75,6 +77,7 @@ const PendingRequestsPanel: React.FC<PendingRequestsPanelProps> = ({ teamId }) =
 
   const handleViewRequest = (request: TeamRequest) => {
     setSelectedRequest(request);
+    setRejectionReason('');
     setIsDetailDialogOpen(true);
   };
 
@@ -114,7 +117,8 @@ const PendingRequestsPanel: React.FC<PendingRequestsPanelProps> = ({ teamId }) =
     try {
       await invoke('reject_team_request', {
         request_id: selectedRequest.id,
-        team_id: teamId
+        team_id: teamId,
+        reason: rejectionReason.trim() || null
       });
       
       // Remove the rejected request from the list
@@ -268,6 +272,16 @@ const PendingRequestsPanel: React.FC<PendingRequestsPanelProps> = ({ teamId }) =
                 <Typography variant="subtitle2" color="text.secondary">Request Date</Typography>
                 <Typography variant="body1">{formatDate(selectedRequest.requested_at)}</Typography>
               </Box>
+
+              <TextField
+                label="Rejection reason (optional)"
+                helperText="Shown to the requester if you reject this request"
+                value={rejectionReason}
+                onChange={(e) => setRejectionReason(e.target.value)}
+                multiline
+                minRows={2}
+                fullWidth
+              />
             </Stack>
           )}
         </DialogContent>